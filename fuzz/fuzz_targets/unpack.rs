@@ -0,0 +1,43 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use multisig_wallet::instruction::WalletInstruction;
+use multisig_wallet::state::{
+    Allowance, AuditLog, Blacklist, Claim, Escrow, KeyRotation, ProgramRegistry, Proposal,
+    RecurringSwap, SessionKey, Stream, Template, TreasuryStats, Vault, Vesting, VoteComment,
+    VoteCount, WalletAuth, WalletConfig, WalletStats,
+};
+use solana_program::program_pack::Pack;
+
+// account data comes from the chain, decoding garbage must fail and never panic
+fn unpack<T: Pack>(data: &[u8]) {
+    let _ = T::unpack_from_slice(data);
+}
+
+fuzz_target!(|data: &[u8]| {
+    // anything unpack accepts has to survive a pack/unpack round trip
+    if let Ok(instruction) = WalletInstruction::unpack(data) {
+        assert!(WalletInstruction::unpack(&instruction.pack()).is_ok());
+    }
+
+    unpack::<WalletConfig>(data);
+    unpack::<WalletAuth>(data);
+    unpack::<Proposal>(data);
+    unpack::<VoteCount>(data);
+    unpack::<Allowance>(data);
+    unpack::<SessionKey>(data);
+    unpack::<Template>(data);
+    unpack::<Vault>(data);
+    unpack::<Escrow>(data);
+    unpack::<Stream>(data);
+    unpack::<Claim>(data);
+    unpack::<ProgramRegistry>(data);
+    unpack::<RecurringSwap>(data);
+    unpack::<KeyRotation>(data);
+    unpack::<TreasuryStats>(data);
+    unpack::<Blacklist>(data);
+    unpack::<AuditLog>(data);
+    unpack::<WalletStats>(data);
+    unpack::<Vesting>(data);
+    unpack::<VoteComment>(data);
+});
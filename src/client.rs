@@ -0,0 +1,2605 @@
+use crate::error::WalletError;
+use crate::instruction::WalletInstruction;
+use crate::processor::pda::{
+    ALLOWANCE, AUDIT, AUTHORITY, AUX_TOKEN, BLACKLIST, CLAIM, COMMENT, ESCROW, OWNER, PROPOSAL,
+    REGISTRY, ROTATION, SESSION, SPLIT_STAKE, STATS, STREAM, SWAP, TEMPLATE, TOKEN_MULTISIG,
+    TREASURY, UNWRAP, VAULT, VESTING, VOTES, WALLET_STATS,
+};
+use crate::processor::stake_pool::{
+    find_withdraw_authority_address, StakePool, STAKE_POOL_PROGRAM_ID,
+};
+use crate::processor::vote::{vote_digest, vote_permit_message};
+use crate::state::{
+    AuditLog, AuditRecord, PriceCondition, Proposal, ProposalAccount, ProposalType, VotePermit,
+    WalletAccount, WalletAuth, DISCRIMINATOR_LEN,
+};
+use solana_address_lookup_table_program::{
+    instruction::derive_lookup_table_address, state::AddressLookupTable,
+    ID as LOOKUP_TABLE_PROGRAM_ID,
+};
+use solana_client::{
+    client_error::ClientError as RpcError,
+    nonce_utils,
+    rpc_client::RpcClient,
+    rpc_config::RpcProgramAccountsConfig,
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{
+    address_lookup_table_account::AddressLookupTableAccount,
+    ed25519_program::ID as ED25519_PROGRAM_ID,
+    hash::{hash, Hash},
+    instruction::{AccountMeta, Instruction, InstructionError},
+    message::{v0, CompileError, Message, VersionedMessage},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::Signature,
+    stake::program::ID as STAKE_PROGRAM_ID,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar,
+    transaction::Transaction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, ID as ASSOCIATED_TOKEN_PROGRAM_ID,
+};
+use spl_token::{state::Account as TokenAccount, ID as TOKEN_PROGRAM_ID};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("rpc request failed: {0}")]
+    Rpc(Box<RpcError>),
+    #[error("account could not be decoded: {0}")]
+    Decode(#[from] ProgramError),
+    #[error("nonce account could not be read: {0}")]
+    Nonce(#[from] nonce_utils::Error),
+    #[error("lookup table could not be decoded: {0}")]
+    LookupTable(#[from] InstructionError),
+    #[error("message could not be compiled: {0}")]
+    Compile(#[from] CompileError),
+}
+
+// the rpc error is boxed since it is several times the size of the others
+impl From<RpcError> for ClientError {
+    fn from(error: RpcError) -> Self {
+        ClientError::Rpc(Box::new(error))
+    }
+}
+
+// pda derivation, seeds match the ones documented in instruction.rs
+
+pub fn find_wallet_auth_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    owner: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[OWNER.as_bytes(), wallet_config.as_ref(), owner.as_ref()],
+        program_id,
+    )
+}
+
+pub fn find_authority_address(program_id: &Pubkey, wallet_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUTHORITY.as_bytes(), wallet_config.as_ref()], program_id)
+}
+
+// vault 0 is the wallet's own authority
+pub fn find_vault_authority_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    vault: u8,
+) -> (Pubkey, u8) {
+    if vault == 0 {
+        return find_authority_address(program_id, wallet_config);
+    }
+    Pubkey::find_program_address(
+        &[AUTHORITY.as_bytes(), wallet_config.as_ref(), &[vault]],
+        program_id,
+    )
+}
+
+pub fn find_vault_address(program_id: &Pubkey, wallet_config: &Pubkey, index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT.as_bytes(), wallet_config.as_ref(), &[index]],
+        program_id,
+    )
+}
+
+pub fn find_proposal_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PROPOSAL.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_vote_count_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    proposal: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VOTES.as_bytes(), wallet_config.as_ref(), proposal.as_ref()],
+        program_id,
+    )
+}
+
+pub fn find_allowance_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ALLOWANCE.as_bytes(),
+            wallet_config.as_ref(),
+            owner.as_ref(),
+            mint.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_session_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    owner: &Pubkey,
+    session_key: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SESSION.as_bytes(),
+            wallet_config.as_ref(),
+            owner.as_ref(),
+            session_key.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_template_address(program_id: &Pubkey, wallet_config: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TEMPLATE.as_bytes(),
+            wallet_config.as_ref(),
+            &id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_escrow_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ESCROW.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+// the seed of a purpose named auxiliary token account, e.g. "grants" or "ops"
+pub fn aux_token_seed(purpose: &str) -> u64 {
+    u64::from_le_bytes(hash(purpose.as_bytes()).to_bytes()[..8].try_into().unwrap())
+}
+
+pub fn find_aux_token_account_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    vault: u8,
+    mint: &Pubkey,
+    seed: u64,
+) -> (Pubkey, u8) {
+    let authority = find_vault_authority_address(program_id, wallet_config, vault).0;
+    Pubkey::find_program_address(
+        &[
+            AUX_TOKEN.as_bytes(),
+            wallet_config.as_ref(),
+            authority.as_ref(),
+            mint.as_ref(),
+            &seed.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_vote_comment_address(
+    program_id: &Pubkey,
+    proposal: &Pubkey,
+    owner: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[COMMENT.as_bytes(), proposal.as_ref(), owner.as_ref()],
+        program_id,
+    )
+}
+
+pub fn find_vesting_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VESTING.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_stream_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            STREAM.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn find_claim_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CLAIM.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+// temporary wrapped SOL account of an unwrapping Transfer, sequence is the proposal's
+pub fn find_unwrap_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            UNWRAP.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+// account decoding, applies the same type and state checks the program does when loading
+
+pub fn decode_account<T: WalletAccount>(data: &[u8]) -> Result<T, ProgramError> {
+    if data.get(..DISCRIMINATOR_LEN) != Some(&T::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN])
+        || data.get(DISCRIMINATOR_LEN) != Some(&(T::ACCOUNT_TYPE as u8))
+    {
+        return Err(WalletError::IncorrectAccountType.into());
+    }
+    let details = T::deserialize(&mut &data[DISCRIMINATOR_LEN..])?;
+    if !details.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    Ok(details)
+}
+
+pub fn fetch_account<T: WalletAccount>(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<T, ClientError> {
+    let data = rpc.get_account_data(address)?;
+    Ok(decode_account::<T>(&data)?)
+}
+
+// account scanning, getProgramAccounts filtered on the account type and a key at a fixed offset of the
+// layout, the offsets count the anchor discriminator and the AccountType byte in front of every account
+
+const WALLET_AUTH_OWNER_OFFSET: usize = DISCRIMINATOR_LEN + 1;
+const PROPOSAL_WALLET_OFFSET: usize = DISCRIMINATOR_LEN + 1;
+const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+
+pub fn account_type_filter<T: WalletAccount>() -> RpcFilterType {
+    let mut bytes = T::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN].to_vec();
+    bytes.push(T::ACCOUNT_TYPE as u8);
+    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, bytes))
+}
+
+pub fn key_filter(offset: usize, key: &Pubkey) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(offset, key.to_bytes().to_vec()))
+}
+
+// accounts of type T matching filters, closed and undecodable accounts are skipped
+pub fn scan_accounts<T: WalletAccount>(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    mut filters: Vec<RpcFilterType>,
+) -> Result<Vec<(Pubkey, T)>, ClientError> {
+    filters.insert(0, account_type_filter::<T>());
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        ..RpcProgramAccountsConfig::default()
+    };
+    Ok(rpc
+        .get_program_accounts_with_config(program_id, config)?
+        .into_iter()
+        .filter_map(|(address, account)| {
+            decode_account::<T>(&account.data)
+                .ok()
+                .map(|details| (address, details))
+        })
+        .collect())
+}
+
+// wallet configs owner is an owner of
+pub fn list_wallets_for_owner(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Vec<Pubkey>, ClientError> {
+    let wallet_auths = scan_accounts::<WalletAuth>(
+        rpc,
+        program_id,
+        vec![key_filter(WALLET_AUTH_OWNER_OFFSET, owner)],
+    )?;
+    Ok(wallet_auths
+        .into_iter()
+        .map(|(_, wallet_auth)| wallet_auth.wallet)
+        .collect())
+}
+
+// proposals are closed once executed or expired, every one still on chain is open
+pub fn list_open_proposals(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+) -> Result<Vec<(Pubkey, Proposal)>, ClientError> {
+    let mut proposals = scan_accounts::<Proposal>(
+        rpc,
+        program_id,
+        vec![key_filter(PROPOSAL_WALLET_OFFSET, wallet_config)],
+    )?;
+    proposals.sort_by_key(|(_, proposal)| proposal.sequence);
+    Ok(proposals)
+}
+
+// token accounts held by the wallet authority
+pub fn list_wallet_token_accounts(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+) -> Result<Vec<(Pubkey, TokenAccount)>, ClientError> {
+    let wallet_authority = find_authority_address(program_id, wallet_config).0;
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(TokenAccount::LEN as u64),
+            key_filter(TOKEN_ACCOUNT_OWNER_OFFSET, &wallet_authority),
+        ]),
+        ..RpcProgramAccountsConfig::default()
+    };
+    Ok(rpc
+        .get_program_accounts_with_config(&TOKEN_PROGRAM_ID, config)?
+        .into_iter()
+        .filter_map(|(address, account)| {
+            TokenAccount::unpack(&account.data)
+                .ok()
+                .map(|token_account| (address, token_account))
+        })
+        .collect())
+}
+
+// instruction builders, account order follows the lists in instruction.rs
+
+fn wallet_instruction(
+    program_id: &Pubkey,
+    instruction: WalletInstruction,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction.pack(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_wallet(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    wallet_config: &Pubkey,
+    m: u8,
+    n: u8,
+    owners: Vec<Pubkey>,
+    proposal_lifetime: i64,
+    min_proposal_lifetime: i64,
+    max_proposal_lifetime: i64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*wallet_config, true),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, user).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    for owner in owners.iter() {
+        accounts.push(AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ));
+    }
+    wallet_instruction(
+        program_id,
+        WalletInstruction::CreateWallet {
+            m,
+            n,
+            owners,
+            proposal_lifetime,
+            min_proposal_lifetime,
+            max_proposal_lifetime,
+        },
+        accounts,
+    )
+}
+
+// other_owners are the source wallet's owners besides the user
+pub fn clone_wallet(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    source_wallet_config: &Pubkey,
+    wallet_config: &Pubkey,
+    other_owners: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(*source_wallet_config, false),
+        AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, source_wallet_config, user).0,
+            false,
+        ),
+        AccountMeta::new(*wallet_config, true),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, user).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    for owner in other_owners.iter() {
+        accounts.push(AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, source_wallet_config, owner).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ));
+    }
+    wallet_instruction(program_id, WalletInstruction::CloneWallet, accounts)
+}
+
+pub fn create_token_account(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    wallet_config: &Pubkey,
+    mint: &Pubkey,
+    vault: u8,
+) -> Instruction {
+    let authority = find_vault_authority_address(program_id, wallet_config, vault).0;
+    let mut accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*wallet_config, false),
+        AccountMeta::new_readonly(authority, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(get_associated_token_address(&authority, mint), false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+    ];
+    if vault != 0 {
+        accounts.push(AccountMeta::new_readonly(
+            find_vault_address(program_id, wallet_config, vault).0,
+            false,
+        ));
+    }
+    wallet_instruction(
+        program_id,
+        WalletInstruction::CreateTokenAccount { vault },
+        accounts,
+    )
+}
+
+pub fn create_aux_token_account(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    wallet_config: &Pubkey,
+    mint: &Pubkey,
+    vault: u8,
+    seed: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*wallet_config, false),
+        AccountMeta::new_readonly(
+            find_vault_authority_address(program_id, wallet_config, vault).0,
+            false,
+        ),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(
+            find_aux_token_account_address(program_id, wallet_config, vault, mint, seed).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    ];
+    if vault != 0 {
+        accounts.push(AccountMeta::new_readonly(
+            find_vault_address(program_id, wallet_config, vault).0,
+            false,
+        ));
+    }
+    wallet_instruction(
+        program_id,
+        WalletInstruction::CreateAuxTokenAccount { vault, seed },
+        accounts,
+    )
+}
+
+// token_accounts are (send, receive, mint) triples to sweep, only needed when the last owner leaves
+pub fn give_up_ownership(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    wallet_config: &Pubkey,
+    refund_destination: &Pubkey,
+    token_accounts: &[(Pubkey, Pubkey, Pubkey)],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, user).0,
+            false,
+        ),
+        AccountMeta::new(*refund_destination, false),
+        AccountMeta::new(find_audit_log_address(program_id, wallet_config).0, false),
+    ];
+    if !token_accounts.is_empty() {
+        accounts.push(AccountMeta::new_readonly(
+            find_authority_address(program_id, wallet_config).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false));
+        for (send_account, receive_account, mint) in token_accounts.iter() {
+            accounts.push(AccountMeta::new(*send_account, false));
+            accounts.push(AccountMeta::new(*receive_account, false));
+            accounts.push(AccountMeta::new_readonly(*mint, false));
+        }
+    }
+    wallet_instruction(program_id, WalletInstruction::GiveupOwnership, accounts)
+}
+
+// user signs either as the owner itself or as a session key registered by the owner, vault is the one the
+// proposal transfers out of, 0 if none
+fn proposal_accounts(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    vault: u8,
+) -> Vec<AccountMeta> {
+    let proposal = find_proposal_address(program_id, wallet_config, sequence).0;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new(
+            find_vote_count_address(program_id, wallet_config, &proposal).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(
+            find_wallet_stats_address(program_id, wallet_config).0,
+            false,
+        ),
+    ];
+    if vault != 0 {
+        accounts.push(AccountMeta::new_readonly(
+            find_vault_address(program_id, wallet_config, vault).0,
+            false,
+        ));
+    }
+    if user != owner {
+        accounts.push(AccountMeta::new_readonly(
+            find_session_address(program_id, wallet_config, owner, user).0,
+            false,
+        ));
+    }
+    accounts
+}
+
+// sequence is the wallet's current proposal_count
+pub fn create_proposal(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    proposal: ProposalType,
+) -> Instruction {
+    let vault = proposal.vault();
+    wallet_instruction(
+        program_id,
+        WalletInstruction::CreateProposal {
+            proposal,
+            urgent: false,
+            treasury_funded: false,
+            draft: false,
+        },
+        proposal_accounts(
+            program_id,
+            user,
+            owner,
+            payer,
+            wallet_config,
+            sequence,
+            vault,
+        ),
+    )
+}
+
+// held to the wallet's emergency threshold and lifetime, sequence is the wallet's current proposal_count
+pub fn create_urgent_proposal(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    proposal: ProposalType,
+) -> Instruction {
+    let vault = proposal.vault();
+    wallet_instruction(
+        program_id,
+        WalletInstruction::CreateProposal {
+            proposal,
+            urgent: true,
+            treasury_funded: false,
+            draft: false,
+        },
+        proposal_accounts(
+            program_id,
+            user,
+            owner,
+            payer,
+            wallet_config,
+            sequence,
+            vault,
+        ),
+    )
+}
+
+// the wallet authority pays the rent, deposit and keeper tip, the rent goes back to it when the proposal is closed
+pub fn create_treasury_funded_proposal(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    proposal: ProposalType,
+    urgent: bool,
+) -> Instruction {
+    let wallet_authority = find_authority_address(program_id, wallet_config).0;
+    let mut accounts = proposal_accounts(
+        program_id,
+        user,
+        owner,
+        &wallet_authority,
+        wallet_config,
+        sequence,
+        proposal.vault(),
+    );
+    accounts[1] = AccountMeta::new(wallet_authority, false);
+    wallet_instruction(
+        program_id,
+        WalletInstruction::CreateProposal {
+            proposal,
+            urgent,
+            treasury_funded: true,
+            draft: false,
+        },
+        accounts,
+    )
+}
+
+// editable through edit_draft and not votable until activate_proposal, sequence is the wallet's current
+// proposal_count
+#[allow(clippy::too_many_arguments)]
+pub fn create_draft_proposal(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    proposal: ProposalType,
+    urgent: bool,
+) -> Instruction {
+    let vault = proposal.vault();
+    wallet_instruction(
+        program_id,
+        WalletInstruction::CreateProposal {
+            proposal,
+            urgent,
+            treasury_funded: false,
+            draft: true,
+        },
+        proposal_accounts(
+            program_id,
+            user,
+            owner,
+            payer,
+            wallet_config,
+            sequence,
+            vault,
+        ),
+    )
+}
+
+pub fn edit_draft(
+    program_id: &Pubkey,
+    proposer: &Pubkey,
+    payer: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    proposal: ProposalType,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*proposer, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(
+            find_proposal_address(program_id, wallet_config, sequence).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    wallet_instruction(
+        program_id,
+        WalletInstruction::EditDraft { proposal },
+        accounts,
+    )
+}
+
+pub fn amend_proposal(
+    program_id: &Pubkey,
+    proposer: &Pubkey,
+    payer: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    proposal: ProposalType,
+) -> Instruction {
+    let proposal_key = find_proposal_address(program_id, wallet_config, sequence).0;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*proposer, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(proposal_key, false),
+        AccountMeta::new(
+            find_vote_count_address(program_id, wallet_config, &proposal_key).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    if proposal.vault() != 0 {
+        accounts.push(AccountMeta::new_readonly(
+            find_vault_address(program_id, wallet_config, proposal.vault()).0,
+            false,
+        ));
+    }
+    wallet_instruction(
+        program_id,
+        WalletInstruction::AmendProposal { proposal },
+        accounts,
+    )
+}
+
+pub fn set_content_hash(
+    program_id: &Pubkey,
+    proposer: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    content_hash: [u8; 32],
+) -> Instruction {
+    let proposal_key = find_proposal_address(program_id, wallet_config, sequence).0;
+    let accounts = vec![
+        AccountMeta::new_readonly(*proposer, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(proposal_key, false),
+        AccountMeta::new_readonly(
+            find_vote_count_address(program_id, wallet_config, &proposal_key).0,
+            false,
+        ),
+    ];
+    wallet_instruction(
+        program_id,
+        WalletInstruction::SetContentHash { content_hash },
+        accounts,
+    )
+}
+
+pub fn set_price_condition(
+    program_id: &Pubkey,
+    proposer: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    condition: PriceCondition,
+) -> Instruction {
+    let proposal_key = find_proposal_address(program_id, wallet_config, sequence).0;
+    let accounts = vec![
+        AccountMeta::new_readonly(*proposer, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(proposal_key, false),
+        AccountMeta::new_readonly(
+            find_vote_count_address(program_id, wallet_config, &proposal_key).0,
+            false,
+        ),
+    ];
+    wallet_instruction(
+        program_id,
+        WalletInstruction::SetPriceCondition { condition },
+        accounts,
+    )
+}
+
+pub fn extend_proposal(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> Instruction {
+    let proposal = find_proposal_address(program_id, wallet_config, sequence).0;
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new_readonly(proposal, false),
+        AccountMeta::new(
+            find_vote_count_address(program_id, wallet_config, &proposal).0,
+            false,
+        ),
+    ];
+    wallet_instruction(program_id, WalletInstruction::ExtendProposal, accounts)
+}
+
+// only accepted while the proposal's challenge period is running
+pub fn object_proposal(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> Instruction {
+    let proposal = find_proposal_address(program_id, wallet_config, sequence).0;
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new_readonly(
+            find_vote_count_address(program_id, wallet_config, &proposal).0,
+            false,
+        ),
+    ];
+    wallet_instruction(program_id, WalletInstruction::ObjectProposal, accounts)
+}
+
+pub fn comment_vote(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    comment: Vec<u8>,
+) -> Instruction {
+    let proposal = find_proposal_address(program_id, wallet_config, sequence).0;
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new_readonly(proposal, false),
+        AccountMeta::new_readonly(
+            find_vote_count_address(program_id, wallet_config, &proposal).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_vote_comment_address(program_id, &proposal, owner).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    wallet_instruction(
+        program_id,
+        WalletInstruction::CommentVote { comment },
+        accounts,
+    )
+}
+
+// vault is the one the draft transfers out of, 0 if none
+pub fn activate_proposal(
+    program_id: &Pubkey,
+    proposer: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    vault: u8,
+) -> Instruction {
+    let proposal = find_proposal_address(program_id, wallet_config, sequence).0;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*proposer, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, proposer).0,
+            false,
+        ),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new(
+            find_vote_count_address(program_id, wallet_config, &proposal).0,
+            false,
+        ),
+    ];
+    if vault != 0 {
+        accounts.push(AccountMeta::new_readonly(
+            find_vault_address(program_id, wallet_config, vault).0,
+            false,
+        ));
+    }
+    wallet_instruction(program_id, WalletInstruction::ActivateProposal, accounts)
+}
+
+// vault is the one the template's proposal transfers out of, 0 if none
+#[allow(clippy::too_many_arguments)]
+pub fn create_proposal_from_template(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    wallet_config: &Pubkey,
+    template_id: u64,
+    sequence: u64,
+    vault: u8,
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(
+        find_template_address(program_id, wallet_config, template_id).0,
+        false,
+    )];
+    accounts.extend(proposal_accounts(
+        program_id,
+        user,
+        owner,
+        payer,
+        wallet_config,
+        sequence,
+        vault,
+    ));
+    wallet_instruction(
+        program_id,
+        WalletInstruction::CreateProposalFromTemplate,
+        accounts,
+    )
+}
+
+// appended to a vote of owner, or after each wallet auth of a signature or permit vote, if the wallet gates votes
+pub fn vote_gate_account(owner: &Pubkey, gate_mint: &Pubkey) -> AccountMeta {
+    AccountMeta::new_readonly(get_associated_token_address(owner, gate_mint), false)
+}
+
+// the first of the execution accounts of CloseProposal when the wallet has a callback program set
+pub fn execution_callback_account(callback_program: &Pubkey) -> AccountMeta {
+    AccountMeta::new_readonly(*callback_program, false)
+}
+
+// follows the callback program, if any, ahead of the execution accounts of CloseProposal when the proposal has a
+// price condition
+pub fn price_feed_account(feed: &Pubkey) -> AccountMeta {
+    AccountMeta::new_readonly(*feed, false)
+}
+
+// takes the place of the session key account when the holder of an NFT-bound seat signs, with mint as the owner
+pub fn seat_holder_account(holder: &Pubkey, mint: &Pubkey) -> AccountMeta {
+    AccountMeta::new_readonly(get_associated_token_address(holder, mint), false)
+}
+
+pub fn vote(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    proposal: &Pubkey,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new_readonly(*proposal, false),
+        AccountMeta::new(
+            find_vote_count_address(program_id, wallet_config, proposal).0,
+            false,
+        ),
+    ];
+    if user != owner {
+        accounts.push(AccountMeta::new_readonly(
+            find_session_address(program_id, wallet_config, owner, user).0,
+            false,
+        ));
+    }
+    wallet_instruction(program_id, WalletInstruction::Vote, accounts)
+}
+
+// owners sign this off-chain instead of sending a vote transaction each, content_hash is all zeroes and
+// price_condition the default if the proposal has none, revision is the proposal's current one
+pub fn proposal_vote_digest(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    proposal: &ProposalType,
+    content_hash: &[u8; 32],
+    price_condition: &PriceCondition,
+    revision: u16,
+) -> [u8; 32] {
+    let proposal_key = find_proposal_address(program_id, wallet_config, sequence).0;
+    vote_digest(
+        program_id,
+        wallet_config,
+        &proposal_key,
+        proposal,
+        content_hash,
+        price_condition,
+        revision,
+    )
+    .unwrap()
+}
+
+// a single ed25519 program instruction verifying every signature over message, keys and message are kept
+// inside the instruction itself as VoteWithSignatures requires
+pub fn ed25519_signatures_instruction(
+    signatures: &[(Pubkey, Signature)],
+    message: &[u8],
+) -> Instruction {
+    let header_len = 2 + signatures.len() * 14;
+    let message_offset = header_len + signatures.len() * 96;
+    let mut data = vec![signatures.len() as u8, 0];
+    for i in 0..signatures.len() {
+        let public_key_offset = header_len + i * 96;
+        for value in [
+            public_key_offset + 32,
+            u16::MAX as usize,
+            public_key_offset,
+            u16::MAX as usize,
+            message_offset,
+            message.len(),
+            u16::MAX as usize,
+        ] {
+            data.extend_from_slice(&(value as u16).to_le_bytes());
+        }
+    }
+    for (public_key, signature) in signatures.iter() {
+        data.extend_from_slice(public_key.as_ref());
+        data.extend_from_slice(signature.as_ref());
+    }
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ED25519_PROGRAM_ID,
+        accounts: Vec::new(),
+        data,
+    }
+}
+
+// ed25519_signatures_instruction with the owners' signatures has to come before this in the transaction
+pub fn vote_with_signatures(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    owners: &[Pubkey],
+) -> Instruction {
+    let proposal = find_proposal_address(program_id, wallet_config, sequence).0;
+    let mut accounts = vec![
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new_readonly(proposal, false),
+        AccountMeta::new(
+            find_vote_count_address(program_id, wallet_config, &proposal).0,
+            false,
+        ),
+        AccountMeta::new_readonly(sysvar::instructions::ID, false),
+    ];
+    for owner in owners.iter() {
+        accounts.push(AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ));
+    }
+    wallet_instruction(program_id, WalletInstruction::VoteWithSignatures, accounts)
+}
+
+// the message an owner signs to approve a proposal through submit_vote_permit, valid until expires_at, revision
+// is the proposal's current one
+pub fn proposal_vote_permit(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    revision: u16,
+    expires_at: i64,
+) -> Vec<u8> {
+    let permit = VotePermit {
+        wallet: *wallet_config,
+        proposal: find_proposal_address(program_id, wallet_config, sequence).0,
+        approve: true,
+        expires_at,
+    };
+    vote_permit_message(program_id, &permit, revision).unwrap()
+}
+
+// permits are (owner, expires_at) pairs, each permit's signature has to be verified by an ed25519 instruction
+// before this one, permits sharing an expiry can share one ed25519_signatures_instruction
+pub fn submit_vote_permit(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    proposer: &Pubkey,
+    permits: &[(Pubkey, i64)],
+) -> Instruction {
+    let proposal = find_proposal_address(program_id, wallet_config, sequence).0;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*proposer, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new_readonly(proposal, false),
+        AccountMeta::new(
+            find_vote_count_address(program_id, wallet_config, &proposal).0,
+            false,
+        ),
+        AccountMeta::new_readonly(sysvar::instructions::ID, false),
+    ];
+    for (owner, _) in permits.iter() {
+        accounts.push(AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ));
+    }
+    let expirations = permits.iter().map(|(_, expires_at)| *expires_at).collect();
+    wallet_instruction(
+        program_id,
+        WalletInstruction::SubmitVotePermit { expirations },
+        accounts,
+    )
+}
+
+pub fn vote_many(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    proposals: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+    ];
+    for proposal in proposals.iter() {
+        accounts.push(AccountMeta::new_readonly(*proposal, false));
+        accounts.push(AccountMeta::new(
+            find_vote_count_address(program_id, wallet_config, proposal).0,
+            false,
+        ));
+    }
+    if user != owner {
+        accounts.push(AccountMeta::new_readonly(
+            find_session_address(program_id, wallet_config, owner, user).0,
+            false,
+        ));
+    }
+    wallet_instruction(
+        program_id,
+        WalletInstruction::VoteMany {
+            proposals: proposals.len() as u8,
+        },
+        accounts,
+    )
+}
+
+// execution_accounts depend on the proposal type, see the CloseProposal account list
+pub fn close_proposal(
+    program_id: &Pubkey,
+    closer: &Pubkey,
+    refund_destination: &Pubkey,
+    wallet_config: &Pubkey,
+    proposal: &Pubkey,
+    execution_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*closer, true),
+        AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, wallet_config, closer).0,
+            false,
+        ),
+        AccountMeta::new(*refund_destination, false),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(*proposal, false),
+        AccountMeta::new(
+            find_vote_count_address(program_id, wallet_config, proposal).0,
+            false,
+        ),
+        AccountMeta::new(find_audit_log_address(program_id, wallet_config).0, false),
+        AccountMeta::new(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new(
+            find_wallet_stats_address(program_id, wallet_config).0,
+            false,
+        ),
+    ];
+    accounts.extend(execution_accounts);
+    wallet_instruction(program_id, WalletInstruction::CloseProposal, accounts)
+}
+
+pub fn deposit_sol(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    wallet_config: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*depositor, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    wallet_instruction(
+        program_id,
+        WalletInstruction::DepositSol { amount },
+        accounts,
+    )
+}
+
+// spendable SOL of the wallet, separate from the authority that pays rent
+pub fn find_treasury_address(program_id: &Pubkey, wallet_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY.as_bytes(), wallet_config.as_ref()], program_id)
+}
+
+pub fn deposit_treasury(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    wallet_config: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*depositor, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(find_treasury_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    wallet_instruction(
+        program_id,
+        WalletInstruction::DepositTreasury { amount },
+        accounts,
+    )
+}
+
+// execution accounts to pass to close_proposal for TreasuryWithdraw
+pub fn treasury_withdraw_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    receive_account: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(find_treasury_address(program_id, wallet_config).0, false),
+        AccountMeta::new(*receive_account, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(
+            find_treasury_stats_address(program_id, wallet_config, &Pubkey::default()).0,
+            false,
+        ),
+        AccountMeta::new_readonly(find_blacklist_address(program_id, wallet_config).0, false),
+    ]
+}
+
+pub fn claim_recovery(
+    program_id: &Pubkey,
+    beneficiary: &Pubkey,
+    wallet_config: &Pubkey,
+    owners: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*beneficiary, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, beneficiary).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(find_audit_log_address(program_id, wallet_config).0, false),
+    ];
+    for owner in owners.iter() {
+        accounts.push(AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ));
+    }
+    wallet_instruction(program_id, WalletInstruction::ClaimRecovery, accounts)
+}
+
+pub fn spend_allowance(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    mint: &Pubkey,
+    send_account: &Pubkey,
+    receive_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_allowance_address(program_id, wallet_config, owner, mint).0,
+            false,
+        ),
+        AccountMeta::new(*send_account, false),
+        AccountMeta::new(*receive_account, false),
+        AccountMeta::new_readonly(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(*mint, false),
+    ];
+    wallet_instruction(
+        program_id,
+        WalletInstruction::SpendAllowance { amount },
+        accounts,
+    )
+}
+
+pub fn register_session_key(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    session_key: &Pubkey,
+    expires_at: i64,
+    scope: u8,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new_readonly(*wallet_config, false),
+        AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_session_address(program_id, wallet_config, owner, session_key).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    wallet_instruction(
+        program_id,
+        WalletInstruction::RegisterSessionKey {
+            session_key: *session_key,
+            expires_at,
+            scope,
+        },
+        accounts,
+    )
+}
+
+pub fn revoke_session_key(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    session_key: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new_readonly(*wallet_config, false),
+        AccountMeta::new(
+            find_session_address(program_id, wallet_config, owner, session_key).0,
+            false,
+        ),
+    ];
+    wallet_instruction(program_id, WalletInstruction::RevokeSessionKey, accounts)
+}
+
+pub fn release_escrow(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    mint: &Pubkey,
+    receive_account: &Pubkey,
+) -> Instruction {
+    let escrow = find_escrow_address(program_id, wallet_config, sequence).0;
+    let accounts = vec![
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new(escrow, false),
+        AccountMeta::new(get_associated_token_address(&escrow, mint), false),
+        AccountMeta::new(*receive_account, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(*mint, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::ReleaseEscrow, accounts)
+}
+
+// sequence is the one of the CreateVesting proposal, anyone can release to the receive account
+pub fn release_vesting(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    mint: &Pubkey,
+    receive_account: &Pubkey,
+) -> Instruction {
+    let vesting = find_vesting_address(program_id, wallet_config, sequence).0;
+    let accounts = vec![
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new(vesting, false),
+        AccountMeta::new(get_associated_token_address(&vesting, mint), false),
+        AccountMeta::new(*receive_account, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(*mint, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::ReleaseVesting, accounts)
+}
+
+pub fn withdraw_stream(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    mint: &Pubkey,
+    send_account: &Pubkey,
+    receive_account: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new(
+            find_stream_address(program_id, wallet_config, sequence).0,
+            false,
+        ),
+        AccountMeta::new(*send_account, false),
+        AccountMeta::new(*receive_account, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(*mint, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::WithdrawStream, accounts)
+}
+
+pub fn withdraw_claim(
+    program_id: &Pubkey,
+    recipient: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    mint: &Pubkey,
+    receive_account: &Pubkey,
+) -> Instruction {
+    let claim = find_claim_address(program_id, wallet_config, sequence).0;
+    let accounts = vec![
+        AccountMeta::new_readonly(*recipient, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new(claim, false),
+        AccountMeta::new(get_associated_token_address(&claim, mint), false),
+        AccountMeta::new(*receive_account, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(*mint, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::WithdrawClaim, accounts)
+}
+
+pub fn reclaim_expired_claim(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    mint: &Pubkey,
+    receive_account: &Pubkey,
+) -> Instruction {
+    let claim = find_claim_address(program_id, wallet_config, sequence).0;
+    let accounts = vec![
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new(claim, false),
+        AccountMeta::new(get_associated_token_address(&claim, mint), false),
+        AccountMeta::new(*receive_account, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(*mint, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::ReclaimExpiredClaim, accounts)
+}
+
+// sources are token accounts of the wallet authority with their mints, each balance moves into the wallet
+// authority's associated token account for the mint, which has to exist already, and the source is closed
+pub fn sweep_token_accounts(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    sources: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let wallet_authority = find_authority_address(program_id, wallet_config).0;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new_readonly(*wallet_config, false),
+        AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new(wallet_authority, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    ];
+    for (source, mint) in sources.iter() {
+        accounts.push(AccountMeta::new(*source, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address(&wallet_authority, mint),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(*mint, false));
+    }
+    wallet_instruction(program_id, WalletInstruction::SweepTokenAccounts, accounts)
+}
+
+// mints of the empty associated token accounts of the vault authority to close, their rent goes to the wallet
+// authority
+pub fn close_empty_token_accounts(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    vault: u8,
+    mints: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new_readonly(*wallet_config, false),
+        AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    ];
+    let vault_authority = find_vault_authority_address(program_id, wallet_config, vault).0;
+    if vault != 0 {
+        accounts.push(AccountMeta::new_readonly(
+            find_vault_address(program_id, wallet_config, vault).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(vault_authority, false));
+    }
+    for mint in mints.iter() {
+        accounts.push(AccountMeta::new(
+            get_associated_token_address(&vault_authority, mint),
+            false,
+        ));
+    }
+    wallet_instruction(
+        program_id,
+        WalletInstruction::CloseEmptyTokenAccounts { vault },
+        accounts,
+    )
+}
+
+pub fn get_sol_balance(program_id: &Pubkey, wallet_config: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*wallet_config, false),
+        AccountMeta::new_readonly(find_authority_address(program_id, wallet_config).0, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::GetSolBalance, accounts)
+}
+
+// applies a threshold, lifetime or timelock change once the wallet's timelock has passed, anyone can send it
+pub fn apply_pending_change(program_id: &Pubkey, wallet_config: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(find_audit_log_address(program_id, wallet_config).0, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::ApplyPendingChange, accounts)
+}
+
+pub fn find_key_rotation_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    owner: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ROTATION.as_bytes(), wallet_config.as_ref(), owner.as_ref()],
+        program_id,
+    )
+}
+
+// signed by the owner's current key, takes effect through complete_key_rotation after the wallet's rotation delay
+pub fn request_key_rotation(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    new_owner: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_key_rotation_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    wallet_instruction(
+        program_id,
+        WalletInstruction::RequestKeyRotation {
+            new_owner: *new_owner,
+        },
+        accounts,
+    )
+}
+
+// refund_destination is the payer of the rotation request
+pub fn emergency_freeze(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new(find_audit_log_address(program_id, wallet_config).0, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::EmergencyFreeze, accounts)
+}
+
+pub fn veto_key_rotation(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    owner: &Pubkey,
+    old_owner: &Pubkey,
+    refund_destination: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new_readonly(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_key_rotation_address(program_id, wallet_config, old_owner).0,
+            false,
+        ),
+        AccountMeta::new(*refund_destination, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::VetoKeyRotation, accounts)
+}
+
+pub fn complete_key_rotation(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    payer: &Pubkey,
+    old_owner: &Pubkey,
+    new_owner: &Pubkey,
+    refund_destination: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, old_owner).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, new_owner).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_key_rotation_address(program_id, wallet_config, old_owner).0,
+            false,
+        ),
+        AccountMeta::new(*refund_destination, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(find_audit_log_address(program_id, wallet_config).0, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::CompleteKeyRotation, accounts)
+}
+
+pub fn find_audit_log_address(program_id: &Pubkey, wallet_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUDIT.as_bytes(), wallet_config.as_ref()], program_id)
+}
+
+pub fn create_audit_log(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    wallet_config: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*wallet_config, false),
+        AccountMeta::new(find_audit_log_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::CreateAuditLog, accounts)
+}
+
+// the records still held by the log, oldest first
+pub fn audit_history(log: &AuditLog) -> Vec<AuditRecord> {
+    let len = log.records.len() as u64;
+    (log.next_index.saturating_sub(len)..log.next_index)
+        .map(|index| log.records[(index % len) as usize])
+        .collect()
+}
+
+pub fn find_wallet_stats_address(program_id: &Pubkey, wallet_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[WALLET_STATS.as_bytes(), wallet_config.as_ref()],
+        program_id,
+    )
+}
+
+pub fn create_wallet_stats(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    wallet_config: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*wallet_config, false),
+        AccountMeta::new(
+            find_wallet_stats_address(program_id, wallet_config).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    wallet_instruction(program_id, WalletInstruction::CreateWalletStats, accounts)
+}
+
+pub fn get_wallet_info(program_id: &Pubkey, wallet_config: &Pubkey) -> Instruction {
+    let accounts = vec![AccountMeta::new_readonly(*wallet_config, false)];
+    wallet_instruction(program_id, WalletInstruction::GetWalletInfo, accounts)
+}
+
+// vault is the one the proposal transfers out of, 0 if it does not
+pub fn get_proposal_status(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> Instruction {
+    let proposal = find_proposal_address(program_id, wallet_config, sequence).0;
+    let accounts = vec![
+        AccountMeta::new_readonly(*wallet_config, false),
+        AccountMeta::new_readonly(proposal, false),
+        AccountMeta::new_readonly(
+            find_vote_count_address(program_id, wallet_config, &proposal).0,
+            false,
+        ),
+    ];
+    wallet_instruction(program_id, WalletInstruction::GetProposalStatus, accounts)
+}
+
+// execution_accounts are the ones close_proposal would get, only checked for Transfer and TransferSol
+pub fn validate_proposal(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    execution_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let proposal = find_proposal_address(program_id, wallet_config, sequence).0;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*wallet_config, false),
+        AccountMeta::new_readonly(proposal, false),
+        AccountMeta::new_readonly(
+            find_vote_count_address(program_id, wallet_config, &proposal).0,
+            false,
+        ),
+    ];
+    accounts.extend(execution_accounts);
+    wallet_instruction(program_id, WalletInstruction::ValidateProposal, accounts)
+}
+
+// spl governance runs the instructions of a passed dao proposal with its native treasury signing through
+// invoke_signed, so a treasury added as an owner proposes, votes and executes like any other owner
+
+const NATIVE_TREASURY: &str = "native-treasury";
+
+pub fn find_native_treasury_address(
+    governance_program: &Pubkey,
+    governance: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[NATIVE_TREASURY.as_bytes(), governance.as_ref()],
+        governance_program,
+    )
+}
+
+// the treasury also pays the proposal rent, sequence has to be the proposal_count once the dao proposal executes
+pub fn governance_create_proposal(
+    program_id: &Pubkey,
+    governance_program: &Pubkey,
+    governance: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    proposal: ProposalType,
+) -> Instruction {
+    let treasury = find_native_treasury_address(governance_program, governance).0;
+    create_proposal(
+        program_id,
+        &treasury,
+        &treasury,
+        &treasury,
+        wallet_config,
+        sequence,
+        proposal,
+    )
+}
+
+pub fn governance_vote(
+    program_id: &Pubkey,
+    governance_program: &Pubkey,
+    governance: &Pubkey,
+    wallet_config: &Pubkey,
+    proposal: &Pubkey,
+) -> Instruction {
+    let treasury = find_native_treasury_address(governance_program, governance).0;
+    vote(program_id, &treasury, &treasury, wallet_config, proposal)
+}
+
+pub fn governance_close_proposal(
+    program_id: &Pubkey,
+    governance_program: &Pubkey,
+    governance: &Pubkey,
+    refund_destination: &Pubkey,
+    wallet_config: &Pubkey,
+    proposal: &Pubkey,
+    execution_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let treasury = find_native_treasury_address(governance_program, governance).0;
+    close_proposal(
+        program_id,
+        &treasury,
+        refund_destination,
+        wallet_config,
+        proposal,
+        execution_accounts,
+    )
+}
+
+// offline signing over durable nonces, signers can take as long as they need before the
+// transaction lands as long as the proposal lifetime of the wallet covers the gap
+
+pub struct DurableNonce {
+    pub account: Pubkey,
+    pub authority: Pubkey,
+    pub blockhash: Hash,
+}
+
+pub fn fetch_durable_nonce(rpc: &RpcClient, account: &Pubkey) -> Result<DurableNonce, ClientError> {
+    let nonce_account = nonce_utils::get_account(rpc, account)?;
+    let data = nonce_utils::data_from_account(&nonce_account)?;
+    Ok(DurableNonce {
+        account: *account,
+        authority: data.authority,
+        blockhash: data.blockhash(),
+    })
+}
+
+// prepends the advance nonce instruction, signers partial_sign the returned transaction independently
+// and anything built here, e.g. close_proposal for the execution, can be wrapped
+pub fn nonce_transaction(
+    instructions: &[Instruction],
+    fee_payer: &Pubkey,
+    nonce: &DurableNonce,
+) -> Transaction {
+    let mut message = Message::new_with_nonce(
+        instructions.to_vec(),
+        Some(fee_payer),
+        &nonce.account,
+        &nonce.authority,
+    );
+    message.recent_blockhash = nonce.blockhash;
+    Transaction::new_unsigned(message)
+}
+
+// relayed voting, the organization's fee_payer and the voter partial_sign the returned transaction
+// independently, the voter's account is only read
+pub fn sponsored_vote_transaction(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    owner: &Pubkey,
+    wallet_config: &Pubkey,
+    proposal: &Pubkey,
+    fee_payer: &Pubkey,
+    recent_blockhash: &Hash,
+) -> Transaction {
+    let message = Message::new_with_blockhash(
+        &[vote(program_id, user, owner, wallet_config, proposal)],
+        Some(fee_payer),
+        recent_blockhash,
+    );
+    Transaction::new_unsigned(message)
+}
+
+pub fn nonce_vote_transaction(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    wallet_config: &Pubkey,
+    proposal: &Pubkey,
+    fee_payer: &Pubkey,
+    nonce: &DurableNonce,
+) -> Transaction {
+    nonce_transaction(
+        &[vote(program_id, user, user, wallet_config, proposal)],
+        fee_payer,
+        nonce,
+    )
+}
+
+// address lookup tables owned by the wallet authority, created and extended through proposals
+
+pub fn find_lookup_table_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    recent_slot: u64,
+) -> (Pubkey, u8) {
+    let authority = find_authority_address(program_id, wallet_config).0;
+    derive_lookup_table_address(&authority, recent_slot)
+}
+
+// execution accounts to pass to close_proposal for the lookup table proposals
+pub fn create_lookup_table_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(
+            find_lookup_table_address(program_id, wallet_config, recent_slot).0,
+            false,
+        ),
+        AccountMeta::new_readonly(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(LOOKUP_TABLE_PROGRAM_ID, false),
+    ]
+}
+
+pub fn extend_lookup_table_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    payer: &Pubkey,
+    table: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*table, false),
+        AccountMeta::new_readonly(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(LOOKUP_TABLE_PROGRAM_ID, false),
+    ]
+}
+
+pub fn freeze_lookup_table_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    table: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*table, false),
+        AccountMeta::new_readonly(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(LOOKUP_TABLE_PROGRAM_ID, false),
+    ]
+}
+
+pub fn fetch_lookup_table(
+    rpc: &RpcClient,
+    table: &Pubkey,
+) -> Result<AddressLookupTableAccount, ClientError> {
+    let data = rpc.get_account_data(table)?;
+    let lookup_table = AddressLookupTable::deserialize(&data)?;
+    Ok(AddressLookupTableAccount {
+        key: *table,
+        addresses: lookup_table.addresses.to_vec(),
+    })
+}
+
+// compiles instructions into a v0 message, accounts found in the tables are referenced by index
+pub fn lookup_table_message(
+    fee_payer: &Pubkey,
+    instructions: &[Instruction],
+    tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedMessage, ClientError> {
+    let message = v0::Message::try_compile(fee_payer, instructions, tables, recent_blockhash)?;
+    Ok(VersionedMessage::V0(message))
+}
+
+// spl stake pool deposits and withdrawals, the pool state supplies the accounts the pool program checks
+
+pub fn fetch_stake_pool(rpc: &RpcClient, stake_pool: &Pubkey) -> Result<StakePool, ClientError> {
+    let data = rpc.get_account_data(stake_pool)?;
+    Ok(StakePool::unpack(&data)?)
+}
+
+// execution accounts to pass to close_proposal, pool_token_account is owned by the wallet authority
+pub fn stake_pool_deposit_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    stake_pool: &Pubkey,
+    pool: &StakePool,
+    pool_token_account: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*stake_pool, false),
+        AccountMeta::new_readonly(find_withdraw_authority_address(stake_pool).0, false),
+        AccountMeta::new(pool.reserve_stake, false),
+        AccountMeta::new(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new(*pool_token_account, false),
+        AccountMeta::new(pool.manager_fee_account, false),
+        AccountMeta::new(pool.pool_mint, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(pool.token_program_id, false),
+        AccountMeta::new_readonly(STAKE_POOL_PROGRAM_ID, false),
+    ]
+}
+
+pub fn stake_pool_withdraw_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    stake_pool: &Pubkey,
+    pool: &StakePool,
+    pool_token_account: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*stake_pool, false),
+        AccountMeta::new_readonly(find_withdraw_authority_address(stake_pool).0, false),
+        AccountMeta::new(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new(*pool_token_account, false),
+        AccountMeta::new(pool.reserve_stake, false),
+        AccountMeta::new(pool.manager_fee_account, false),
+        AccountMeta::new(pool.pool_mint, false),
+        AccountMeta::new_readonly(sysvar::clock::ID, false),
+        AccountMeta::new_readonly(sysvar::stake_history::ID, false),
+        AccountMeta::new_readonly(STAKE_PROGRAM_ID, false),
+        AccountMeta::new_readonly(pool.token_program_id, false),
+        AccountMeta::new_readonly(STAKE_POOL_PROGRAM_ID, false),
+    ]
+}
+
+// stake account created by a SplitStake proposal, sequence is the proposal's
+pub fn find_split_stake_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SPLIT_STAKE.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+// execution accounts to pass to close_proposal for SplitStake
+pub fn split_stake_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    payer: &Pubkey,
+    sequence: u64,
+    stake_account: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*stake_account, false),
+        AccountMeta::new(
+            find_split_stake_address(program_id, wallet_config, sequence).0,
+            false,
+        ),
+        AccountMeta::new_readonly(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(STAKE_PROGRAM_ID, false),
+    ]
+}
+
+// execution accounts to pass to close_proposal for MergeStake
+pub fn merge_stake_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    destination: &Pubkey,
+    source: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*destination, false),
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(sysvar::clock::ID, false),
+        AccountMeta::new_readonly(sysvar::stake_history::ID, false),
+        AccountMeta::new_readonly(STAKE_PROGRAM_ID, false),
+    ]
+}
+
+// registry of programs ExecuteInstruction proposals may call into
+
+pub fn find_registry_address(program_id: &Pubkey, wallet_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REGISTRY.as_bytes(), wallet_config.as_ref()], program_id)
+}
+
+// execution accounts to pass to close_proposal for RegisterProgram, UnregisterProgram only needs the registry
+pub fn register_program_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    payer: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(find_registry_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ]
+}
+
+// wraps an instruction to be signed by the wallet authority into an ExecuteInstruction proposal
+pub fn execute_instruction_proposal(instruction: &Instruction) -> ProposalType {
+    ProposalType::ExecuteInstruction {
+        program: instruction.program_id,
+        accounts: instruction
+            .accounts
+            .iter()
+            .map(|account| ProposalAccount {
+                pubkey: account.pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: instruction.data.clone(),
+    }
+}
+
+// execution accounts for an ExecuteInstruction proposal made from instruction, the wallet authority
+// signs inside the program so it is never a signer of the outer transaction
+pub fn execute_instruction_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    instruction: &Instruction,
+) -> Vec<AccountMeta> {
+    let wallet_authority = find_authority_address(program_id, wallet_config).0;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_registry_address(program_id, wallet_config).0, false),
+        AccountMeta::new(wallet_authority, false),
+        AccountMeta::new_readonly(instruction.program_id, false),
+    ];
+    for account in instruction.accounts.iter() {
+        let is_signer = account.is_signer && account.pubkey != wallet_authority;
+        accounts.push(if account.is_writable {
+            AccountMeta::new(account.pubkey, is_signer)
+        } else {
+            AccountMeta::new_readonly(account.pubkey, is_signer)
+        });
+    }
+    accounts
+}
+
+// execution accounts to pass to close_proposal for FreezeAccount and ThawAccount
+pub fn freeze_account_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    mint: &Pubkey,
+    account: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    ]
+}
+
+// execution accounts to pass to close_proposal for ChangeOwnerKey
+pub fn change_owner_key_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    payer: &Pubkey,
+    old_owner: &Pubkey,
+    new_owner: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, old_owner).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, new_owner).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ]
+}
+
+// spend caps of mint, the default pubkey for SOL, Transfer and TransferSol executions pass it after their program
+pub fn find_treasury_stats_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[STATS.as_bytes(), wallet_config.as_ref(), mint.as_ref()],
+        program_id,
+    )
+}
+
+pub fn find_blacklist_address(program_id: &Pubkey, wallet_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BLACKLIST.as_bytes(), wallet_config.as_ref()], program_id)
+}
+
+// execution accounts to pass to close_proposal for SplitPayment, the send account is the vault authority's
+// associated token account and receive_accounts follow the payments of the proposal
+pub fn split_payment_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    token_mint: &Pubkey,
+    vault: u8,
+    receive_accounts: &[Pubkey],
+) -> Vec<AccountMeta> {
+    let wallet_authority = find_vault_authority_address(program_id, wallet_config, vault).0;
+    let mut accounts = vec![
+        AccountMeta::new(
+            get_associated_token_address(&wallet_authority, token_mint),
+            false,
+        ),
+        AccountMeta::new_readonly(wallet_authority, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(
+            find_treasury_stats_address(program_id, wallet_config, token_mint).0,
+            false,
+        ),
+        AccountMeta::new_readonly(find_blacklist_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(*token_mint, false),
+    ];
+    if vault != 0 {
+        accounts.push(AccountMeta::new_readonly(
+            find_vault_address(program_id, wallet_config, vault).0,
+            false,
+        ));
+    }
+    for receive_account in receive_accounts.iter() {
+        accounts.push(AccountMeta::new(*receive_account, false));
+    }
+    accounts
+}
+
+// execution accounts to pass to close_proposal for TransferToWallet, payer is ignored if the proposal is
+// treasury funded
+pub fn transfer_to_wallet_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    payer: &Pubkey,
+    token_mint: &Pubkey,
+    recipient: &Pubkey,
+    vault: u8,
+    treasury_funded: bool,
+) -> Vec<AccountMeta> {
+    let wallet_authority = find_vault_authority_address(program_id, wallet_config, vault).0;
+    let mut accounts = vec![
+        AccountMeta::new(
+            get_associated_token_address(&wallet_authority, token_mint),
+            false,
+        ),
+        AccountMeta::new(get_associated_token_address(recipient, token_mint), false),
+        AccountMeta::new_readonly(*recipient, false),
+        AccountMeta::new_readonly(*token_mint, false),
+        AccountMeta::new_readonly(wallet_authority, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(
+            find_treasury_stats_address(program_id, wallet_config, token_mint).0,
+            false,
+        ),
+        AccountMeta::new_readonly(find_blacklist_address(program_id, wallet_config).0, false),
+    ];
+    if treasury_funded {
+        accounts.push(AccountMeta::new(
+            find_authority_address(program_id, wallet_config).0,
+            false,
+        ));
+    } else {
+        accounts.push(AccountMeta::new(*payer, true));
+    }
+    accounts.push(AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false));
+    accounts.push(AccountMeta::new_readonly(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        false,
+    ));
+    if vault != 0 {
+        accounts.push(AccountMeta::new_readonly(
+            find_vault_address(program_id, wallet_config, vault).0,
+            false,
+        ));
+    }
+    accounts
+}
+
+// execution accounts to pass to close_proposal for BlacklistAddress, UnblacklistAddress only needs the blacklist
+pub fn blacklist_address_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    payer: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(find_blacklist_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ]
+}
+
+// execution accounts to pass to close_proposal for SetMandatoryApprovers
+pub fn set_mandatory_approvers_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    approvers: &[Pubkey],
+) -> Vec<AccountMeta> {
+    approvers
+        .iter()
+        .map(|approver| {
+            AccountMeta::new_readonly(
+                find_wallet_auth_address(program_id, wallet_config, approver).0,
+                false,
+            )
+        })
+        .collect()
+}
+
+// execution accounts to pass to close_proposal for SetSpendLimit
+pub fn set_spend_limit_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    payer: &Pubkey,
+    mint: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(
+            find_treasury_stats_address(program_id, wallet_config, mint).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ]
+}
+
+// spl token multisig created by a CreateTokenMultisig proposal, sequence is the proposal's
+pub fn find_token_multisig_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TOKEN_MULTISIG.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+// execution accounts to pass to close_proposal for CreateTokenMultisig
+pub fn create_token_multisig_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    payer: &Pubkey,
+    sequence: u64,
+    signers: &[Pubkey],
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(
+            find_token_multisig_address(program_id, wallet_config, sequence).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    ];
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(*signer, false));
+    }
+    accounts
+}
+
+// execution accounts to pass to close_proposal for MultisigTransfer, other_signers sign the transaction
+pub fn multisig_transfer_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    multisig: &Pubkey,
+    token_mint: &Pubkey,
+    send_account: &Pubkey,
+    receive_account: &Pubkey,
+    other_signers: &[Pubkey],
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+        AccountMeta::new(*send_account, false),
+        AccountMeta::new(*receive_account, false),
+        AccountMeta::new_readonly(*multisig, false),
+        AccountMeta::new_readonly(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(*token_mint, false),
+    ];
+    for signer in other_signers.iter() {
+        accounts.push(AccountMeta::new_readonly(*signer, true));
+    }
+    accounts
+}
+
+// execution accounts to pass to close_proposal for CloseWallet, open_accounts are the wallet's templates, treasury
+// stats, allowances and recurring swaps still open, followed by the vaults, the token triples to sweep go after
+pub fn close_wallet_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    destination: &Pubkey,
+    owners: &[Pubkey],
+    open_accounts: &[Pubkey],
+    vaults: &[u8],
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+        AccountMeta::new(find_authority_address(program_id, wallet_config).0, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(find_treasury_address(program_id, wallet_config).0, false),
+        AccountMeta::new(find_registry_address(program_id, wallet_config).0, false),
+        AccountMeta::new(find_blacklist_address(program_id, wallet_config).0, false),
+    ];
+    for owner in owners.iter() {
+        accounts.push(AccountMeta::new(
+            find_wallet_auth_address(program_id, wallet_config, owner).0,
+            false,
+        ));
+    }
+    for account in open_accounts.iter() {
+        accounts.push(AccountMeta::new(*account, false));
+    }
+    for vault in vaults.iter() {
+        accounts.push(AccountMeta::new(
+            find_vault_address(program_id, wallet_config, *vault).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            find_vault_authority_address(program_id, wallet_config, *vault).0,
+            false,
+        ));
+    }
+    accounts
+}
+
+// recurring swaps through a registered swap program, cranked by anyone once a swap is due
+
+pub fn find_recurring_swap_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SWAP.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+// execution accounts to pass to close_proposal for CreateRecurringSwap, sequence is the proposal's
+pub fn create_recurring_swap_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    payer: &Pubkey,
+    sequence: u64,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(
+            find_recurring_swap_address(program_id, wallet_config, sequence).0,
+            false,
+        ),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ]
+}
+
+// execution accounts to pass to close_proposal for CancelRecurringSwap
+pub fn cancel_recurring_swap_accounts(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+) -> Vec<AccountMeta> {
+    vec![AccountMeta::new(
+        find_recurring_swap_address(program_id, wallet_config, sequence).0,
+        false,
+    )]
+}
+
+// swap_instruction is the swap program's instruction with the wallet authority as its signer, the
+// authority signs inside the program so it is never a signer of the outer transaction
+pub fn execute_recurring_swap(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    input_account: &Pubkey,
+    output_account: &Pubkey,
+    swap_instruction: &Instruction,
+) -> Instruction {
+    let wallet_authority = find_authority_address(program_id, wallet_config).0;
+    let mut accounts = vec![
+        AccountMeta::new(*wallet_config, false),
+        AccountMeta::new(wallet_authority, false),
+        AccountMeta::new(
+            find_recurring_swap_address(program_id, wallet_config, sequence).0,
+            false,
+        ),
+        AccountMeta::new_readonly(find_registry_address(program_id, wallet_config).0, false),
+        AccountMeta::new(*input_account, false),
+        AccountMeta::new(*output_account, false),
+        AccountMeta::new_readonly(swap_instruction.program_id, false),
+    ];
+    for account in swap_instruction.accounts.iter() {
+        let is_signer = account.is_signer && account.pubkey != wallet_authority;
+        accounts.push(if account.is_writable {
+            AccountMeta::new(account.pubkey, is_signer)
+        } else {
+            AccountMeta::new_readonly(account.pubkey, is_signer)
+        });
+    }
+    wallet_instruction(
+        program_id,
+        WalletInstruction::ExecuteRecurringSwap {
+            data: swap_instruction.data.clone(),
+        },
+        accounts,
+    )
+}
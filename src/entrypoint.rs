@@ -1,8 +1,8 @@
-use processor::process_instruction;
+use crate::error::WalletError;
+use crate::processor::process_instruction;
 use solana_program::{
-    account_info::AccountInfo,
-    entrypoint::{entrypoint, ProgramResult},
-    pubkey::Pubkey,
+    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult,
+    program_error::PrintProgramError, pubkey::Pubkey,
 };
 
 entrypoint!(entrypoint_function);
@@ -12,5 +12,10 @@ pub fn entrypoint_function(
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
-    process_instruction(program_id, accounts, data)
+    if let Err(error) = process_instruction(program_id, accounts, data) {
+        // custom codes are logged with the message of their WalletError
+        error.print::<WalletError>();
+        return Err(error);
+    }
+    Ok(())
 }
@@ -1,11 +1,16 @@
-use solana_program::program_error::ProgramError;
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, Copy, FromPrimitive)]
 pub enum WalletError {
     #[error("Wallet parameter constraint violdated, m=0 or m>n")]
     InvalidWalletParameters,
-    #[error("Proposal lifetime specified is less than 10 minutes")]
+    #[error("Proposal lifetime specified is less than the wallet's minimum")]
     TooShortLifetime,
     #[error("Invalid Wallet Auth account passed")]
     InvalidWalletAuth,
@@ -33,6 +38,224 @@ pub enum WalletError {
     IncorrectReceiveAccount,
     #[error("The wallet already has maximum number of owners")]
     MaximumOwnersReached,
+    #[error("The operation would leave the Wallet Authority below its rent exempt minimum")]
+    BelowRentFloor,
+    #[error("Recovery inactivity period can not be negative")]
+    InvalidRecoveryPeriod,
+    #[error("No recovery beneficiary has been configured for the wallet")]
+    RecoveryNotConfigured,
+    #[error(
+        "The account passed for Beneficiary does not correspond to the configured beneficiary"
+    )]
+    IncorrectBeneficiary,
+    #[error("The wallet has not been inactive for the configured recovery period")]
+    WalletStillActive,
+    #[error("The account is not rent exempt")]
+    NotRentExempt,
+    #[error("The account passed is of a different type than expected")]
+    IncorrectAccountType,
+    #[error("The proposal passed does not belong to the given wallet")]
+    ProposalWalletMismatch,
+    #[error("Invalid Proposal account passed")]
+    InvalidProposal,
+    #[error("The signer is neither an executor nor an owner of the wallet")]
+    UnauthorizedCloser,
+    #[error("The proposal has already been executed")]
+    AlreadyExecuted,
+    #[error("The owner set of the wallet has changed since the proposal was created")]
+    EpochMismatch,
+    #[error("The owner does not have the role required for this action")]
+    MissingRole,
+    #[error("Invalid Allowance account passed")]
+    InvalidAllowance,
+    #[error("The amount exceeds the remaining allowance")]
+    AllowanceExceeded,
+    #[error("Invalid SessionKey account passed")]
+    InvalidSessionKey,
+    #[error("Session key expiry must be in the future")]
+    InvalidSessionExpiry,
+    #[error("Session keys can only be scoped to proposing and voting")]
+    InvalidSessionScope,
+    #[error("The session key has expired")]
+    SessionExpired,
+    #[error("The session key is not scoped for this action")]
+    SessionScopeMismatch,
+    #[error("Rent can only be refunded to the payer or the wallet authority")]
+    InvalidRefundDestination,
+    #[error("Invalid Template account passed")]
+    InvalidTemplate,
+    #[error("A template cannot hold another template proposal")]
+    NestedTemplate,
+    #[error("Invalid Vault account passed")]
+    InvalidVault,
+    #[error("Invalid Escrow account passed")]
+    InvalidEscrow,
+    #[error("The escrow has not reached its unlock time")]
+    EscrowLocked,
+    #[error("Invalid Stream account passed")]
+    InvalidStream,
+    #[error("Stream rate and duration must be positive")]
+    InvalidStreamParameters,
+    #[error("Nothing has vested in the stream since the last withdrawal")]
+    NothingVested,
+    #[error("Invalid Claim account passed")]
+    InvalidClaim,
+    #[error("Claim period must be positive")]
+    InvalidClaimPeriod,
+    #[error("The claim has expired")]
+    ClaimExpired,
+    #[error("The claim can still be withdrawn by its recipient")]
+    ClaimNotExpired,
+    #[error("The signer is not the recipient of the claim")]
+    IncorrectRecipient,
+    #[error("Invalid address lookup table passed")]
+    InvalidLookupTable,
+    #[error("Invalid stake pool passed")]
+    InvalidStakePool,
+    #[error("Invalid ProgramRegistry account passed")]
+    InvalidRegistry,
+    #[error("Program is not in the wallet's registry")]
+    ProgramNotRegistered,
+    #[error("Program is already in the wallet's registry")]
+    ProgramAlreadyRegistered,
+    #[error("The program registry is full")]
+    RegistryFull,
+    #[error("Invalid RecurringSwap account passed")]
+    InvalidRecurringSwap,
+    #[error("Recurring swap amount, interval and number of swaps must be non zero and the mints must differ")]
+    InvalidSwapParameters,
+    #[error("The next swap is not due yet")]
+    SwapNotDue,
+    #[error("Swap returned less than the minimum output")]
+    SwapOutputTooLow,
+    #[error("Swap spent more than the recurring swap amount")]
+    SwapAmountExceeded,
+    #[error("Swap touched wallet accounts or funds it is not allowed to")]
+    UnauthorizedSwapAccount,
+    #[error("No ed25519 signature of the owner over the proposal digest")]
+    MissingOwnerSignature,
+    #[error("Timelock can not be negative")]
+    InvalidTimelock,
+    #[error("The wallet has no pending change")]
+    NoPendingChange,
+    #[error("The timelock of the pending change has not passed yet")]
+    TimelockNotElapsed,
+    #[error("Proposal lifetime specified is more than the wallet's maximum")]
+    TooLongLifetime,
+    #[error("Minimum proposal lifetime must be positive and not above the maximum")]
+    InvalidLifetimeBounds,
+    #[error("Only transfers of the native mint can be unwrapped")]
+    InvalidUnwrap,
+    #[error("The wallet authority is not the freeze authority of the mint")]
+    NotFreezeAuthority,
+    #[error("Invalid spl token multisig account or signers")]
+    InvalidTokenMultisig,
+    #[error("Invalid key rotation")]
+    InvalidKeyRotation,
+    #[error("Key rotation delay has not elapsed")]
+    KeyRotationNotDue,
+    #[error("Only the proposer can submit vote permits for, edit, activate or discard a proposal")]
+    NotProposer,
+    #[error("The vote permit has expired")]
+    VotePermitExpired,
+    #[error("Invalid TreasuryStats account passed")]
+    InvalidTreasuryStats,
+    #[error("The outflow would exceed the wallet's daily or weekly spend cap for the mint")]
+    SpendLimitExceeded,
+    #[error("Invalid Blacklist account passed")]
+    InvalidBlacklist,
+    #[error("The destination is on the wallet's blacklist")]
+    DestinationBlacklisted,
+    #[error("Address is already on the wallet's blacklist")]
+    AddressAlreadyBlacklisted,
+    #[error("Address is not on the wallet's blacklist")]
+    AddressNotBlacklisted,
+    #[error("The blacklist is full")]
+    BlacklistFull,
+    #[error("Not every mandatory approver has voted for the proposal")]
+    MissingMandatoryApproval,
+    #[error("Invalid AuditLog account passed")]
+    InvalidAuditLog,
+    #[error("The wallet has too many open proposals")]
+    TooManyOpenProposals,
+    #[error("The token program passed, or the owner of a mint or token account, is not the spl token program")]
+    IncorrectTokenProgram,
+    #[error("The account passed for the system program is not the system program")]
+    IncorrectSystemProgram,
+    #[error("The account does not exist or has been closed")]
+    AccountClosed,
+    #[error("The number of accounts passed does not match the instruction")]
+    AccountCountMismatch,
+    #[error("An account the instruction writes to was not passed as writable")]
+    AccountNotWritable,
+    #[error("Invalid WalletStats account passed")]
+    InvalidWalletStats,
+    #[error("The token account still holds tokens")]
+    TokenAccountNotEmpty,
+    #[error("Invalid Treasury account passed")]
+    InvalidTreasury,
+    #[error("Basis points have to be between 1 and 10000")]
+    InvalidBasisPoints,
+    #[error("A split payment needs between 1 and 16 payments")]
+    InvalidSplitPayment,
+    #[error("Invalid Vesting account passed")]
+    InvalidVesting,
+    #[error("A vesting schedule needs an amount and start <= cliff <= end with start < end")]
+    InvalidVestingSchedule,
+    #[error("The wallet is frozen, only an Unfreeze proposal can be executed")]
+    WalletFrozen,
+    #[error("The proposal is a draft, it can not be voted on or executed before it is activated")]
+    ProposalIsDraft,
+    #[error("The proposal is not a draft")]
+    ProposalNotDraft,
+    #[error("Owners other than the proposer have voted, the proposal can no longer be amended")]
+    ProposalAlreadyVoted,
+    #[error("The proposal has already been extended once")]
+    ProposalAlreadyExtended,
+    #[error("Only an owner who voted for the proposal can extend it, once half of its threshold has voted")]
+    ExtensionQuorumNotMet,
+    #[error("Removing inactive owners needs a positive period and at least one owner, and has to leave one")]
+    InvalidOwnerRemoval,
+    #[error("The owner has voted within the inactivity period")]
+    OwnerNotInactive,
+    #[error("Not enough owners have voted on the proposal to reach the quorum")]
+    QuorumNotReached,
+    #[error("The owner does not hold enough of the wallet's gate mint to vote")]
+    VoteGateNotMet,
+    #[error("The signer does not hold the NFT the owner seat is bound to")]
+    NotSeatHolder,
+    #[error("The owner seat is bound to an NFT and can only be used by its holder")]
+    NftBoundSeat,
+    #[error("The execution callback program is missing or can not be this program")]
+    InvalidCallbackProgram,
+    #[error("Only an owner who voted for the proposal can comment on it, in at most 128 bytes")]
+    InvalidVoteComment,
+    #[error("Invalid token metadata, or the wallet authority is not its update authority")]
+    InvalidMetadata,
+    #[error("Invalid name account, or the wallet authority does not own it")]
+    InvalidNameAccount,
+    #[error("Invalid price feed, or its price is not trading or is stale")]
+    InvalidPriceFeed,
+    #[error("The price condition of the proposal is not met")]
+    PriceConditionNotMet,
+    #[error("Invalid stake account")]
+    InvalidStakeAccount,
+    #[error("The proposal is not waiting out a challenge period")]
+    NotOptimistic,
+    #[error("Arithmetic overflow")]
+    Overflow,
+    #[error(
+        "An owner seat can only be bound to a mint with a supply of one, no decimals and no mint or freeze authority"
+    )]
+    NotNftMint,
+    #[error("The wallet can only be closed by its last open proposal")]
+    ProposalsStillOpen,
+    #[error(
+        "The wallet can only be closed once its escrows, streams, claims and vestings are closed and every other account it opened is passed"
+    )]
+    AccountsStillOpen,
+    #[error("The account belongs to another wallet")]
+    AccountWalletMismatch,
 }
 
 impl From<WalletError> for ProgramError {
@@ -40,3 +263,22 @@ impl From<WalletError> for ProgramError {
         Self::Custom(value as u32)
     }
 }
+
+impl<T> DecodeError<T> for WalletError {
+    fn type_of() -> &'static str {
+        "WalletError"
+    }
+}
+
+impl PrintProgramError for WalletError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        msg!(&self.to_string());
+    }
+}
@@ -0,0 +1,176 @@
+use crate::state::{PriceCondition, WalletConfig};
+use borsh::BorshSerialize;
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+// variants are only ever appended so the borsh layout stays stable for indexers
+#[derive(BorshSerialize)]
+pub enum WalletEvent {
+    SolDeposited {
+        wallet: Pubkey,
+        depositor: Pubkey,
+        amount: u64,
+        total_deposited: u64,
+    },
+    WalletCreated {
+        wallet: Pubkey,
+        creator: Pubkey,
+        m: u8,
+        n: u8,
+        owners: u8,
+        proposal_lifetime: i64,
+    },
+    ProposalCreated {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        proposer: Pubkey,
+        sequence: u64,
+    },
+    Voted {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        owner: Pubkey,
+        votes: u8,
+    },
+    Executed {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        sequence: u64,
+    },
+    ProposalClosed {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        sequence: u64,
+    },
+    OwnerAdded {
+        wallet: Pubkey,
+        owner: Pubkey,
+        id: u8,
+    },
+    OwnerRemoved {
+        wallet: Pubkey,
+        owner: Pubkey,
+    },
+    OwnershipRenounced {
+        wallet: Pubkey,
+        owner: Pubkey,
+    },
+    RecoveryClaimed {
+        wallet: Pubkey,
+        beneficiary: Pubkey,
+    },
+    WalletClosed {
+        wallet: Pubkey,
+    },
+    TokensTransferred {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        proposal_type: u8,
+        mint: Pubkey,
+        source: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+    },
+    SolTransferred {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        proposal_type: u8,
+        destination: Pubkey,
+        amount: u64,
+    },
+    SwapExecuted {
+        wallet: Pubkey,
+        swap: Pubkey,
+        input_amount: u64,
+        output_amount: u64,
+    },
+    OwnerKeyChanged {
+        wallet: Pubkey,
+        old_owner: Pubkey,
+        new_owner: Pubkey,
+        id: u8,
+    },
+    KeyRotationRequested {
+        wallet: Pubkey,
+        old_owner: Pubkey,
+        new_owner: Pubkey,
+        effective_at: i64,
+    },
+    KeyRotationVetoed {
+        wallet: Pubkey,
+        old_owner: Pubkey,
+        vetoed_by: Pubkey,
+    },
+    TreasuryDeposited {
+        wallet: Pubkey,
+        depositor: Pubkey,
+        amount: u64,
+    },
+    WalletFrozen {
+        wallet: Pubkey,
+        owner: Pubkey,
+    },
+    DraftEdited {
+        wallet: Pubkey,
+        proposal: Pubkey,
+    },
+    ProposalActivated {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        sequence: u64,
+    },
+    ProposalAmended {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        revision: u16,
+    },
+    ProposalExtended {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        owner: Pubkey,
+        expires_at: i64,
+    },
+    InactiveOwnerRemoved {
+        wallet: Pubkey,
+        owner: Pubkey,
+        last_vote_at: i64,
+    },
+    WalletCloned {
+        wallet: Pubkey,
+        source: Pubkey,
+        creator: Pubkey,
+        owners: u8,
+    },
+    VoteCommented {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        owner: Pubkey,
+    },
+    ContentHashSet {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        content_hash: [u8; 32],
+        revision: u16,
+    },
+    PriceConditionSet {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        condition: PriceCondition,
+        revision: u16,
+    },
+    ProposalObjected {
+        wallet: Pubkey,
+        proposal: Pubkey,
+        owner: Pubkey,
+    },
+}
+
+impl WalletEvent {
+    // logged as the event followed by the wallet's event count after it, a gap in the count tells an indexer it
+    // missed an event, the caller stores wallet_details afterwards
+    pub fn emit(&self, wallet_details: &mut WalletConfig) {
+        wallet_details.event_count += 1;
+        if let Ok(data) = self.try_to_vec() {
+            sol_log_data(&[&data, &wallet_details.event_count.to_le_bytes()]);
+        }
+    }
+}
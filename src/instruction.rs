@@ -1,8 +1,15 @@
-use crate::state::ProposalType;
+use crate::state::{
+    Payment, PriceCondition, ProposalAccount, ProposalType, TemplateProposal,
+    DEFAULT_MAX_PROPOSAL_LIFETIME, DEFAULT_MIN_PROPOSAL_LIFETIME, MAX_EXECUTORS,
+};
 use borsh::BorshDeserialize;
 use solana_program::{program_error::ProgramError, pubkey::Pubkey};
 use std::convert::TryInto;
 
+// a template's proposal can't be another template, so unpacking stops one level down rather than recursing for as
+// long as the data nests CreateTemplate
+const MAX_PROPOSAL_DEPTH: u8 = 1;
+
 pub enum WalletInstruction {
     /*
     User: signer, mutable
@@ -16,65 +23,741 @@ pub enum WalletInstruction {
         n: u8,
         owners: Vec<Pubkey>,
         proposal_lifetime: i64,
+        min_proposal_lifetime: i64,
+        max_proposal_lifetime: i64,
     },
     /*
     Payer: signer, mutable
     WalletConfig
-    WalletAuthority ["authority", wallet_config.key]
+    WalletAuthority ["authority", wallet_config.key] or ["authority", wallet_config.key, vault] if vault is not 0
     Mint
     AssociatedTokenAccount: mutable
     SystemProgram
     TokenProgram
     AssociatedTokenProgram
+    Vault ["vault", wallet_config.key, vault] - only if vault is not 0
      */
-    CreateTokenAccount,
+    CreateTokenAccount {
+        vault: u8,
+    },
+    /*
+    Payer: signer, mutable
+    WalletConfig
+    WalletAuthority ["authority", wallet_config.key] or ["authority", wallet_config.key, vault] if vault is not 0
+    Mint
+    TokenAccount: mutable ["aux-token", wallet_config.key, wallet_authority.key, mint.key, seed as u64 le bytes]
+    SystemProgram
+    TokenProgram
+    Vault ["vault", wallet_config.key, vault] - only if vault is not 0
+     */
+    // a further token account of the authority for the mint besides its associated one, e.g. for protocols that
+    // refund into an account of their own choosing, proposals can send from either
+    CreateAuxTokenAccount {
+        vault: u8,
+        seed: u64,
+    },
     /*
     User: signer, mutable
     WalletConfig: mutable
     WalletAuth: mutable ["owner", wallet_config.key, user.key]
+    RefundDestination: mutable, either the user or WalletAuthority ["authority", wallet_config.key] - the user if last owner
+    AuditLog: mutable ["audit", wallet_config.key] - not written until CreateAuditLog creates it
     ...all below accounts can be either present or not...
     WalletAuthority ["authority", wallet_config.key]
     TokenProgram
-    pairs of send and receive accounts
+    triples of send, receive and mint accounts
      */
     GiveupOwnership,
     /*
-    User: signer, mutable
-    WalletConfig
-    WalletAuth ["owner", wallet_config.key, user.key]
-    Proposal: signer, mutable
+    User: signer - owners can be PDAs of other programs, e.g. an spl governance treasury, signing through CPI
+    Payer: signer, mutable - funds the proposal and vote count rent, the wallet's proposal deposit and keeper tip, can be the user
+    or WalletAuthority ["authority", wallet_config.key], not a signer, if treasury funded
+    WalletConfig: mutable
+    WalletAuth: mutable ["owner", wallet_config.key, user.key]
+    Proposal: mutable ["proposal", wallet_config.key, wallet_config.proposal_count as u64 le bytes]
     VoteCount: mutable ["votes", wallet_config.key, proposal.key]
     SystemProgram
+    WalletStats: mutable ["wallet-stats", wallet_config.key] - not written until CreateWalletStats creates it
+    Vault ["vault", wallet_config.key, vault] - only if the proposal transfers out of a vault other than 0, its
+    threshold is snapshotted
+    SessionKey ["session", wallet_config.key, owner.key, user.key] - only if user is a session key of the owner, or
+    the user's token account holding the NFT of an NFT-bound seat, the seat's mint taking the place of owner
      */
+    // an urgent proposal is held to the wallet's emergency threshold and lifetime instead of the regular ones,
+    // a draft gets no votes, not even the proposer's, until it is activated, nor does any proposal of a wallet with
+    // gated votes
     CreateProposal {
         proposal: ProposalType,
+        urgent: bool,
+        treasury_funded: bool,
+        draft: bool,
     },
     /*
-    User: signer
-    WalletConfig
-    WalletAuth ["owner", wallet_config.key, user.key]
+    User: signer - can be a PDA signing through CPI, never debited so any other account can pay the fees
+    WalletConfig: mutable
+    WalletAuth: mutable ["owner", wallet_config.key, user.key]
     Proposal
     VoteCount: mutable ["votes", wallet_config.key, proposal.key]
+    SessionKey ["session", wallet_config.key, owner.key, user.key] - only if user is a session key of the owner, or
+    the user's token account holding the NFT of an NFT-bound seat, the seat's mint taking the place of owner
+    GateTokenAccount - associated token account of the owner for the wallet's gate mint, only if votes are gated
      */
     Vote,
     /*
-    Proposer: mutable
-    WalletConfig: mutable(if ChangeLifetime or AddOwner proposal)
+    Closer: signer, mutable, either one of the wallet executors, an owner with the execute role or the proposer of a proposal closed without executing - can be a PDA signing through CPI, an executor receives the proposal's keeper tip
+    CloserWalletAuth ["owner", wallet_config.key, closer.key] - not read if closer is an executor or such a proposer
+    RefundDestination: mutable, either the proposer or WalletAuthority ["authority", wallet_config.key] - the wallet authority if treasury funded
+    WalletConfig: mutable
     Proposal: mutable
     VoteCount: mutable ["votes", wallet_config.key, proposal.key]
+    AuditLog: mutable ["audit", wallet_config.key] - not written until CreateAuditLog creates it
+    WalletAuthority: mutable ["authority", wallet_config.key] - receives the deposit of a proposal that expired unvoted
+    WalletStats: mutable ["wallet-stats", wallet_config.key] - not written until CreateWalletStats creates it
+    CallbackProgram - the wallet's callback program, only if one is set
+    PriceFeed - the feed of the proposal's price condition, only if it has one
     ...rest of the accounts vary depending on the proposal type and only required if proposal is still valid and got majority votes...
     ...for Transfer
+    SendAccount: mutable - ["aux-token", wallet_config.key, wallet_authority.key, token_mint, source as u64 le bytes]
+    if source is present in proposal
+    ReceiveAccount: mutable, a system account if unwrap is set
+    WalletAuthority ["authority", wallet_config.key] or ["authority", wallet_config.key, vault] if vault is not 0,
+    mutable if unwrap is set
+    TokenProgram
+    TreasuryStats: mutable ["stats", wallet_config.key, token_mint] - spend caps are only checked once set
+    Blacklist ["blacklist", wallet_config.key] - checked against the receive account and the owner of a token account
+    Mint, the token_mint present in proposal - its decimals are checked by the transfer
+    Vault ["vault", wallet_config.key, vault] - only if vault is not 0
+    UnwrapAccount: mutable ["unwrap", wallet_config.key, proposal.sequence as u64 le bytes] - only if unwrap is set
+    NativeMint - only if unwrap is set
+    SystemProgram - only if unwrap is set
+    ...for TransferPercent the accounts of Transfer without the unwrap ones
+    ...for SplitPayment
     SendAccount: mutable
-    ReceiveAccount: mutable
-    WalletAuthority ["authority", wallet_config.key]
+    WalletAuthority ["authority", wallet_config.key] or ["authority", wallet_config.key, vault] if vault is not 0
     TokenProgram
+    TreasuryStats: mutable ["stats", wallet_config.key, token_mint] - checked against the total of the payments
+    Blacklist ["blacklist", wallet_config.key]
+    Mint, the token_mint present in proposal - its decimals are checked by the transfers
+    Vault ["vault", wallet_config.key, vault] - only if vault is not 0
+    ReceiveAccounts: mutable - one for each payment in the order present in proposal
+    ...for TransferToWallet
+    SendAccount: mutable
+    ReceiveAccount: mutable, the associated token account of the recipient for token_mint - created if missing
+    Recipient
+    Mint, the token_mint present in proposal - its decimals are checked by the transfer
+    WalletAuthority ["authority", wallet_config.key] or ["authority", wallet_config.key, vault] if vault is not 0
+    TokenProgram
+    TreasuryStats: mutable ["stats", wallet_config.key, token_mint] - spend caps are only checked once set
+    Blacklist ["blacklist", wallet_config.key] - checked against the recipient
+    Payer: signer, mutable - pays for a missing receive account,
+    WalletAuthority ["authority", wallet_config.key] if treasury_funded
+    SystemProgram
+    AssociatedTokenProgram
+    Vault ["vault", wallet_config.key, vault] - only if vault is not 0
     ...for AddOwner
     Payer: signer, mutable
     WalletAuth: mutable ["owner", wallet_config.key, user.key] user present in proposal
     SystemProgram
+    ...for AddNftOwner
+    Payer: signer, mutable
+    WalletAuth: mutable ["owner", wallet_config.key, mint] mint present in proposal
+    SystemProgram
+    Mint - supply of one, no decimals and no mint or freeze authority
     ...for ChangeLifetime no other accounts required
+    ...for TransferSol
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    ReceiveAccount: mutable
+    SystemProgram
+    TreasuryStats: mutable ["stats", wallet_config.key, default pubkey] - spend caps are only checked once set
+    Blacklist ["blacklist", wallet_config.key]
+    ...for TreasuryWithdraw
+    Treasury: mutable ["treasury", wallet_config.key]
+    ReceiveAccount: mutable
+    SystemProgram
+    TreasuryStats: mutable ["stats", wallet_config.key, default pubkey] - the caps are shared with TransferSol
+    Blacklist ["blacklist", wallet_config.key]
+    ...for CloseWallet
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    Destination: mutable, the destination present in proposal
+    SystemProgram
+    TokenProgram
+    Treasury: mutable ["treasury", wallet_config.key]
+    Registry: mutable ["registry", wallet_config.key] - closed if it was created
+    Blacklist: mutable ["blacklist", wallet_config.key] - closed if it was created
+    WalletAuths: mutable - one for every current owner
+    the wallet's vaults, templates, treasury stats, allowances and recurring swaps: mutable - every one still open,
+    each vault followed by its VaultAuthority ["authority", wallet_config.key, vault.index], mutable
+    triples of send, receive and mint accounts, send accounts owned by the wallet authority or the authority of a
+    vault passed above, receive accounts owned by the destination
+    escrows, streams, claims and vestings have to be closed first, the audit log and wallet stats are closed if created
+    ...for SetRecovery, SetExecutors, SetMaxOpenProposals, SetProposalDeposit, SetKeeperTip and Unfreeze no other accounts required
+    ...for SetRoles
+    WalletAuth: mutable ["owner", wallet_config.key, user.key] user present in proposal
+    ...for SetAllowance
+    Payer: signer, mutable
+    Allowance: mutable ["allowance", wallet_config.key, user.key, token_mint.key] user and mint present in proposal
+    SystemProgram
+    ...for CreateTemplate
+    Payer: signer, mutable
+    Template: mutable ["template", wallet_config.key, id as u64 le bytes] id present in proposal
+    SystemProgram
+    ...for DeleteTemplate
+    Template: mutable ["template", wallet_config.key, id as u64 le bytes] id present in proposal
+    ...for CreateVault
+    Payer: signer, mutable
+    Vault: mutable ["vault", wallet_config.key, index] index present in proposal
+    SystemProgram
+    ...for EscrowTransfer
+    Payer: signer, mutable
+    SendAccount: mutable
+    Escrow: mutable ["escrow", wallet_config.key, proposal.sequence as u64 le bytes]
+    EscrowTokenAccount: mutable, associated token account of the escrow
+    Mint
+    WalletAuthority ["authority", wallet_config.key]
+    SystemProgram
+    TokenProgram
+    AssociatedTokenProgram
+    ...for CancelEscrow
+    Escrow: mutable ["escrow", wallet_config.key, sequence as u64 le bytes] sequence present in proposal
+    EscrowTokenAccount: mutable
+    ReceiveAccount: mutable, owned by the wallet authority
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    TokenProgram
+    Mint, the mint of the escrow - its decimals are checked by the transfer
+    ...for CreateVesting
+    Payer: signer, mutable
+    SendAccount: mutable
+    Vesting: mutable ["vesting", wallet_config.key, proposal.sequence as u64 le bytes]
+    VestingTokenAccount: mutable, associated token account of the vesting
+    Mint
+    WalletAuthority ["authority", wallet_config.key]
+    SystemProgram
+    TokenProgram
+    AssociatedTokenProgram
+    ...for CreateStream
+    Payer: signer, mutable
+    Stream: mutable ["stream", wallet_config.key, proposal.sequence as u64 le bytes]
+    SystemProgram
+    ...for CancelStream
+    Stream: mutable ["stream", wallet_config.key, sequence as u64 le bytes] sequence present in proposal
+    ...for ClaimableTransfer
+    Payer: signer, mutable
+    SendAccount: mutable
+    Claim: mutable ["claim", wallet_config.key, proposal.sequence as u64 le bytes]
+    ClaimTokenAccount: mutable, associated token account of the claim
+    Mint
+    WalletAuthority ["authority", wallet_config.key]
+    SystemProgram
+    TokenProgram
+    AssociatedTokenProgram
+    ...for CreateLookupTable, recent_slot must still be in the SlotHashes sysvar when executed
+    Payer: signer, mutable
+    LookupTable: mutable [wallet_authority.key, recent_slot as u64 le bytes] of the address lookup table program
+    WalletAuthority ["authority", wallet_config.key]
+    SystemProgram
+    AddressLookupTableProgram
+    ...for ExtendLookupTable
+    Payer: signer, mutable
+    LookupTable: mutable, the table present in proposal
+    WalletAuthority ["authority", wallet_config.key]
+    SystemProgram
+    AddressLookupTableProgram
+    ...for FreezeLookupTable
+    LookupTable: mutable, the table present in proposal
+    WalletAuthority ["authority", wallet_config.key]
+    AddressLookupTableProgram
+    ...for StakePoolDeposit
+    StakePool: mutable, the stake pool present in proposal
+    StakePoolWithdrawAuthority
+    ReserveStake: mutable
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    PoolTokenAccount: mutable, owned by the wallet authority
+    ManagerFeeAccount: mutable
+    PoolMint: mutable
+    SystemProgram
+    TokenProgram
+    StakePoolProgram
+    ...for StakePoolWithdraw
+    StakePool: mutable, the stake pool present in proposal
+    StakePoolWithdrawAuthority
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    PoolTokenAccount: mutable, owned by the wallet authority
+    ReserveStake: mutable
+    ManagerFeeAccount: mutable
+    PoolMint: mutable
+    ClockSysvar
+    StakeHistorySysvar
+    StakeProgram
+    TokenProgram
+    StakePoolProgram
+    ...for SplitStake
+    Payer: signer, mutable - pays the rent of the split stake account
+    StakeAccount: mutable, the stake account present in proposal
+    SplitStake: mutable ["split-stake", wallet_config.key, proposal.sequence as u64 le bytes]
+    WalletAuthority ["authority", wallet_config.key]
+    SystemProgram
+    StakeProgram
+    ...for MergeStake
+    Destination: mutable, the destination present in proposal
+    Source: mutable, the source present in proposal
+    WalletAuthority ["authority", wallet_config.key]
+    ClockSysvar
+    StakeHistorySysvar
+    StakeProgram
+    ...for RegisterProgram
+    Payer: signer, mutable - pays for the registry the first time a program is registered
+    ProgramRegistry: mutable ["registry", wallet_config.key]
+    SystemProgram
+    ...for UnregisterProgram
+    ProgramRegistry: mutable ["registry", wallet_config.key]
+    ...for ExecuteInstruction, program can be this program for CreateProposal, Vote, VoteMany and CloseProposal of
+    another wallet the wallet authority owns, the registry is not checked then
+    ProgramRegistry ["registry", wallet_config.key]
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    Program, the program present in proposal
+    accounts of the instruction in the order present in proposal
+    ...for FreezeAccount and ThawAccount
+    TokenAccount: mutable, the account present in proposal
+    Mint, the mint present in proposal
+    WalletAuthority ["authority", wallet_config.key]
+    TokenProgram
+    ...for CreateTokenMultisig
+    Payer: signer, mutable
+    TokenMultisig: mutable ["token-multisig", wallet_config.key, proposal.sequence as u64 le bytes]
+    SystemProgram
+    TokenProgram
+    signers in the order present in proposal
+    ...for MultisigTransfer
+    SendAccount: mutable, owned by the multisig
+    ReceiveAccount: mutable
+    TokenMultisig, the multisig present in proposal
+    WalletAuthority ["authority", wallet_config.key]
+    TokenProgram
+    Mint, the token_mint present in proposal - its decimals are checked by the transfer
+    other signers of the multisig: signer - as many as its threshold needs besides the wallet authority
+    ...for ChangeRotationDelay, SetEmergencyPolicy, SetThresholdMode, SetQuorum, SetVoteGate, SetExecutionCallback and
+    SetOptimisticDelay
+    no other accounts required
+    ...for CloseMint, SetTransferFee, SetInterestRate and SetDefaultAccountState
+    Mint: mutable, the mint present in proposal
+    WalletAuthority: mutable ["authority", wallet_config.key] - receives the rent of a closed mint
+    Token2022Program
+    ...for UpdateMetadata and TransferUpdateAuthority
+    Metadata: mutable, the metadata present in proposal
+    WalletAuthority ["authority", wallet_config.key]
+    TokenMetadataProgram
+    ...for SetAndVerifyCollection
+    Metadata: mutable, the metadata present in proposal
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    TokenMetadataProgram
+    CollectionMint, the collection mint present in proposal
+    CollectionMetadata: mutable if sized ["metadata", token_metadata_program.key, collection_mint]
+    CollectionMasterEdition ["metadata", token_metadata_program.key, collection_mint, "edition"]
+    ...for TransferDomain and UpdateDomainRecord
+    NameAccount: mutable, the name account present in proposal
+    WalletAuthority ["authority", wallet_config.key]
+    NameServiceProgram
+    ...for SetSpendLimit
+    Payer: signer, mutable
+    TreasuryStats: mutable ["stats", wallet_config.key, mint]
+    SystemProgram
+    ...for BlacklistAddress
+    Payer: signer, mutable - pays for the blacklist the first time an address is added
+    Blacklist: mutable ["blacklist", wallet_config.key]
+    SystemProgram
+    ...for UnblacklistAddress
+    Blacklist: mutable ["blacklist", wallet_config.key]
+    ...for SetMandatoryApprovers
+    WalletAuths ["owner", wallet_config.key, approver] - one for each approver, in the order present in proposal
+    ...for RemoveInactiveOwners
+    WalletAuthority: mutable ["authority", wallet_config.key] - receives the rent of the closed wallet auths
+    WalletAuths: mutable ["owner", wallet_config.key, owner] - one for each owner, in the order present in proposal
+    ...for ChangeOwnerKey
+    Payer: signer, mutable
+    OldWalletAuth: mutable ["owner", wallet_config.key, old_owner] - closed, rent goes to the payer
+    NewWalletAuth: mutable ["owner", wallet_config.key, new_owner]
+    SystemProgram
+    ...for CreateRecurringSwap
+    Payer: signer, mutable
+    RecurringSwap: mutable ["swap", wallet_config.key, proposal.sequence as u64 le bytes]
+    SystemProgram
+    ...for CancelRecurringSwap
+    RecurringSwap: mutable ["swap", wallet_config.key, sequence as u64 le bytes] sequence present in proposal
      */
     CloseProposal,
+    /*
+    Depositor: signer, mutable
+    WalletConfig: mutable
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    SystemProgram
+     */
+    DepositSol {
+        amount: u64,
+    },
+    /*
+    Beneficiary: signer, mutable
+    WalletConfig: mutable
+    WalletAuth: mutable ["owner", wallet_config.key, beneficiary.key]
+    SystemProgram
+    AuditLog: mutable ["audit", wallet_config.key] - not written until CreateAuditLog creates it
+    WalletAuths: mutable - one for every current owner
+     */
+    ClaimRecovery,
+    /*
+    Owner: signer
+    WalletConfig: mutable
+    WalletAuth: mutable ["owner", wallet_config.key, owner.key]
+    Allowance: mutable ["allowance", wallet_config.key, owner.key, mint.key]
+    SendAccount: mutable
+    ReceiveAccount: mutable
+    WalletAuthority ["authority", wallet_config.key]
+    TokenProgram
+    Mint, the mint of the allowance - its decimals are checked by the transfer
+     */
+    SpendAllowance {
+        amount: u64,
+    },
+    /*
+    Owner: signer, mutable
+    WalletConfig
+    WalletAuth ["owner", wallet_config.key, owner.key]
+    SessionKey: mutable ["session", wallet_config.key, owner.key, session_key]
+    SystemProgram
+     */
+    RegisterSessionKey {
+        session_key: Pubkey,
+        expires_at: i64,
+        scope: u8,
+    },
+    /*
+    Owner: signer, mutable
+    WalletConfig
+    SessionKey: mutable ["session", wallet_config.key, owner.key, session_key]
+     */
+    RevokeSessionKey,
+    /*
+    Template ["template", wallet_config.key, template.id as u64 le bytes]
+    ...followed by the same accounts as CreateProposal...
+     */
+    CreateProposalFromTemplate,
+    /*
+    WalletConfig: mutable - the escrow is no longer counted as open
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    Escrow: mutable ["escrow", wallet_config.key, escrow.sequence as u64 le bytes]
+    EscrowTokenAccount: mutable
+    ReceiveAccount: mutable, the receive account present in escrow
+    TokenProgram
+    Mint, the mint of the escrow - its decimals are checked by the transfer
+     */
+    ReleaseEscrow,
+    /*
+    WalletConfig: mutable - a paid out stream is no longer counted as open
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    Stream: mutable ["stream", wallet_config.key, stream.sequence as u64 le bytes]
+    SendAccount: mutable
+    ReceiveAccount: mutable, the receive account present in stream
+    TokenProgram
+    Mint, the mint of the stream - its decimals are checked by the transfer
+     */
+    WithdrawStream,
+    /*
+    Recipient: signer
+    WalletConfig: mutable - the claim is no longer counted as open
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    Claim: mutable ["claim", wallet_config.key, claim.sequence as u64 le bytes]
+    ClaimTokenAccount: mutable
+    ReceiveAccount: mutable
+    TokenProgram
+    Mint, the mint of the claim - its decimals are checked by the transfer
+     */
+    WithdrawClaim,
+    /*
+    WalletConfig: mutable - the claim is no longer counted as open
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    Claim: mutable ["claim", wallet_config.key, claim.sequence as u64 le bytes]
+    ClaimTokenAccount: mutable
+    ReceiveAccount: mutable, owned by the wallet authority
+    TokenProgram
+    Mint, the mint of the claim - its decimals are checked by the transfer
+     */
+    ReclaimExpiredClaim,
+    /*
+    WalletConfig
+    WalletAuthority ["authority", wallet_config.key]
+     */
+    GetSolBalance,
+    /*
+    WalletConfig: mutable
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    RecurringSwap: mutable ["swap", wallet_config.key, swap.sequence as u64 le bytes]
+    ProgramRegistry ["registry", wallet_config.key]
+    InputAccount: mutable, owned by the wallet authority
+    OutputAccount: mutable, owned by the wallet authority
+    SwapProgram, the swap program present in the recurring swap
+    accounts of the swap instruction in order, the wallet authority is signed for
+     */
+    ExecuteRecurringSwap {
+        data: Vec<u8>,
+    },
+    /*
+    WalletConfig
+     */
+    GetWalletInfo,
+    /*
+    WalletConfig
+    Proposal ["proposal", wallet_config.key, proposal.sequence as u64 le bytes]
+    VoteCount ["votes", wallet_config.key, proposal.key]
+     */
+    GetProposalStatus,
+    /*
+    WalletConfig
+    Proposal ["proposal", wallet_config.key, proposal.sequence as u64 le bytes]
+    VoteCount ["votes", wallet_config.key, proposal.key]
+    PriceFeed - the feed of the proposal's price condition, only if it has one
+    ...for Transfer, TransferPercent, TransferToWallet, SplitPayment, TransferSol and TreasuryWithdraw, the same accounts
+    CloseProposal takes for them
+     */
+    ValidateProposal,
+    /*
+    User: signer - can be a PDA signing through CPI
+    WalletConfig: mutable
+    WalletAuth: mutable ["owner", wallet_config.key, user.key]
+    Proposal, VoteCount: mutable ["votes", wallet_config.key, proposal.key] - one pair for each of the proposals
+    SessionKey ["session", wallet_config.key, owner.key, user.key] - only if user is a session key of the owner, or
+    the user's token account holding the NFT of an NFT-bound seat, the seat's mint taking the place of owner
+    GateTokenAccount - associated token account of the owner for the wallet's gate mint, only if votes are gated
+     */
+    VoteMany {
+        proposals: u8,
+    },
+    /*
+    WalletConfig: mutable
+    Proposal
+    VoteCount: mutable ["votes", wallet_config.key, proposal.key]
+    InstructionsSysvar
+    WalletAuths: mutable ["owner", wallet_config.key, owner] - one for each owner whose signature over the
+    proposal digest is verified by an ed25519 program instruction placed before this one, each followed by the
+    owner's associated token account for the gate mint if votes are gated
+     */
+    VoteWithSignatures,
+    /*
+    WalletConfig: mutable
+    AuditLog: mutable ["audit", wallet_config.key] - not written until CreateAuditLog creates it
+     */
+    ApplyPendingChange,
+    /*
+    Owner: signer
+    WalletConfig
+    WalletAuth ["owner", wallet_config.key, owner.key]
+    WalletAuthority: mutable ["authority", wallet_config.key]
+    TokenProgram
+    SourceAccount: mutable, owned by the wallet authority
+    AssociatedTokenAccount: mutable, of the wallet authority for the source account's mint
+    Mint, the source account's mint - its decimals are checked by the transfer
+    ...more source, associated token account and mint triples
+     */
+    SweepTokenAccounts,
+    /*
+    Owner: signer - the key being rotated out, a session key cannot request a rotation
+    Payer: signer, mutable
+    WalletConfig: mutable
+    WalletAuth ["owner", wallet_config.key, owner.key]
+    KeyRotation: mutable ["rotation", wallet_config.key, owner.key]
+    SystemProgram
+     */
+    RequestKeyRotation {
+        new_owner: Pubkey,
+    },
+    /*
+    Owner: signer, any owner with the vote role - including the one rotating
+    WalletConfig: mutable
+    WalletAuth ["owner", wallet_config.key, owner.key]
+    KeyRotation: mutable ["rotation", wallet_config.key, old_owner]
+    RefundDestination: mutable, the payer of the rotation request
+     */
+    VetoKeyRotation,
+    /*
+    Payer: signer, mutable
+    WalletConfig: mutable
+    OldWalletAuth: mutable ["owner", wallet_config.key, old_owner]
+    NewWalletAuth: mutable ["owner", wallet_config.key, new_owner]
+    KeyRotation: mutable ["rotation", wallet_config.key, old_owner]
+    RefundDestination: mutable, the payer of the rotation request
+    SystemProgram
+    AuditLog: mutable ["audit", wallet_config.key] - not written until CreateAuditLog creates it
+     */
+    CompleteKeyRotation,
+    /*
+    Proposer: signer, the proposer of the proposal
+    WalletConfig: mutable
+    Proposal
+    VoteCount: mutable ["votes", wallet_config.key, proposal.key]
+    InstructionsSysvar
+    WalletAuths: mutable ["owner", wallet_config.key, owner] - one for each expiration, in the same order, whose
+    signature over the vote permit is verified by an ed25519 program instruction placed before this one, each
+    followed by the owner's associated token account for the gate mint if votes are gated
+     */
+    SubmitVotePermit {
+        expirations: Vec<i64>,
+    },
+    /*
+    Payer: signer, mutable
+    WalletConfig
+    AuditLog: mutable ["audit", wallet_config.key]
+    SystemProgram
+     */
+    CreateAuditLog,
+    /*
+    Payer: signer, mutable
+    WalletConfig
+    WalletStats: mutable ["wallet-stats", wallet_config.key]
+    SystemProgram
+     */
+    CreateWalletStats,
+    /*
+    Owner: signer
+    WalletConfig
+    WalletAuth ["owner", wallet_config.key, owner.key]
+    WalletAuthority: mutable ["authority", wallet_config.key] - receives the rent of the closed accounts
+    TokenProgram
+    Vault ["vault", wallet_config.key, vault] - only if vault is not 0
+    VaultAuthority ["authority", wallet_config.key, vault] - only if vault is not 0
+    TokenAccounts: mutable - associated token accounts of the vault authority with a zero balance
+     */
+    CloseEmptyTokenAccounts {
+        vault: u8,
+    },
+    /*
+    Depositor: signer, mutable
+    WalletConfig: mutable
+    Treasury: mutable ["treasury", wallet_config.key]
+    SystemProgram
+     */
+    DepositTreasury {
+        amount: u64,
+    },
+    /*
+    WalletConfig: mutable - a released vesting is no longer counted as open
+    WalletAuthority: mutable ["authority", wallet_config.key] - receives the rent once everything is released
+    Vesting: mutable ["vesting", wallet_config.key, vesting.sequence as u64 le bytes]
+    VestingTokenAccount: mutable, associated token account of the vesting
+    ReceiveAccount: mutable, the receive account present in vesting
+    TokenProgram
+    Mint, the mint of the vesting - its decimals are checked by the transfer
+     */
+    ReleaseVesting,
+    /*
+    Owner: signer
+    WalletConfig: mutable
+    WalletAuth ["owner", wallet_config.key, owner.key]
+    AuditLog: mutable ["audit", wallet_config.key] - not written until CreateAuditLog creates it
+     */
+    EmergencyFreeze,
+    /*
+    Proposer: signer
+    Payer: signer, mutable - tops up the rent if the proposal grows, a shrinking edit leaves the surplus in the
+    proposal until it is closed
+    WalletConfig: mutable
+    Proposal: mutable ["proposal", wallet_config.key, proposal.sequence as u64 le bytes]
+    SystemProgram
+     */
+    EditDraft {
+        proposal: ProposalType,
+    },
+    /*
+    Proposer: signer
+    WalletConfig: mutable
+    WalletAuth: mutable ["owner", wallet_config.key, proposer.key]
+    Proposal: mutable ["proposal", wallet_config.key, proposal.sequence as u64 le bytes]
+    VoteCount: mutable ["votes", wallet_config.key, proposal.key]
+    Vault ["vault", wallet_config.key, vault] - only if the draft transfers out of a vault other than 0
+    SeatHolding - the proposer's token account holding the NFT of an NFT-bound seat, the seat's mint taking the place
+    of proposer.key, only if the draft came from such a seat
+     */
+    // the lifetime, threshold and mandatory approvers are taken from the wallet, or the vault, as of activation
+    ActivateProposal,
+    /*
+    Proposer: signer
+    Payer: signer, mutable - tops up the rent if the proposal grows, a shrinking amendment leaves the surplus in the
+    proposal until it is closed
+    WalletConfig: mutable
+    Proposal: mutable ["proposal", wallet_config.key, proposal.sequence as u64 le bytes]
+    VoteCount: mutable ["votes", wallet_config.key, proposal.key]
+    SystemProgram
+    Vault ["vault", wallet_config.key, vault] - only if the amended proposal transfers out of a vault other than 0,
+    its threshold is snapshotted if the amendment changes the vault
+     */
+    // only while no vote beyond the proposer's came in, the lifetime restarts with the same length
+    AmendProposal {
+        proposal: ProposalType,
+    },
+    /*
+    Owner: signer
+    WalletConfig: mutable
+    WalletAuth ["owner", wallet_config.key, owner.key]
+    Proposal ["proposal", wallet_config.key, proposal.sequence as u64 le bytes]
+    VoteCount: mutable ["votes", wallet_config.key, proposal.key]
+     */
+    // pushes the expiry of an active proposal out by its lifetime once, for an owner who voted for it once at least
+    // half of the threshold has
+    ExtendProposal,
+    /*
+    User: signer, mutable - an owner of the source wallet, pays for the new accounts
+    SourceWalletConfig
+    SourceWalletAuth ["owner", source_wallet_config.key, user.key]
+    WalletConfig: signer, mutable
+    WalletAuth: mutable ["owner", wallet_config.key, user.key]
+    SystemProgram
+    pairs of SourceWalletAuth ["owner", source_wallet_config.key, owner] and WalletAuth: mutable
+    ["owner", wallet_config.key, owner] - one for every other owner of the source wallet
+     */
+    // the owners keep their ids and roles, the policies are copied while proposals, counters, the pending change
+    // and the freeze start fresh
+    CloneWallet,
+    /*
+    Owner: signer, mutable - pays for the comment
+    WalletConfig: mutable
+    WalletAuth ["owner", wallet_config.key, owner.key]
+    Proposal
+    VoteCount ["votes", wallet_config.key, proposal.key]
+    VoteComment: mutable ["comment", proposal.key, owner.key]
+    SystemProgram
+     */
+    // records why the owner voted for the proposal, usually sent right after their vote, it outlives the proposal
+    CommentVote {
+        comment: Vec<u8>,
+    },
+    /*
+    Proposer: signer
+    WalletConfig: mutable
+    Proposal: mutable ["proposal", wallet_config.key, proposal.sequence as u64 le bytes]
+    VoteCount ["votes", wallet_config.key, proposal.key]
+     */
+    // any time for a draft, for an active proposal only while AmendProposal would be allowed, the revision is bumped
+    // and signed votes cover the hash
+    SetContentHash {
+        content_hash: [u8; 32],
+    },
+    /*
+    Proposer: signer
+    WalletConfig: mutable
+    Proposal: mutable ["proposal", wallet_config.key, proposal.sequence as u64 le bytes]
+    VoteCount ["votes", wallet_config.key, proposal.key]
+     */
+    // same rules as SetContentHash, the default feed removes the condition
+    SetPriceCondition {
+        condition: PriceCondition,
+    },
+    /*
+    Owner: signer
+    WalletConfig: mutable
+    WalletAuth ["owner", wallet_config.key, owner.key]
+    Proposal: mutable
+    VoteCount ["votes", wallet_config.key, proposal.key]
+     */
+    // any owner can object to an optimistic proposal while its challenge period runs, it then needs votes like any
+    // other
+    ObjectProposal,
 }
 
 impl WalletInstruction {
@@ -90,70 +773,1195 @@ impl WalletInstruction {
                 let (&n, rest) = rest
                     .split_first()
                     .ok_or(ProgramError::InvalidInstructionData)?;
-                let proposal_lifetime = i64::deserialize(&mut &rest[..8])?;
+                let proposal_lifetime = i64::from_le_bytes(read_bytes(rest, 0)?);
                 let rest = &rest[8..];
-                if rest.len() == 0 {
-                    Self::CreateWallet {
-                        m,
-                        n,
-                        owners: Vec::new(),
-                        proposal_lifetime,
-                    }
-                } else {
-                    let mut owners = Vec::new();
-                    let (&owner_count, rest) = rest.split_first().unwrap();
+                let mut owners = Vec::new();
+                let mut min_proposal_lifetime = DEFAULT_MIN_PROPOSAL_LIFETIME;
+                let mut max_proposal_lifetime = DEFAULT_MAX_PROPOSAL_LIFETIME;
+                if let Some((&owner_count, rest)) = rest.split_first() {
                     let owner_count = owner_count as usize;
                     let mut count = 0;
                     while count < owner_count {
-                        owners.push(Pubkey::deserialize(&mut &rest[count..count + 32]).unwrap());
+                        owners.push(Pubkey::new_from_array(read_bytes(rest, count)?));
                         count += 32;
                     }
-                    Self::CreateWallet {
-                        m,
-                        n,
-                        owners,
-                        proposal_lifetime,
+                    // lifetime bounds are optional after the owners
+                    if rest.len() > count {
+                        min_proposal_lifetime = i64::from_le_bytes(read_bytes(rest, count)?);
+                        max_proposal_lifetime = i64::from_le_bytes(read_bytes(rest, count + 8)?);
                     }
                 }
+                Self::CreateWallet {
+                    m,
+                    n,
+                    owners,
+                    proposal_lifetime,
+                    min_proposal_lifetime,
+                    max_proposal_lifetime,
+                }
             }
-            1 => Self::CreateTokenAccount,
+            1 => Self::CreateTokenAccount {
+                vault: rest.first().copied().unwrap_or(0),
+            },
             2 => Self::GiveupOwnership,
+            3 => Self::CreateProposal {
+                proposal: Self::unpack_proposal(rest)?,
+                urgent: false,
+                treasury_funded: false,
+                draft: false,
+            },
+            4 => Self::Vote,
+            5 => Self::CloseProposal,
+            6 => {
+                let amount = u64::deserialize(&mut &rest[..])?;
+                Self::DepositSol { amount }
+            }
+            7 => Self::ClaimRecovery,
+            8 => {
+                let amount = u64::deserialize(&mut &rest[..])?;
+                Self::SpendAllowance { amount }
+            }
+            9 => {
+                let session_key = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let expires_at = i64::from_le_bytes(read_bytes(rest, 32)?);
+                let scope = *rest.get(40).ok_or(ProgramError::InvalidInstructionData)?;
+                Self::RegisterSessionKey {
+                    session_key,
+                    expires_at,
+                    scope,
+                }
+            }
+            10 => Self::RevokeSessionKey,
+            11 => Self::CreateProposalFromTemplate,
+            12 => Self::ReleaseEscrow,
+            13 => Self::WithdrawStream,
+            14 => Self::WithdrawClaim,
+            15 => Self::ReclaimExpiredClaim,
+            16 => Self::GetSolBalance,
+            17 => Self::ExecuteRecurringSwap {
+                data: rest.to_vec(),
+            },
+            18 => Self::GetWalletInfo,
+            19 => Self::GetProposalStatus,
+            20 => Self::ValidateProposal,
+            21 => Self::VoteMany {
+                proposals: *rest.first().ok_or(ProgramError::InvalidInstructionData)?,
+            },
+            22 => Self::VoteWithSignatures,
+            23 => Self::ApplyPendingChange,
+            24 => Self::SweepTokenAccounts,
+            25 => Self::RequestKeyRotation {
+                new_owner: Pubkey::new_from_array(read_bytes(rest, 0)?),
+            },
+            26 => Self::VetoKeyRotation,
+            27 => Self::CompleteKeyRotation,
+            28 => {
+                let (&count, rest) = rest
+                    .split_first()
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let expirations = (0..count as usize)
+                    .map(|i| read_bytes(rest, i * 8).map(i64::from_le_bytes))
+                    .collect::<Result<_, _>>()?;
+                Self::SubmitVotePermit { expirations }
+            }
+            29 => Self::CreateProposal {
+                proposal: Self::unpack_proposal(rest)?,
+                urgent: true,
+                treasury_funded: false,
+                draft: false,
+            },
+            30 => Self::CreateAuditLog,
+            // a treasury funded proposal, the urgent flag comes first
+            31 => {
+                let (&urgent, rest) = rest
+                    .split_first()
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                Self::CreateProposal {
+                    proposal: Self::unpack_proposal(rest)?,
+                    urgent: urgent != 0,
+                    treasury_funded: true,
+                    draft: false,
+                }
+            }
+            32 => Self::CreateWalletStats,
+            33 => Self::CloseEmptyTokenAccounts {
+                vault: *rest.first().ok_or(ProgramError::InvalidInstructionData)?,
+            },
+            34 => Self::DepositTreasury {
+                amount: u64::from_le_bytes(read_bytes(rest, 0)?),
+            },
+            35 => Self::ReleaseVesting,
+            36 => Self::EmergencyFreeze,
+            // a draft proposal, the urgent and treasury funded flags come first
+            37 => {
+                let urgent = *rest.first().ok_or(ProgramError::InvalidInstructionData)?;
+                let treasury_funded = *rest.get(1).ok_or(ProgramError::InvalidInstructionData)?;
+                Self::CreateProposal {
+                    proposal: Self::unpack_proposal(&rest[2..])?,
+                    urgent: urgent != 0,
+                    treasury_funded: treasury_funded != 0,
+                    draft: true,
+                }
+            }
+            38 => Self::EditDraft {
+                proposal: Self::unpack_proposal(rest)?,
+            },
+            39 => Self::ActivateProposal,
+            40 => Self::AmendProposal {
+                proposal: Self::unpack_proposal(rest)?,
+            },
+            41 => Self::ExtendProposal,
+            42 => Self::CloneWallet,
+            43 => Self::CommentVote {
+                comment: rest.to_vec(),
+            },
+            44 => Self::SetContentHash {
+                content_hash: read_bytes(rest, 0)?,
+            },
+            45 => Self::CreateAuxTokenAccount {
+                vault: *rest.first().ok_or(ProgramError::InvalidInstructionData)?,
+                seed: u64::from_le_bytes(read_bytes(rest, 1)?),
+            },
+            // feed, the above flag and the threshold
+            46 => Self::SetPriceCondition {
+                condition: PriceCondition {
+                    feed: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                    above: *rest.get(32).ok_or(ProgramError::InvalidInstructionData)? != 0,
+                    threshold: i64::from_le_bytes(read_bytes(rest, 33)?),
+                },
+            },
+            47 => Self::ObjectProposal,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        Ok(res)
+    }
+
+    // inverse of unpack, used by off-chain clients to build instruction data
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::CreateWallet {
+                m,
+                n,
+                owners,
+                proposal_lifetime,
+                min_proposal_lifetime,
+                max_proposal_lifetime,
+            } => {
+                buf.push(0);
+                buf.push(*m);
+                buf.push(*n);
+                buf.extend_from_slice(&proposal_lifetime.to_le_bytes());
+                // the length byte counts the bytes of owner keys that follow
+                buf.push((owners.len() * 32) as u8);
+                for owner in owners.iter() {
+                    buf.extend_from_slice(owner.as_ref());
+                }
+                buf.extend_from_slice(&min_proposal_lifetime.to_le_bytes());
+                buf.extend_from_slice(&max_proposal_lifetime.to_le_bytes());
+            }
+            Self::CreateTokenAccount { vault } => {
+                buf.push(1);
+                buf.push(*vault);
+            }
+            Self::GiveupOwnership => buf.push(2),
+            Self::CreateProposal {
+                proposal,
+                urgent,
+                treasury_funded,
+                draft,
+            } => {
+                if *draft {
+                    buf.push(37);
+                    buf.push(*urgent as u8);
+                    buf.push(*treasury_funded as u8);
+                } else if *treasury_funded {
+                    buf.push(31);
+                    buf.push(*urgent as u8);
+                } else {
+                    buf.push(if *urgent { 29 } else { 3 });
+                }
+                Self::pack_proposal(proposal, &mut buf);
+            }
+            Self::Vote => buf.push(4),
+            Self::CloseProposal => buf.push(5),
+            Self::DepositSol { amount } => {
+                buf.push(6);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::ClaimRecovery => buf.push(7),
+            Self::SpendAllowance { amount } => {
+                buf.push(8);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::RegisterSessionKey {
+                session_key,
+                expires_at,
+                scope,
+            } => {
+                buf.push(9);
+                buf.extend_from_slice(session_key.as_ref());
+                buf.extend_from_slice(&expires_at.to_le_bytes());
+                buf.push(*scope);
+            }
+            Self::RevokeSessionKey => buf.push(10),
+            Self::CreateProposalFromTemplate => buf.push(11),
+            Self::ReleaseEscrow => buf.push(12),
+            Self::WithdrawStream => buf.push(13),
+            Self::WithdrawClaim => buf.push(14),
+            Self::ReclaimExpiredClaim => buf.push(15),
+            Self::GetSolBalance => buf.push(16),
+            Self::ExecuteRecurringSwap { data } => {
+                buf.push(17);
+                buf.extend_from_slice(data);
+            }
+            Self::GetWalletInfo => buf.push(18),
+            Self::GetProposalStatus => buf.push(19),
+            Self::ValidateProposal => buf.push(20),
+            Self::VoteMany { proposals } => {
+                buf.push(21);
+                buf.push(*proposals);
+            }
+            Self::VoteWithSignatures => buf.push(22),
+            Self::ApplyPendingChange => buf.push(23),
+            Self::SweepTokenAccounts => buf.push(24),
+            Self::RequestKeyRotation { new_owner } => {
+                buf.push(25);
+                buf.extend_from_slice(new_owner.as_ref());
+            }
+            Self::VetoKeyRotation => buf.push(26),
+            Self::CompleteKeyRotation => buf.push(27),
+            Self::SubmitVotePermit { expirations } => {
+                buf.push(28);
+                buf.push(expirations.len() as u8);
+                for expires_at in expirations.iter() {
+                    buf.extend_from_slice(&expires_at.to_le_bytes());
+                }
+            }
+            Self::CreateAuditLog => buf.push(30),
+            Self::CreateWalletStats => buf.push(32),
+            Self::CloseEmptyTokenAccounts { vault } => {
+                buf.push(33);
+                buf.push(*vault);
+            }
+            Self::DepositTreasury { amount } => {
+                buf.push(34);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::ReleaseVesting => buf.push(35),
+            Self::EmergencyFreeze => buf.push(36),
+            Self::EditDraft { proposal } => {
+                buf.push(38);
+                Self::pack_proposal(proposal, &mut buf);
+            }
+            Self::ActivateProposal => buf.push(39),
+            Self::AmendProposal { proposal } => {
+                buf.push(40);
+                Self::pack_proposal(proposal, &mut buf);
+            }
+            Self::ExtendProposal => buf.push(41),
+            Self::CloneWallet => buf.push(42),
+            Self::CommentVote { comment } => {
+                buf.push(43);
+                buf.extend_from_slice(comment);
+            }
+            Self::SetContentHash { content_hash } => {
+                buf.push(44);
+                buf.extend_from_slice(content_hash);
+            }
+            Self::CreateAuxTokenAccount { vault, seed } => {
+                buf.push(45);
+                buf.push(*vault);
+                buf.extend_from_slice(&seed.to_le_bytes());
+            }
+            Self::SetPriceCondition { condition } => {
+                buf.push(46);
+                buf.extend_from_slice(condition.feed.as_ref());
+                buf.push(condition.above as u8);
+                buf.extend_from_slice(&condition.threshold.to_le_bytes());
+            }
+            Self::ObjectProposal => buf.push(47),
+        }
+        buf
+    }
+
+    fn unpack_proposal(data: &[u8]) -> Result<ProposalType, ProgramError> {
+        Self::unpack_proposal_at_depth(data, 0)
+    }
+
+    fn unpack_proposal_at_depth(data: &[u8], depth: u8) -> Result<ProposalType, ProgramError> {
+        let (&proposal_type, rest) = data
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let res = match proposal_type {
+            0 => {
+                let token_mint = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let receive_account = Pubkey::new_from_array(read_bytes(rest, 32)?);
+                let amount = u64::from_be_bytes(read_bytes(rest, 64)?);
+                let vault = rest.get(72).copied().unwrap_or(0);
+                let unwrap = rest.get(73).copied().unwrap_or(0) != 0;
+                let source = read_bytes(rest, 74).ok().map(u64::from_be_bytes);
+                ProposalType::Transfer {
+                    token_mint,
+                    receive_account,
+                    amount,
+                    vault,
+                    unwrap,
+                    source,
+                }
+            }
+            1 => {
+                let user = Pubkey::deserialize(&mut &rest[..])?;
+                ProposalType::AddOwner { user }
+            }
+            2 => {
+                let duration = i64::from_be_bytes(read_bytes(rest, 0)?);
+                ProposalType::ChangeProposalLifetime { duration }
+            }
             3 => {
-                let (&proposal_type, rest) = rest
+                let receive_account = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let amount = u64::from_be_bytes(read_bytes(rest, 32)?);
+                ProposalType::TransferSol {
+                    receive_account,
+                    amount,
+                }
+            }
+            4 => {
+                let destination = Pubkey::deserialize(&mut &rest[..])?;
+                ProposalType::CloseWallet { destination }
+            }
+            5 => {
+                let beneficiary = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let inactivity_period = i64::from_be_bytes(read_bytes(rest, 32)?);
+                ProposalType::SetRecovery {
+                    beneficiary,
+                    inactivity_period,
+                }
+            }
+            6 => {
+                let mut executors = [Pubkey::default(); MAX_EXECUTORS];
+                for (i, executor) in executors.iter_mut().enumerate() {
+                    *executor = Pubkey::new_from_array(read_bytes(rest, i * 32)?);
+                }
+                ProposalType::SetExecutors { executors }
+            }
+            7 => {
+                let user = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let roles = *rest.get(32).ok_or(ProgramError::InvalidInstructionData)?;
+                ProposalType::SetRoles { user, roles }
+            }
+            8 => {
+                let user = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let token_mint = Pubkey::new_from_array(read_bytes(rest, 32)?);
+                let amount = u64::from_be_bytes(read_bytes(rest, 64)?);
+                ProposalType::SetAllowance {
+                    user,
+                    token_mint,
+                    amount,
+                }
+            }
+            9 => {
+                if depth == MAX_PROPOSAL_DEPTH {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+                let id = u64::from_be_bytes(read_bytes(rest, 0)?);
+                let template = Self::unpack_proposal_at_depth(&rest[8..], depth + 1)?;
+                ProposalType::CreateTemplate {
+                    id,
+                    template: TemplateProposal(Box::new(template)),
+                }
+            }
+            10 => {
+                let id = u64::from_be_bytes(read_bytes(rest, 0)?);
+                ProposalType::DeleteTemplate { id }
+            }
+            11 => {
+                let (&index, rest) = rest
                     .split_first()
                     .ok_or(ProgramError::InvalidInstructionData)?;
-                match proposal_type {
-                    0 => {
-                        let token_mint = Pubkey::deserialize(&mut &rest[0..32])?;
-                        let receive_account = Pubkey::deserialize(&mut &rest[32..64])?;
-                        let amount = u64::from_be_bytes((&rest[64..]).try_into().unwrap());
-                        Self::CreateProposal {
-                            proposal: ProposalType::Transfer {
-                                token_mint,
-                                receive_account,
-                                amount,
-                            },
-                        }
-                    }
-                    1 => {
-                        let user = Pubkey::deserialize(&mut &rest[..])?;
-                        Self::CreateProposal {
-                            proposal: ProposalType::AddOwner { user },
-                        }
-                    }
-                    2 => {
-                        let duration = i64::from_be_bytes(rest.try_into().unwrap());
-                        Self::CreateProposal {
-                            proposal: ProposalType::ChangeProposalLifetime { duration },
-                        }
-                    }
-                    _ => return Err(ProgramError::InvalidInstructionData),
+                let (&m, rest) = rest
+                    .split_first()
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                let (&n, _) = rest
+                    .split_first()
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                ProposalType::CreateVault { index, m, n }
+            }
+            12 => {
+                let token_mint = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let receive_account = Pubkey::new_from_array(read_bytes(rest, 32)?);
+                let amount = u64::from_be_bytes(read_bytes(rest, 64)?);
+                let unlock_time = i64::from_be_bytes(read_bytes(rest, 72)?);
+                ProposalType::EscrowTransfer {
+                    token_mint,
+                    receive_account,
+                    amount,
+                    unlock_time,
+                }
+            }
+            13 => {
+                let sequence = u64::from_be_bytes(read_bytes(rest, 0)?);
+                ProposalType::CancelEscrow { sequence }
+            }
+            14 => {
+                let token_mint = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let receive_account = Pubkey::new_from_array(read_bytes(rest, 32)?);
+                let rate = u64::from_be_bytes(read_bytes(rest, 64)?);
+                let duration = i64::from_be_bytes(read_bytes(rest, 72)?);
+                ProposalType::CreateStream {
+                    token_mint,
+                    receive_account,
+                    rate,
+                    duration,
+                }
+            }
+            15 => {
+                let sequence = u64::from_be_bytes(read_bytes(rest, 0)?);
+                ProposalType::CancelStream { sequence }
+            }
+            16 => {
+                let token_mint = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let recipient = Pubkey::new_from_array(read_bytes(rest, 32)?);
+                let amount = u64::from_be_bytes(read_bytes(rest, 64)?);
+                let claim_period = i64::from_be_bytes(read_bytes(rest, 72)?);
+                ProposalType::ClaimableTransfer {
+                    token_mint,
+                    recipient,
+                    amount,
+                    claim_period,
+                }
+            }
+            17 => {
+                let recent_slot = u64::from_be_bytes(read_bytes(rest, 0)?);
+                ProposalType::CreateLookupTable { recent_slot }
+            }
+            18 => {
+                let table = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                // every 32 bytes after the table is one address to add
+                let addresses = &rest[32..];
+                if addresses.len() % 32 != 0 {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+                let addresses = addresses
+                    .chunks_exact(32)
+                    .map(|address| Pubkey::new_from_array(address.try_into().unwrap()))
+                    .collect();
+                ProposalType::ExtendLookupTable { table, addresses }
+            }
+            19 => {
+                let table = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                ProposalType::FreezeLookupTable { table }
+            }
+            20 => {
+                let stake_pool = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let amount = u64::from_be_bytes(read_bytes(rest, 32)?);
+                ProposalType::StakePoolDeposit { stake_pool, amount }
+            }
+            21 => {
+                let stake_pool = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let pool_tokens = u64::from_be_bytes(read_bytes(rest, 32)?);
+                ProposalType::StakePoolWithdraw {
+                    stake_pool,
+                    pool_tokens,
+                }
+            }
+            22 => {
+                let program = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                ProposalType::RegisterProgram { program }
+            }
+            23 => {
+                let program = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                ProposalType::UnregisterProgram { program }
+            }
+            24 => {
+                // program, account count, 34 bytes per account and the instruction data after them
+                let program = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let [account_count] = read_bytes(rest, 32)?;
+                let mut accounts = Vec::new();
+                let mut offset = 33;
+                for _ in 0..account_count {
+                    let [is_signer, is_writable] = read_bytes(rest, offset + 32)?;
+                    accounts.push(ProposalAccount {
+                        pubkey: Pubkey::new_from_array(read_bytes(rest, offset)?),
+                        is_signer: is_signer != 0,
+                        is_writable: is_writable != 0,
+                    });
+                    offset += 34;
+                }
+                ProposalType::ExecuteInstruction {
+                    program,
+                    accounts,
+                    data: rest[offset..].to_vec(),
+                }
+            }
+            25 => {
+                let swap_program = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let input_mint = Pubkey::new_from_array(read_bytes(rest, 32)?);
+                let output_mint = Pubkey::new_from_array(read_bytes(rest, 64)?);
+                let amount = u64::from_be_bytes(read_bytes(rest, 96)?);
+                let min_output = u64::from_be_bytes(read_bytes(rest, 104)?);
+                let interval = i64::from_be_bytes(read_bytes(rest, 112)?);
+                let swaps = u64::from_be_bytes(read_bytes(rest, 120)?);
+                ProposalType::CreateRecurringSwap {
+                    swap_program,
+                    input_mint,
+                    output_mint,
+                    amount,
+                    min_output,
+                    interval,
+                    swaps,
+                }
+            }
+            26 => {
+                let sequence = u64::from_be_bytes(read_bytes(rest, 0)?);
+                ProposalType::CancelRecurringSwap { sequence }
+            }
+            27 => {
+                let [m, n] = read_bytes(rest, 0)?;
+                ProposalType::ChangeThreshold { m, n }
+            }
+            28 => {
+                let delay = i64::from_be_bytes(read_bytes(rest, 0)?);
+                ProposalType::ChangeTimelock { delay }
+            }
+            29 => ProposalType::CancelPendingChange,
+            30 => {
+                let min = i64::from_be_bytes(read_bytes(rest, 0)?);
+                let max = i64::from_be_bytes(read_bytes(rest, 8)?);
+                ProposalType::SetLifetimeBounds { min, max }
+            }
+            31 => {
+                let mint = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let account = Pubkey::new_from_array(read_bytes(rest, 32)?);
+                ProposalType::FreezeAccount { mint, account }
+            }
+            32 => {
+                let mint = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let account = Pubkey::new_from_array(read_bytes(rest, 32)?);
+                ProposalType::ThawAccount { mint, account }
+            }
+            33 => {
+                // threshold followed by the signer keys
+                let [m] = read_bytes(rest, 0)?;
+                let signers = rest[1..]
+                    .chunks_exact(32)
+                    .map(|signer| Pubkey::new_from_array(signer.try_into().unwrap()))
+                    .collect();
+                ProposalType::CreateTokenMultisig { m, signers }
+            }
+            34 => {
+                let multisig = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let token_mint = Pubkey::new_from_array(read_bytes(rest, 32)?);
+                let receive_account = Pubkey::new_from_array(read_bytes(rest, 64)?);
+                let amount = u64::from_be_bytes(read_bytes(rest, 96)?);
+                ProposalType::MultisigTransfer {
+                    multisig,
+                    token_mint,
+                    receive_account,
+                    amount,
+                }
+            }
+            35 => {
+                let old_owner = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let new_owner = Pubkey::new_from_array(read_bytes(rest, 32)?);
+                ProposalType::ChangeOwnerKey {
+                    old_owner,
+                    new_owner,
+                }
+            }
+            36 => {
+                let delay = i64::from_be_bytes(read_bytes(rest, 0)?);
+                ProposalType::ChangeRotationDelay { delay }
+            }
+            37 => {
+                let [m] = read_bytes(rest, 0)?;
+                let lifetime = i64::from_be_bytes(read_bytes(rest, 1)?);
+                ProposalType::SetEmergencyPolicy { m, lifetime }
+            }
+            38 => {
+                let mint = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let daily_cap = u64::from_be_bytes(read_bytes(rest, 32)?);
+                let weekly_cap = u64::from_be_bytes(read_bytes(rest, 40)?);
+                ProposalType::SetSpendLimit {
+                    mint,
+                    daily_cap,
+                    weekly_cap,
+                }
+            }
+            39 => {
+                let address = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                ProposalType::BlacklistAddress { address }
+            }
+            40 => {
+                let address = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                ProposalType::UnblacklistAddress { address }
+            }
+            41 => {
+                let approvers = rest
+                    .chunks_exact(32)
+                    .map(|approver| Pubkey::new_from_array(approver.try_into().unwrap()))
+                    .collect();
+                ProposalType::SetMandatoryApprovers { approvers }
+            }
+            42 => ProposalType::SetMaxOpenProposals {
+                max: u16::from_be_bytes(read_bytes(rest, 0)?),
+            },
+            43 => ProposalType::SetProposalDeposit {
+                amount: u64::from_be_bytes(read_bytes(rest, 0)?),
+            },
+            44 => ProposalType::SetKeeperTip {
+                amount: u64::from_be_bytes(read_bytes(rest, 0)?),
+            },
+            45 => ProposalType::TransferToWallet {
+                token_mint: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                recipient: Pubkey::new_from_array(read_bytes(rest, 32)?),
+                amount: u64::from_be_bytes(read_bytes(rest, 64)?),
+                vault: rest.get(72).copied().unwrap_or(0),
+                treasury_funded: rest.get(73).copied().unwrap_or(0) != 0,
+            },
+            46 => ProposalType::TreasuryWithdraw {
+                receive_account: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                amount: u64::from_be_bytes(read_bytes(rest, 32)?),
+            },
+            47 => ProposalType::TransferPercent {
+                token_mint: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                receive_account: Pubkey::new_from_array(read_bytes(rest, 32)?),
+                basis_points: u16::from_be_bytes(read_bytes(rest, 64)?),
+                vault: rest.get(66).copied().unwrap_or(0),
+            },
+            48 => {
+                let token_mint = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let vault = *rest.get(32).ok_or(ProgramError::InvalidInstructionData)?;
+                let by_share = *rest.get(33).ok_or(ProgramError::InvalidInstructionData)? != 0;
+                let payments = rest[34..]
+                    .chunks_exact(40)
+                    .map(|payment| Payment {
+                        receive_account: Pubkey::new_from_array(payment[..32].try_into().unwrap()),
+                        amount: u64::from_be_bytes(payment[32..].try_into().unwrap()),
+                    })
+                    .collect();
+                ProposalType::SplitPayment {
+                    token_mint,
+                    vault,
+                    by_share,
+                    payments,
+                }
+            }
+            49 => ProposalType::CreateVesting {
+                token_mint: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                receive_account: Pubkey::new_from_array(read_bytes(rest, 32)?),
+                amount: u64::from_be_bytes(read_bytes(rest, 64)?),
+                start_time: i64::from_be_bytes(read_bytes(rest, 72)?),
+                cliff_time: i64::from_be_bytes(read_bytes(rest, 80)?),
+                end_time: i64::from_be_bytes(read_bytes(rest, 88)?),
+            },
+            50 => ProposalType::Unfreeze,
+            52 => ProposalType::SetThresholdMode {
+                fixed: *rest.first().ok_or(ProgramError::InvalidInstructionData)? != 0,
+            },
+            53 => ProposalType::SetQuorum {
+                basis_points: u16::from_be_bytes(read_bytes(rest, 0)?),
+            },
+            54 => ProposalType::SetVoteGate {
+                mint: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                amount: u64::from_be_bytes(read_bytes(rest, 32)?),
+            },
+            55 => ProposalType::AddNftOwner {
+                mint: Pubkey::new_from_array(read_bytes(rest, 0)?),
+            },
+            56 => ProposalType::SetExecutionCallback {
+                program: Pubkey::new_from_array(read_bytes(rest, 0)?),
+            },
+            57 => ProposalType::CloseMint {
+                mint: Pubkey::new_from_array(read_bytes(rest, 0)?),
+            },
+            58 => ProposalType::SetTransferFee {
+                mint: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                basis_points: u16::from_be_bytes(read_bytes(rest, 32)?),
+                maximum_fee: u64::from_be_bytes(read_bytes(rest, 34)?),
+            },
+            59 => ProposalType::SetInterestRate {
+                mint: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                rate: i16::from_be_bytes(read_bytes(rest, 32)?),
+            },
+            60 => ProposalType::SetDefaultAccountState {
+                mint: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                frozen: *rest.get(32).ok_or(ProgramError::InvalidInstructionData)? != 0,
+            },
+            // metadata, fee and the name, symbol and uri each after a length byte
+            61 => {
+                let metadata = Pubkey::new_from_array(read_bytes(rest, 0)?);
+                let seller_fee_basis_points = u16::from_be_bytes(read_bytes(rest, 32)?);
+                let mut offset = 34;
+                let mut strings = Vec::new();
+                for _ in 0..3 {
+                    let [len] = read_bytes(rest, offset)?;
+                    let bytes = rest
+                        .get(offset + 1..offset + 1 + len as usize)
+                        .ok_or(ProgramError::InvalidInstructionData)?;
+                    strings.push(
+                        String::from_utf8(bytes.to_vec())
+                            .map_err(|_| ProgramError::InvalidInstructionData)?,
+                    );
+                    offset += 1 + len as usize;
+                }
+                let uri = strings.pop().unwrap();
+                let symbol = strings.pop().unwrap();
+                let name = strings.pop().unwrap();
+                ProposalType::UpdateMetadata {
+                    metadata,
+                    name,
+                    symbol,
+                    uri,
+                    seller_fee_basis_points,
+                }
+            }
+            62 => ProposalType::SetAndVerifyCollection {
+                metadata: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                collection_mint: Pubkey::new_from_array(read_bytes(rest, 32)?),
+                sized: *rest.get(64).ok_or(ProgramError::InvalidInstructionData)? != 0,
+            },
+            63 => ProposalType::TransferUpdateAuthority {
+                metadata: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                new_authority: Pubkey::new_from_array(read_bytes(rest, 32)?),
+            },
+            64 => ProposalType::TransferDomain {
+                name_account: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                new_owner: Pubkey::new_from_array(read_bytes(rest, 32)?),
+            },
+            // name account, offset and the record data after them
+            65 => ProposalType::UpdateDomainRecord {
+                name_account: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                offset: u32::from_be_bytes(read_bytes(rest, 32)?),
+                data: rest[36..].to_vec(),
+            },
+            66 => ProposalType::SplitStake {
+                stake_account: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                lamports: u64::from_be_bytes(read_bytes(rest, 32)?),
+            },
+            67 => ProposalType::MergeStake {
+                destination: Pubkey::new_from_array(read_bytes(rest, 0)?),
+                source: Pubkey::new_from_array(read_bytes(rest, 32)?),
+            },
+            68 => ProposalType::SetOptimisticDelay {
+                delay: i64::from_be_bytes(read_bytes(rest, 0)?),
+            },
+            51 => {
+                let inactive_for = i64::from_be_bytes(read_bytes(rest, 0)?);
+                let owners = rest[8..]
+                    .chunks_exact(32)
+                    .map(|owner| Pubkey::new_from_array(owner.try_into().unwrap()))
+                    .collect();
+                ProposalType::RemoveInactiveOwners {
+                    inactive_for,
+                    owners,
                 }
             }
-            4 => Self::Vote,
-            5 => Self::CloseProposal,
             _ => return Err(ProgramError::InvalidInstructionData),
         };
         Ok(res)
     }
+
+    fn pack_proposal(proposal: &ProposalType, buf: &mut Vec<u8>) {
+        buf.push(proposal.kind());
+        match proposal {
+            ProposalType::Transfer {
+                token_mint,
+                receive_account,
+                amount,
+                vault,
+                unwrap,
+                source,
+            } => {
+                buf.extend_from_slice(token_mint.as_ref());
+                buf.extend_from_slice(receive_account.as_ref());
+                buf.extend_from_slice(&amount.to_be_bytes());
+                buf.push(*vault);
+                buf.push(*unwrap as u8);
+                if let Some(seed) = source {
+                    buf.extend_from_slice(&seed.to_be_bytes());
+                }
+            }
+            ProposalType::AddOwner { user } => buf.extend_from_slice(user.as_ref()),
+            ProposalType::ChangeProposalLifetime { duration } => {
+                buf.extend_from_slice(&duration.to_be_bytes())
+            }
+            ProposalType::TransferSol {
+                receive_account,
+                amount,
+            }
+            | ProposalType::TreasuryWithdraw {
+                receive_account,
+                amount,
+            } => {
+                buf.extend_from_slice(receive_account.as_ref());
+                buf.extend_from_slice(&amount.to_be_bytes());
+            }
+            ProposalType::CloseWallet { destination } => {
+                buf.extend_from_slice(destination.as_ref())
+            }
+            ProposalType::SetRecovery {
+                beneficiary,
+                inactivity_period,
+            } => {
+                buf.extend_from_slice(beneficiary.as_ref());
+                buf.extend_from_slice(&inactivity_period.to_be_bytes());
+            }
+            ProposalType::SetExecutors { executors } => {
+                for executor in executors.iter() {
+                    buf.extend_from_slice(executor.as_ref());
+                }
+            }
+            ProposalType::SetRoles { user, roles } => {
+                buf.extend_from_slice(user.as_ref());
+                buf.push(*roles);
+            }
+            ProposalType::SetAllowance {
+                user,
+                token_mint,
+                amount,
+            } => {
+                buf.extend_from_slice(user.as_ref());
+                buf.extend_from_slice(token_mint.as_ref());
+                buf.extend_from_slice(&amount.to_be_bytes());
+            }
+            ProposalType::CreateTemplate { id, template } => {
+                buf.extend_from_slice(&id.to_be_bytes());
+                Self::pack_proposal(template, buf);
+            }
+            ProposalType::DeleteTemplate { id } => buf.extend_from_slice(&id.to_be_bytes()),
+            ProposalType::CreateVault { index, m, n } => {
+                buf.push(*index);
+                buf.push(*m);
+                buf.push(*n);
+            }
+            ProposalType::EscrowTransfer {
+                token_mint,
+                receive_account,
+                amount,
+                unlock_time,
+            } => {
+                buf.extend_from_slice(token_mint.as_ref());
+                buf.extend_from_slice(receive_account.as_ref());
+                buf.extend_from_slice(&amount.to_be_bytes());
+                buf.extend_from_slice(&unlock_time.to_be_bytes());
+            }
+            ProposalType::CancelEscrow { sequence } => {
+                buf.extend_from_slice(&sequence.to_be_bytes())
+            }
+            ProposalType::CreateStream {
+                token_mint,
+                receive_account,
+                rate,
+                duration,
+            } => {
+                buf.extend_from_slice(token_mint.as_ref());
+                buf.extend_from_slice(receive_account.as_ref());
+                buf.extend_from_slice(&rate.to_be_bytes());
+                buf.extend_from_slice(&duration.to_be_bytes());
+            }
+            ProposalType::CancelStream { sequence } => {
+                buf.extend_from_slice(&sequence.to_be_bytes())
+            }
+            ProposalType::ClaimableTransfer {
+                token_mint,
+                recipient,
+                amount,
+                claim_period,
+            } => {
+                buf.extend_from_slice(token_mint.as_ref());
+                buf.extend_from_slice(recipient.as_ref());
+                buf.extend_from_slice(&amount.to_be_bytes());
+                buf.extend_from_slice(&claim_period.to_be_bytes());
+            }
+            ProposalType::CreateLookupTable { recent_slot } => {
+                buf.extend_from_slice(&recent_slot.to_be_bytes())
+            }
+            ProposalType::ExtendLookupTable { table, addresses } => {
+                buf.extend_from_slice(table.as_ref());
+                for address in addresses.iter() {
+                    buf.extend_from_slice(address.as_ref());
+                }
+            }
+            ProposalType::FreezeLookupTable { table } => buf.extend_from_slice(table.as_ref()),
+            ProposalType::StakePoolDeposit { stake_pool, amount } => {
+                buf.extend_from_slice(stake_pool.as_ref());
+                buf.extend_from_slice(&amount.to_be_bytes());
+            }
+            ProposalType::StakePoolWithdraw {
+                stake_pool,
+                pool_tokens,
+            } => {
+                buf.extend_from_slice(stake_pool.as_ref());
+                buf.extend_from_slice(&pool_tokens.to_be_bytes());
+            }
+            ProposalType::RegisterProgram { program } => buf.extend_from_slice(program.as_ref()),
+            ProposalType::UnregisterProgram { program } => buf.extend_from_slice(program.as_ref()),
+            ProposalType::ExecuteInstruction {
+                program,
+                accounts,
+                data,
+            } => {
+                buf.extend_from_slice(program.as_ref());
+                buf.push(accounts.len() as u8);
+                for account in accounts.iter() {
+                    buf.extend_from_slice(account.pubkey.as_ref());
+                    buf.push(account.is_signer as u8);
+                    buf.push(account.is_writable as u8);
+                }
+                buf.extend_from_slice(data);
+            }
+            ProposalType::CreateRecurringSwap {
+                swap_program,
+                input_mint,
+                output_mint,
+                amount,
+                min_output,
+                interval,
+                swaps,
+            } => {
+                buf.extend_from_slice(swap_program.as_ref());
+                buf.extend_from_slice(input_mint.as_ref());
+                buf.extend_from_slice(output_mint.as_ref());
+                buf.extend_from_slice(&amount.to_be_bytes());
+                buf.extend_from_slice(&min_output.to_be_bytes());
+                buf.extend_from_slice(&interval.to_be_bytes());
+                buf.extend_from_slice(&swaps.to_be_bytes());
+            }
+            ProposalType::CancelRecurringSwap { sequence } => {
+                buf.extend_from_slice(&sequence.to_be_bytes())
+            }
+            ProposalType::ChangeThreshold { m, n } => {
+                buf.push(*m);
+                buf.push(*n);
+            }
+            ProposalType::ChangeTimelock { delay } => buf.extend_from_slice(&delay.to_be_bytes()),
+            ProposalType::CancelPendingChange => {}
+            ProposalType::SetLifetimeBounds { min, max } => {
+                buf.extend_from_slice(&min.to_be_bytes());
+                buf.extend_from_slice(&max.to_be_bytes());
+            }
+            ProposalType::FreezeAccount { mint, account }
+            | ProposalType::ThawAccount { mint, account } => {
+                buf.extend_from_slice(mint.as_ref());
+                buf.extend_from_slice(account.as_ref());
+            }
+            ProposalType::CreateTokenMultisig { m, signers } => {
+                buf.push(*m);
+                for signer in signers.iter() {
+                    buf.extend_from_slice(signer.as_ref());
+                }
+            }
+            ProposalType::MultisigTransfer {
+                multisig,
+                token_mint,
+                receive_account,
+                amount,
+            } => {
+                buf.extend_from_slice(multisig.as_ref());
+                buf.extend_from_slice(token_mint.as_ref());
+                buf.extend_from_slice(receive_account.as_ref());
+                buf.extend_from_slice(&amount.to_be_bytes());
+            }
+            ProposalType::ChangeOwnerKey {
+                old_owner,
+                new_owner,
+            } => {
+                buf.extend_from_slice(old_owner.as_ref());
+                buf.extend_from_slice(new_owner.as_ref());
+            }
+            ProposalType::ChangeRotationDelay { delay } => {
+                buf.extend_from_slice(&delay.to_be_bytes())
+            }
+            ProposalType::SetEmergencyPolicy { m, lifetime } => {
+                buf.push(*m);
+                buf.extend_from_slice(&lifetime.to_be_bytes());
+            }
+            ProposalType::SetSpendLimit {
+                mint,
+                daily_cap,
+                weekly_cap,
+            } => {
+                buf.extend_from_slice(mint.as_ref());
+                buf.extend_from_slice(&daily_cap.to_be_bytes());
+                buf.extend_from_slice(&weekly_cap.to_be_bytes());
+            }
+            ProposalType::BlacklistAddress { address }
+            | ProposalType::UnblacklistAddress { address } => {
+                buf.extend_from_slice(address.as_ref())
+            }
+            ProposalType::SetMandatoryApprovers { approvers } => {
+                for approver in approvers.iter() {
+                    buf.extend_from_slice(approver.as_ref());
+                }
+            }
+            ProposalType::SetMaxOpenProposals { max } => buf.extend_from_slice(&max.to_be_bytes()),
+            ProposalType::SetProposalDeposit { amount } | ProposalType::SetKeeperTip { amount } => {
+                buf.extend_from_slice(&amount.to_be_bytes())
+            }
+            ProposalType::TransferToWallet {
+                token_mint,
+                recipient,
+                amount,
+                vault,
+                treasury_funded,
+            } => {
+                buf.extend_from_slice(token_mint.as_ref());
+                buf.extend_from_slice(recipient.as_ref());
+                buf.extend_from_slice(&amount.to_be_bytes());
+                buf.push(*vault);
+                buf.push(*treasury_funded as u8);
+            }
+            ProposalType::TransferPercent {
+                token_mint,
+                receive_account,
+                basis_points,
+                vault,
+            } => {
+                buf.extend_from_slice(token_mint.as_ref());
+                buf.extend_from_slice(receive_account.as_ref());
+                buf.extend_from_slice(&basis_points.to_be_bytes());
+                buf.push(*vault);
+            }
+            ProposalType::SplitPayment {
+                token_mint,
+                vault,
+                by_share,
+                payments,
+            } => {
+                buf.extend_from_slice(token_mint.as_ref());
+                buf.push(*vault);
+                buf.push(*by_share as u8);
+                for payment in payments.iter() {
+                    buf.extend_from_slice(payment.receive_account.as_ref());
+                    buf.extend_from_slice(&payment.amount.to_be_bytes());
+                }
+            }
+            ProposalType::CreateVesting {
+                token_mint,
+                receive_account,
+                amount,
+                start_time,
+                cliff_time,
+                end_time,
+            } => {
+                buf.extend_from_slice(token_mint.as_ref());
+                buf.extend_from_slice(receive_account.as_ref());
+                buf.extend_from_slice(&amount.to_be_bytes());
+                buf.extend_from_slice(&start_time.to_be_bytes());
+                buf.extend_from_slice(&cliff_time.to_be_bytes());
+                buf.extend_from_slice(&end_time.to_be_bytes());
+            }
+            ProposalType::Unfreeze => {}
+            ProposalType::SetThresholdMode { fixed } => buf.push(*fixed as u8),
+            ProposalType::SetQuorum { basis_points } => {
+                buf.extend_from_slice(&basis_points.to_be_bytes())
+            }
+            ProposalType::SetVoteGate { mint, amount } => {
+                buf.extend_from_slice(mint.as_ref());
+                buf.extend_from_slice(&amount.to_be_bytes());
+            }
+            ProposalType::AddNftOwner { mint } => buf.extend_from_slice(mint.as_ref()),
+            ProposalType::SetExecutionCallback { program } => {
+                buf.extend_from_slice(program.as_ref())
+            }
+            ProposalType::CloseMint { mint } => buf.extend_from_slice(mint.as_ref()),
+            ProposalType::SetTransferFee {
+                mint,
+                basis_points,
+                maximum_fee,
+            } => {
+                buf.extend_from_slice(mint.as_ref());
+                buf.extend_from_slice(&basis_points.to_be_bytes());
+                buf.extend_from_slice(&maximum_fee.to_be_bytes());
+            }
+            ProposalType::SetInterestRate { mint, rate } => {
+                buf.extend_from_slice(mint.as_ref());
+                buf.extend_from_slice(&rate.to_be_bytes());
+            }
+            ProposalType::SetDefaultAccountState { mint, frozen } => {
+                buf.extend_from_slice(mint.as_ref());
+                buf.push(*frozen as u8);
+            }
+            ProposalType::UpdateMetadata {
+                metadata,
+                name,
+                symbol,
+                uri,
+                seller_fee_basis_points,
+            } => {
+                buf.extend_from_slice(metadata.as_ref());
+                buf.extend_from_slice(&seller_fee_basis_points.to_be_bytes());
+                for string in [name, symbol, uri] {
+                    buf.push(string.len() as u8);
+                    buf.extend_from_slice(string.as_bytes());
+                }
+            }
+            ProposalType::SetAndVerifyCollection {
+                metadata,
+                collection_mint,
+                sized,
+            } => {
+                buf.extend_from_slice(metadata.as_ref());
+                buf.extend_from_slice(collection_mint.as_ref());
+                buf.push(*sized as u8);
+            }
+            ProposalType::TransferUpdateAuthority {
+                metadata,
+                new_authority,
+            } => {
+                buf.extend_from_slice(metadata.as_ref());
+                buf.extend_from_slice(new_authority.as_ref());
+            }
+            ProposalType::TransferDomain {
+                name_account,
+                new_owner,
+            } => {
+                buf.extend_from_slice(name_account.as_ref());
+                buf.extend_from_slice(new_owner.as_ref());
+            }
+            ProposalType::UpdateDomainRecord {
+                name_account,
+                offset,
+                data,
+            } => {
+                buf.extend_from_slice(name_account.as_ref());
+                buf.extend_from_slice(&offset.to_be_bytes());
+                buf.extend_from_slice(data);
+            }
+            ProposalType::SplitStake {
+                stake_account,
+                lamports,
+            } => {
+                buf.extend_from_slice(stake_account.as_ref());
+                buf.extend_from_slice(&lamports.to_be_bytes());
+            }
+            ProposalType::MergeStake {
+                destination,
+                source,
+            } => {
+                buf.extend_from_slice(destination.as_ref());
+                buf.extend_from_slice(source.as_ref());
+            }
+            ProposalType::SetOptimisticDelay { delay } => {
+                buf.extend_from_slice(&delay.to_be_bytes())
+            }
+            ProposalType::RemoveInactiveOwners {
+                inactive_for,
+                owners,
+            } => {
+                buf.extend_from_slice(&inactive_for.to_be_bytes());
+                for owner in owners.iter() {
+                    buf.extend_from_slice(owner.as_ref());
+                }
+            }
+        }
+    }
+}
+
+// bounds checked read of N bytes at offset, the parser never indexes past the end of the input
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], ProgramError> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(ProgramError::InvalidInstructionData)
 }
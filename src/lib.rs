@@ -1,4 +1,11 @@
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(not(feature = "no-entrypoint"))]
+pub mod entrypoint;
 pub mod error;
+pub mod event;
 pub mod instruction;
 pub mod processor;
 pub mod state;
+#[cfg(feature = "test-support")]
+pub mod test_support;
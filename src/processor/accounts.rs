@@ -0,0 +1,959 @@
+use super::pda::{
+    authority_address, claim_address, escrow_address, proposal_address, registry_address,
+    session_address, stream_address, swap_address, vault_address, vesting_address,
+    vote_count_address, wallet_auth_address, AUDIT, BLACKLIST, PROPOSAL, ROTATION, STATS, VOTES,
+    WALLET_STATS,
+};
+use crate::error::WalletError;
+use crate::state::{
+    Blacklist, Claim, Escrow, KeyRotation, ProgramRegistry, Proposal, ProposalType, RecurringSwap,
+    SessionKey, Stream, TreasuryStats, Vault, Vesting, VoteCount, WalletAccount, WalletAuth,
+    WalletConfig, DISCRIMINATOR_LEN, ROLE_PROPOSE, ROLE_VOTE,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::{rent::Rent, Sysvar},
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{
+    state::{Account, Mint},
+    ID as TOKEN_PROGRAM_ID,
+};
+
+// every program account is read through here so its owner, type and state are always checked
+// a failure logs the account, with many accounts in an instruction the error code alone does not say which one
+pub(super) fn load<T: WalletAccount>(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<T, ProgramError> {
+    if account.owner != program_id {
+        msg!("account {} is not owned by the program", account.key);
+        if account.lamports() == 0 {
+            return Err(WalletError::AccountClosed.into());
+        }
+        return Err(ProgramError::IllegalOwner);
+    }
+    let data = account.data.borrow();
+    if data.get(..DISCRIMINATOR_LEN) != Some(&T::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN])
+        || data.get(DISCRIMINATOR_LEN) != Some(&(T::ACCOUNT_TYPE as u8))
+    {
+        msg!("account {} is not of the expected type", account.key);
+        return Err(WalletError::IncorrectAccountType.into());
+    }
+    let details = T::deserialize(&mut &data[DISCRIMINATOR_LEN..])?;
+    if !details.is_initialized() {
+        msg!("account {} has been closed", account.key);
+        return Err(WalletError::AccountClosed.into());
+    }
+    Ok(details)
+}
+
+// every program account is written through here so the anchor discriminator is kept in place
+pub(super) fn store<T: WalletAccount>(details: &T, account: &AccountInfo) -> ProgramResult {
+    let mut data = account.data.borrow_mut();
+    data[..DISCRIMINATOR_LEN].copy_from_slice(&T::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+    details.serialize(&mut &mut data[DISCRIMINATOR_LEN..])?;
+    Ok(())
+}
+
+// loads the wallet auth of user, making sure it is the one derived for the wallet
+pub(super) fn load_owner(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    user: &Pubkey,
+    wallet_auth: &AccountInfo,
+) -> Result<WalletAuth, ProgramError> {
+    let user_details = load::<WalletAuth>(wallet_auth, program_id)?;
+    if *wallet_auth.key != wallet_auth_address(program_id, wallet_config, user, user_details.bump)?
+    {
+        return Err(WalletError::InvalidWalletAuth.into());
+    }
+    Ok(user_details)
+}
+
+// user is either an owner or, when a session account is passed, a live session key of an owner scoped for role
+pub(super) fn load_signer(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    user: &Pubkey,
+    wallet_auth: &AccountInfo,
+    session: Option<&AccountInfo>,
+    role: u8,
+) -> Result<WalletAuth, ProgramError> {
+    let session = match session {
+        Some(session) => session,
+        None => return load_key_owner(program_id, wallet_config, user, wallet_auth),
+    };
+    // the holder of the NFT of an NFT-bound seat passes their token account instead of a session key
+    if *session.owner == TOKEN_PROGRAM_ID {
+        let holding = Account::unpack(&session.data.borrow())?;
+        if holding.owner != *user || holding.amount != 1 {
+            return Err(WalletError::NotSeatHolder.into());
+        }
+        let user_details = load_owner(program_id, wallet_config, &holding.mint, wallet_auth)?;
+        if !user_details.nft_bound {
+            return Err(WalletError::NotSeatHolder.into());
+        }
+        return Ok(user_details);
+    }
+    let session_details = load::<SessionKey>(session, program_id)?;
+    let session_key = session_address(
+        program_id,
+        wallet_config,
+        &session_details.owner,
+        user,
+        session_details.bump,
+    )?;
+    if *session.key != session_key {
+        return Err(WalletError::InvalidSessionKey.into());
+    }
+    if Clock::get()?.unix_timestamp > session_details.expires_at {
+        return Err(WalletError::SessionExpired.into());
+    }
+    if session_details.scope & role != role {
+        return Err(WalletError::SessionScopeMismatch.into());
+    }
+    load_key_owner(
+        program_id,
+        wallet_config,
+        &session_details.owner,
+        wallet_auth,
+    )
+}
+
+// an NFT-bound seat's owner is its mint, the mint's key must not act for the seat's holder
+fn load_key_owner(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    owner: &Pubkey,
+    wallet_auth: &AccountInfo,
+) -> Result<WalletAuth, ProgramError> {
+    let user_details = load_owner(program_id, wallet_config, owner, wallet_auth)?;
+    if user_details.nft_bound {
+        return Err(WalletError::NftBoundSeat.into());
+    }
+    Ok(user_details)
+}
+
+// with gated votes the owner's associated token account for the gate mint has to hold the gate amount
+pub(super) fn check_vote_gate(
+    wallet_details: &WalletConfig,
+    owner: &Pubkey,
+    gate_account: Option<&AccountInfo>,
+) -> ProgramResult {
+    if wallet_details.gate_mint == Pubkey::default() {
+        return Ok(());
+    }
+    let gate_account = gate_account.ok_or(WalletError::VoteGateNotMet)?;
+    if *gate_account.key != get_associated_token_address(owner, &wallet_details.gate_mint)
+        || *gate_account.owner != TOKEN_PROGRAM_ID
+    {
+        return Err(WalletError::VoteGateNotMet.into());
+    }
+    if Account::unpack(&gate_account.data.borrow())?.amount < wallet_details.gate_amount {
+        return Err(WalletError::VoteGateNotMet.into());
+    }
+    Ok(())
+}
+
+// the decimals a checked transfer of token_mint is made with, mint has to be its account
+pub(super) fn load_mint_decimals(
+    mint: &AccountInfo,
+    token_mint: &Pubkey,
+) -> Result<u8, ProgramError> {
+    if *mint.key != *token_mint || *mint.owner != TOKEN_PROGRAM_ID {
+        return Err(WalletError::InvalidMint.into());
+    }
+    let mint_details = Mint::unpack(&mint.data.borrow()).map_err(|_| WalletError::InvalidMint)?;
+    Ok(mint_details.decimals)
+}
+
+// any token account of authority for mint, associated or not
+pub(super) fn load_token_account(
+    token_account: &AccountInfo,
+    mint: &Pubkey,
+    authority: &Pubkey,
+) -> Result<Account, ProgramError> {
+    if *token_account.owner != TOKEN_PROGRAM_ID {
+        return Err(WalletError::IncorrectSendAccount.into());
+    }
+    let token_account_details = Account::unpack(&token_account.data.borrow())?;
+    if token_account_details.mint != *mint || token_account_details.owner != *authority {
+        return Err(WalletError::IncorrectSendAccount.into());
+    }
+    Ok(token_account_details)
+}
+
+pub(super) fn check_role(user_details: &WalletAuth, role: u8) -> ProgramResult {
+    if user_details.roles & role != role {
+        return Err(WalletError::MissingRole.into());
+    }
+    Ok(())
+}
+
+// reclaimed rent goes back to whoever paid for it or into the wallet's treasury
+pub(super) fn check_refund_destination(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    wallet_details: &WalletConfig,
+    refund_destination: &Pubkey,
+    payer: &Pubkey,
+) -> ProgramResult {
+    if refund_destination == payer {
+        return Ok(());
+    }
+    let wallet_authority_key =
+        authority_address(program_id, wallet_config, wallet_details.authority_bump)?;
+    if *refund_destination != wallet_authority_key {
+        return Err(WalletError::InvalidRefundDestination.into());
+    }
+    Ok(())
+}
+
+pub(super) fn load_blacklist(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    blacklist: &AccountInfo,
+) -> Result<Blacklist, ProgramError> {
+    let blacklist_details = load::<Blacklist>(blacklist, program_id)?;
+    let blacklist_key = Pubkey::create_program_address(
+        &[
+            BLACKLIST.as_bytes(),
+            wallet_config.as_ref(),
+            &[blacklist_details.bump],
+        ],
+        program_id,
+    )?;
+    if blacklist_details.wallet != *wallet_config || *blacklist.key != blacklist_key {
+        return Err(WalletError::InvalidBlacklist.into());
+    }
+    Ok(blacklist_details)
+}
+
+pub(super) fn load_treasury_stats(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    mint: &Pubkey,
+    treasury_stats: &AccountInfo,
+) -> Result<TreasuryStats, ProgramError> {
+    let stats_details = load::<TreasuryStats>(treasury_stats, program_id)?;
+    let treasury_stats_key = Pubkey::create_program_address(
+        &[
+            STATS.as_bytes(),
+            wallet_config.as_ref(),
+            mint.as_ref(),
+            &[stats_details.bump],
+        ],
+        program_id,
+    )?;
+    if *treasury_stats.key != treasury_stats_key {
+        return Err(WalletError::InvalidTreasuryStats.into());
+    }
+    Ok(stats_details)
+}
+
+pub(super) fn load_vault(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    index: u8,
+    vault: &AccountInfo,
+) -> Result<Vault, ProgramError> {
+    let vault_details = load::<Vault>(vault, program_id)?;
+    if vault_details.index != index
+        || *vault.key != vault_address(program_id, wallet_config, index, vault_details.bump)?
+    {
+        return Err(WalletError::InvalidVault.into());
+    }
+    Ok(vault_details)
+}
+
+pub(super) fn load_vesting(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    vesting: &AccountInfo,
+) -> Result<Vesting, ProgramError> {
+    let vesting_details = load::<Vesting>(vesting, program_id)?;
+    let vesting_key = vesting_address(
+        program_id,
+        wallet_config,
+        vesting_details.sequence,
+        vesting_details.bump,
+    )?;
+    if vesting_details.wallet != *wallet_config || *vesting.key != vesting_key {
+        return Err(WalletError::InvalidVesting.into());
+    }
+    Ok(vesting_details)
+}
+
+pub(super) fn load_escrow(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    escrow: &AccountInfo,
+) -> Result<Escrow, ProgramError> {
+    let escrow_details = load::<Escrow>(escrow, program_id)?;
+    let escrow_key = escrow_address(
+        program_id,
+        wallet_config,
+        escrow_details.sequence,
+        escrow_details.bump,
+    )?;
+    if escrow_details.wallet != *wallet_config || *escrow.key != escrow_key {
+        return Err(WalletError::InvalidEscrow.into());
+    }
+    Ok(escrow_details)
+}
+
+pub(super) fn load_stream(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    stream: &AccountInfo,
+) -> Result<Stream, ProgramError> {
+    let stream_details = load::<Stream>(stream, program_id)?;
+    let stream_key = stream_address(
+        program_id,
+        wallet_config,
+        stream_details.sequence,
+        stream_details.bump,
+    )?;
+    if stream_details.wallet != *wallet_config || *stream.key != stream_key {
+        return Err(WalletError::InvalidStream.into());
+    }
+    Ok(stream_details)
+}
+
+pub(super) fn load_claim(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    claim: &AccountInfo,
+) -> Result<Claim, ProgramError> {
+    let claim_details = load::<Claim>(claim, program_id)?;
+    let claim_key = claim_address(
+        program_id,
+        wallet_config,
+        claim_details.sequence,
+        claim_details.bump,
+    )?;
+    if claim_details.wallet != *wallet_config || *claim.key != claim_key {
+        return Err(WalletError::InvalidClaim.into());
+    }
+    Ok(claim_details)
+}
+
+pub(super) fn load_registry(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    registry: &AccountInfo,
+) -> Result<ProgramRegistry, ProgramError> {
+    let registry_details = load::<ProgramRegistry>(registry, program_id)?;
+    if registry_details.wallet != *wallet_config
+        || *registry.key != registry_address(program_id, wallet_config, registry_details.bump)?
+    {
+        return Err(WalletError::InvalidRegistry.into());
+    }
+    Ok(registry_details)
+}
+
+pub(super) fn load_recurring_swap(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    swap: &AccountInfo,
+) -> Result<RecurringSwap, ProgramError> {
+    let swap_details = load::<RecurringSwap>(swap, program_id)?;
+    let swap_key = swap_address(
+        program_id,
+        wallet_config,
+        swap_details.sequence,
+        swap_details.bump,
+    )?;
+    if swap_details.wallet != *wallet_config || *swap.key != swap_key {
+        return Err(WalletError::InvalidRecurringSwap.into());
+    }
+    Ok(swap_details)
+}
+
+pub(super) fn check_rent_exempt(account: &AccountInfo, rent: &Rent) -> ProgramResult {
+    if !rent.is_exempt(account.lamports(), account.data_len()) {
+        return Err(WalletError::NotRentExempt.into());
+    }
+    Ok(())
+}
+
+pub(super) fn load_key_rotation(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    key_rotation: &AccountInfo,
+) -> Result<KeyRotation, ProgramError> {
+    let rotation_details = load::<KeyRotation>(key_rotation, program_id)?;
+    let key_rotation_key = Pubkey::create_program_address(
+        &[
+            ROTATION.as_bytes(),
+            wallet_config.as_ref(),
+            rotation_details.old_owner.as_ref(),
+            &[rotation_details.bump],
+        ],
+        program_id,
+    )?;
+    if *key_rotation.key != key_rotation_key {
+        return Err(WalletError::InvalidKeyRotation.into());
+    }
+    Ok(rotation_details)
+}
+
+// the accounts of an instruction are read in the order documented in instruction.rs and all of their
+// signer and writable flags, owners, types and addresses are checked in load_and_validate, handlers using a
+// context only ever see accounts that passed
+// CreateProposal, CloseProposal, Vote, VoteMany, GetProposalStatus, VetoKeyRotation, CreateAuditLog and
+// CreateWalletStats have a context so far, every other instruction still reads and checks its accounts inline in
+// its handler, CloseProposal's context only covers its fixed accounts and not the ones of each proposal type
+
+fn signer(account: &AccountInfo) -> ProgramResult {
+    if !account.is_signer {
+        msg!("account {} is not a signer", account.key);
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+fn writable(account: &AccountInfo) -> ProgramResult {
+    if !account.is_writable {
+        msg!("account {} is not writable", account.key);
+        return Err(WalletError::AccountNotWritable.into());
+    }
+    Ok(())
+}
+
+// a proposal of the wallet with its vote count
+pub(crate) struct ProposalAccounts<'a, 'info> {
+    pub proposal: &'a AccountInfo<'info>,
+    pub vote_count: &'a AccountInfo<'info>,
+    pub proposal_details: Proposal,
+    pub voting_details: VoteCount,
+}
+
+impl<'a, 'info> ProposalAccounts<'a, 'info> {
+    pub fn load_and_validate(
+        program_id: &Pubkey,
+        wallet_config: &Pubkey,
+        proposal: &'a AccountInfo<'info>,
+        vote_count: &'a AccountInfo<'info>,
+    ) -> Result<Self, ProgramError> {
+        let proposal_details = load::<Proposal>(proposal, program_id)?;
+        if proposal_details.wallet != *wallet_config {
+            return Err(WalletError::ProposalWalletMismatch.into());
+        }
+        let proposal_key = proposal_address(
+            program_id,
+            wallet_config,
+            proposal_details.sequence,
+            proposal_details.bump,
+        )?;
+        if *proposal.key != proposal_key {
+            return Err(WalletError::InvalidProposal.into());
+        }
+        let voting_details = load::<VoteCount>(vote_count, program_id)?;
+        let vote_count_key =
+            vote_count_address(program_id, wallet_config, proposal.key, voting_details.bump)?;
+        if *vote_count.key != vote_count_key {
+            return Err(WalletError::InvalidVoteCount.into());
+        }
+        Ok(Self {
+            proposal,
+            vote_count,
+            proposal_details,
+            voting_details,
+        })
+    }
+}
+
+// a signer allowed to vote, either an owner or one of its session keys
+pub(crate) struct VoterAccounts<'a, 'info> {
+    pub wallet_config: &'a AccountInfo<'info>,
+    pub wallet_auth: &'a AccountInfo<'info>,
+    pub wallet_details: WalletConfig,
+    pub user_details: WalletAuth,
+}
+
+impl<'a, 'info> VoterAccounts<'a, 'info> {
+    fn load_and_validate(
+        program_id: &Pubkey,
+        user: &'a AccountInfo<'info>,
+        wallet_config: &'a AccountInfo<'info>,
+        wallet_auth: &'a AccountInfo<'info>,
+        optional: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        signer(user)?;
+        writable(wallet_config)?;
+        writable(wallet_auth)?;
+        let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+        // the gate token account comes last, after the session key if there is one
+        let (session, gate_account) = match optional {
+            optional if wallet_details.gate_mint == Pubkey::default() => (optional.first(), None),
+            [gate_account] => (None, Some(gate_account)),
+            [session, gate_account, ..] => (Some(session), Some(gate_account)),
+            [] => (None, None),
+        };
+        let user_details = load_signer(
+            program_id,
+            wallet_config.key,
+            user.key,
+            wallet_auth,
+            session,
+            ROLE_VOTE,
+        )?;
+        check_role(&user_details, ROLE_VOTE)?;
+        // an NFT-bound seat's owner is its mint, the gate tokens are held by whoever holds the NFT and signs
+        let gate_owner = if user_details.nft_bound {
+            user.key
+        } else {
+            &user_details.owner
+        };
+        check_vote_gate(&wallet_details, gate_owner, gate_account)?;
+        Ok(Self {
+            wallet_config,
+            wallet_auth,
+            wallet_details,
+            user_details,
+        })
+    }
+}
+
+pub(crate) struct VoteAccounts<'a, 'info> {
+    pub voter: VoterAccounts<'a, 'info>,
+    pub proposal: ProposalAccounts<'a, 'info>,
+}
+
+impl<'a, 'info> VoteAccounts<'a, 'info> {
+    pub fn load_and_validate(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let user = next_account_info(accounts_iter)?;
+        let wallet_config = next_account_info(accounts_iter)?;
+        let wallet_auth = next_account_info(accounts_iter)?;
+        let proposal = next_account_info(accounts_iter)?;
+        let vote_count = next_account_info(accounts_iter)?;
+
+        let voter = VoterAccounts::load_and_validate(
+            program_id,
+            user,
+            wallet_config,
+            wallet_auth,
+            accounts_iter.as_slice(),
+        )?;
+        writable(vote_count)?;
+        let proposal = ProposalAccounts::load_and_validate(
+            program_id,
+            wallet_config.key,
+            proposal,
+            vote_count,
+        )?;
+        Ok(Self { voter, proposal })
+    }
+}
+
+pub(crate) struct VoteManyAccounts<'a, 'info> {
+    pub voter: VoterAccounts<'a, 'info>,
+    pub proposals: Vec<ProposalAccounts<'a, 'info>>,
+}
+
+impl<'a, 'info> VoteManyAccounts<'a, 'info> {
+    pub fn load_and_validate(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        proposals: u8,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let user = next_account_info(accounts_iter)?;
+        let wallet_config = next_account_info(accounts_iter)?;
+        let wallet_auth = next_account_info(accounts_iter)?;
+        let pairs = accounts_iter
+            .as_slice()
+            .get(..proposals as usize * 2)
+            .ok_or(WalletError::AccountCountMismatch)?;
+        let optional = &accounts_iter.as_slice()[pairs.len()..];
+
+        let voter = VoterAccounts::load_and_validate(
+            program_id,
+            user,
+            wallet_config,
+            wallet_auth,
+            optional,
+        )?;
+        let mut proposals = Vec::with_capacity(pairs.len() / 2);
+        for pair in pairs.chunks(2) {
+            // each pair is loaded once up front, a repeated proposal would otherwise be counted twice
+            if proposals
+                .iter()
+                .any(|loaded: &ProposalAccounts| loaded.proposal.key == pair[0].key)
+            {
+                return Err(WalletError::AlreadyVoted.into());
+            }
+            writable(&pair[1])?;
+            proposals.push(ProposalAccounts::load_and_validate(
+                program_id,
+                wallet_config.key,
+                &pair[0],
+                &pair[1],
+            )?);
+        }
+        Ok(Self { voter, proposals })
+    }
+}
+
+pub(crate) struct ProposalStatusAccounts<'a, 'info> {
+    pub wallet_details: WalletConfig,
+    pub proposal: ProposalAccounts<'a, 'info>,
+}
+
+impl<'a, 'info> ProposalStatusAccounts<'a, 'info> {
+    pub fn load_and_validate(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let wallet_config = next_account_info(accounts_iter)?;
+        let proposal = next_account_info(accounts_iter)?;
+        let vote_count = next_account_info(accounts_iter)?;
+
+        let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+        let proposal = ProposalAccounts::load_and_validate(
+            program_id,
+            wallet_config.key,
+            proposal,
+            vote_count,
+        )?;
+        Ok(Self {
+            wallet_details,
+            proposal,
+        })
+    }
+}
+
+pub(crate) struct VetoKeyRotationAccounts<'a, 'info> {
+    pub owner: &'a AccountInfo<'info>,
+    pub wallet_config: &'a AccountInfo<'info>,
+    pub key_rotation: &'a AccountInfo<'info>,
+    pub refund_destination: &'a AccountInfo<'info>,
+    pub wallet_details: WalletConfig,
+    pub rotation_details: KeyRotation,
+}
+
+impl<'a, 'info> VetoKeyRotationAccounts<'a, 'info> {
+    pub fn load_and_validate(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let owner = next_account_info(accounts_iter)?;
+        let wallet_config = next_account_info(accounts_iter)?;
+        let wallet_auth = next_account_info(accounts_iter)?;
+        let key_rotation = next_account_info(accounts_iter)?;
+        let refund_destination = next_account_info(accounts_iter)?;
+
+        signer(owner)?;
+        writable(wallet_config)?;
+        writable(key_rotation)?;
+        writable(refund_destination)?;
+        let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+        let user_details = load_owner(program_id, wallet_config.key, owner.key, wallet_auth)?;
+        check_role(&user_details, ROLE_VOTE)?;
+        let rotation_details = load_key_rotation(program_id, wallet_config.key, key_rotation)?;
+        Ok(Self {
+            owner,
+            wallet_config,
+            key_rotation,
+            refund_destination,
+            wallet_details,
+            rotation_details,
+        })
+    }
+}
+
+pub(crate) struct CreateAuditLogAccounts<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub wallet_config: &'a AccountInfo<'info>,
+    pub audit_log: &'a AccountInfo<'info>,
+    pub bump: u8,
+}
+
+impl<'a, 'info> CreateAuditLogAccounts<'a, 'info> {
+    pub fn load_and_validate(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let payer = next_account_info(accounts_iter)?;
+        let wallet_config = next_account_info(accounts_iter)?;
+        let audit_log = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+
+        signer(payer)?;
+        writable(payer)?;
+        writable(audit_log)?;
+        load::<WalletConfig>(wallet_config, program_id)?;
+        let (audit_log_key, bump) = Pubkey::find_program_address(
+            &[AUDIT.as_bytes(), wallet_config.key.as_ref()],
+            program_id,
+        );
+        if *audit_log.key != audit_log_key {
+            return Err(WalletError::InvalidAuditLog.into());
+        }
+        if *system_program.key != SYSTEM_PROGRAM_ID {
+            return Err(WalletError::IncorrectSystemProgram.into());
+        }
+        Ok(Self {
+            payer,
+            wallet_config,
+            audit_log,
+            bump,
+        })
+    }
+}
+
+pub(crate) struct CreateWalletStatsAccounts<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub wallet_config: &'a AccountInfo<'info>,
+    pub wallet_stats: &'a AccountInfo<'info>,
+    pub bump: u8,
+}
+
+impl<'a, 'info> CreateWalletStatsAccounts<'a, 'info> {
+    pub fn load_and_validate(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let payer = next_account_info(accounts_iter)?;
+        let wallet_config = next_account_info(accounts_iter)?;
+        let wallet_stats = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+
+        signer(payer)?;
+        writable(payer)?;
+        writable(wallet_stats)?;
+        load::<WalletConfig>(wallet_config, program_id)?;
+        let (wallet_stats_key, bump) = Pubkey::find_program_address(
+            &[WALLET_STATS.as_bytes(), wallet_config.key.as_ref()],
+            program_id,
+        );
+        if *wallet_stats.key != wallet_stats_key {
+            return Err(WalletError::InvalidWalletStats.into());
+        }
+        if *system_program.key != SYSTEM_PROGRAM_ID {
+            return Err(WalletError::IncorrectSystemProgram.into());
+        }
+        Ok(Self {
+            payer,
+            wallet_config,
+            wallet_stats,
+            bump,
+        })
+    }
+}
+
+// the vault account follows the fixed accounts only for a transfer out of a vault other than 0
+pub(super) fn next_vault_account<'a, 'info>(
+    proposal: &ProposalType,
+    accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'info>>,
+) -> Result<Option<&'a AccountInfo<'info>>, ProgramError> {
+    if proposal.vault() == 0 {
+        return Ok(None);
+    }
+    next_account_info(accounts_iter).map(Some)
+}
+
+pub(crate) struct CreateProposalAccounts<'a, 'info> {
+    pub user: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub wallet_config: &'a AccountInfo<'info>,
+    pub wallet_auth: &'a AccountInfo<'info>,
+    pub proposal: &'a AccountInfo<'info>,
+    pub vote_count: &'a AccountInfo<'info>,
+    pub wallet_stats: &'a AccountInfo<'info>,
+    // only for a transfer out of a vault other than 0
+    pub vault: Option<&'a AccountInfo<'info>>,
+    pub wallet_details: WalletConfig,
+    pub user_details: WalletAuth,
+    pub sequence: u64,
+    pub proposal_bump: u8,
+    pub vote_count_bump: u8,
+}
+
+impl<'a, 'info> CreateProposalAccounts<'a, 'info> {
+    pub fn load_and_validate(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        new_proposal: &ProposalType,
+        treasury_funded: bool,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let user = next_account_info(accounts_iter)?;
+        let payer = next_account_info(accounts_iter)?;
+        let wallet_config = next_account_info(accounts_iter)?;
+        let wallet_auth = next_account_info(accounts_iter)?;
+        let proposal = next_account_info(accounts_iter)?;
+        let vote_count = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let wallet_stats = next_account_info(accounts_iter)?;
+        let vault = next_vault_account(new_proposal, accounts_iter)?;
+        let session = next_account_info(accounts_iter).ok();
+
+        signer(user)?;
+        writable(payer)?;
+        writable(wallet_config)?;
+        writable(wallet_auth)?;
+        writable(proposal)?;
+        writable(vote_count)?;
+        writable(wallet_stats)?;
+        let user_details = load_signer(
+            program_id,
+            wallet_config.key,
+            user.key,
+            wallet_auth,
+            session,
+            ROLE_PROPOSE,
+        )?;
+        check_role(&user_details, ROLE_PROPOSE)?;
+        let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+        // a treasury funded proposal is paid for by the wallet authority, which the program signs for
+        if treasury_funded {
+            if *payer.key
+                != authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?
+            {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+        } else {
+            signer(payer)?;
+        }
+        let sequence = wallet_details.proposal_count;
+        let (proposal_key, proposal_bump) = Pubkey::find_program_address(
+            &[
+                PROPOSAL.as_bytes(),
+                wallet_config.key.as_ref(),
+                &sequence.to_le_bytes(),
+            ],
+            program_id,
+        );
+        if *proposal.key != proposal_key {
+            return Err(WalletError::InvalidProposal.into());
+        }
+        let (vote_count_key, vote_count_bump) = Pubkey::find_program_address(
+            &[
+                VOTES.as_bytes(),
+                wallet_config.key.as_ref(),
+                proposal.key.as_ref(),
+            ],
+            program_id,
+        );
+        if *vote_count.key != vote_count_key {
+            return Err(WalletError::InvalidVoteCount.into());
+        }
+        if *system_program.key != SYSTEM_PROGRAM_ID {
+            return Err(WalletError::IncorrectSystemProgram.into());
+        }
+        Ok(Self {
+            user,
+            payer,
+            wallet_config,
+            wallet_auth,
+            proposal,
+            vote_count,
+            wallet_stats,
+            vault,
+            wallet_details,
+            user_details,
+            sequence,
+            proposal_bump,
+            vote_count_bump,
+        })
+    }
+}
+
+// the fixed accounts of CloseProposal, the execution accounts that follow are left in accounts_iter
+pub(crate) struct CloseProposalAccounts<'a, 'info> {
+    pub closer: &'a AccountInfo<'info>,
+    pub closer_wallet_auth: &'a AccountInfo<'info>,
+    pub refund_destination: &'a AccountInfo<'info>,
+    pub wallet_config: &'a AccountInfo<'info>,
+    pub proposal: ProposalAccounts<'a, 'info>,
+    pub audit_log: &'a AccountInfo<'info>,
+    pub treasury: &'a AccountInfo<'info>,
+    pub wallet_stats: &'a AccountInfo<'info>,
+    pub wallet_details: WalletConfig,
+    // one of the wallet's executors, paid the keeper tip
+    pub keeper: bool,
+}
+
+impl<'a, 'info> CloseProposalAccounts<'a, 'info> {
+    pub fn load_and_validate(
+        program_id: &Pubkey,
+        accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'info>>,
+    ) -> Result<Self, ProgramError> {
+        let closer = next_account_info(accounts_iter)?;
+        let closer_wallet_auth = next_account_info(accounts_iter)?;
+        let refund_destination = next_account_info(accounts_iter)?;
+        let wallet_config = next_account_info(accounts_iter)?;
+        let proposal = next_account_info(accounts_iter)?;
+        let vote_count = next_account_info(accounts_iter)?;
+        let audit_log = next_account_info(accounts_iter)?;
+        let treasury = next_account_info(accounts_iter)?;
+        let wallet_stats = next_account_info(accounts_iter)?;
+
+        signer(closer)?;
+        writable(closer)?;
+        writable(refund_destination)?;
+        writable(wallet_config)?;
+        writable(proposal)?;
+        writable(vote_count)?;
+        writable(audit_log)?;
+        writable(treasury)?;
+        writable(wallet_stats)?;
+        let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+        let proposal = ProposalAccounts::load_and_validate(
+            program_id,
+            wallet_config.key,
+            proposal,
+            vote_count,
+        )?;
+        let wallet_authority_key =
+            authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?;
+        if *treasury.key != wallet_authority_key {
+            return Err(WalletError::InvalidWalletAuthority.into());
+        }
+        // the rent of a treasury funded proposal can only go back to the treasury
+        let payer = if proposal.proposal_details.treasury_funded {
+            wallet_authority_key
+        } else {
+            proposal.proposal_details.proposer
+        };
+        check_refund_destination(
+            program_id,
+            wallet_config.key,
+            &wallet_details,
+            refund_destination.key,
+            &payer,
+        )?;
+        let keeper =
+            *closer.key != Pubkey::default() && wallet_details.executors.contains(closer.key);
+        Ok(Self {
+            closer,
+            closer_wallet_auth,
+            refund_destination,
+            wallet_config,
+            proposal,
+            audit_log,
+            treasury,
+            wallet_stats,
+            wallet_details,
+            keeper,
+        })
+    }
+}
@@ -0,0 +1,91 @@
+use super::accounts::{check_rent_exempt, load, store, CreateAuditLogAccounts};
+use super::pda::AUDIT;
+use crate::error::WalletError;
+use crate::state::{AccountType, AuditAction, AuditLog, AuditRecord, AUDIT_LOG_LEN};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+// appends to the wallet's audit log, a no-op until CreateAuditLog creates it
+pub(super) fn append_audit_record(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    audit_log: &AccountInfo,
+    action: AuditAction,
+    kind: u8,
+    sequence: u64,
+    subject: Pubkey,
+) -> ProgramResult {
+    if audit_log.owner != program_id {
+        let (audit_log_key, _) =
+            Pubkey::find_program_address(&[AUDIT.as_bytes(), wallet_config.as_ref()], program_id);
+        if *audit_log.key != audit_log_key {
+            return Err(WalletError::InvalidAuditLog.into());
+        }
+        return Ok(());
+    }
+    let mut log_details = load::<AuditLog>(audit_log, program_id)?;
+    let audit_log_key = Pubkey::create_program_address(
+        &[
+            AUDIT.as_bytes(),
+            wallet_config.as_ref(),
+            &[log_details.bump],
+        ],
+        program_id,
+    )?;
+    if log_details.wallet != *wallet_config || *audit_log.key != audit_log_key {
+        return Err(WalletError::InvalidAuditLog.into());
+    }
+    let slot = (log_details.next_index % log_details.records.len() as u64) as usize;
+    log_details.records[slot] = AuditRecord {
+        action,
+        kind,
+        sequence,
+        subject,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    log_details.next_index += 1;
+    store(&log_details, audit_log)
+}
+
+// anyone can pay for the log, records are appended from then on
+pub fn create_audit_log(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let CreateAuditLogAccounts {
+        payer,
+        wallet_config,
+        audit_log,
+        bump,
+    } = CreateAuditLogAccounts::load_and_validate(program_id, accounts)?;
+
+    let account_size: u64 = AuditLog::LEN.try_into().unwrap();
+    let rent = Rent::get()?;
+    let rent_amount = rent.minimum_balance(AuditLog::LEN);
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            audit_log.key,
+            rent_amount,
+            account_size,
+            program_id,
+        ),
+        &[payer.clone(), audit_log.clone()],
+        &[&[AUDIT.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+    )?;
+    check_rent_exempt(audit_log, &rent)?;
+    let log_details = AuditLog {
+        discriminator: AccountType::AuditLog,
+        wallet: *wallet_config.key,
+        next_index: 0,
+        records: [AuditRecord::default(); AUDIT_LOG_LEN],
+        bump,
+        is_initialized: true,
+    };
+    store(&log_details, audit_log)
+}
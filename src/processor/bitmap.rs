@@ -0,0 +1,21 @@
+// owner_identities and vote_record are 256 bit maps indexed by owner id, id 0 is the high bit of the first byte
+pub fn is_bit_set(map: &[u8; 32], id: u8) -> bool {
+    map[id as usize / 8] & (0x80 >> (id % 8)) != 0
+}
+
+pub fn set_bit(map: &mut [u8; 32], id: u8) {
+    map[id as usize / 8] |= 0x80 >> (id % 8);
+}
+
+pub fn clear_bit(map: &mut [u8; 32], id: u8) {
+    map[id as usize / 8] &= !(0x80 >> (id % 8));
+}
+
+pub fn count_bits(map: &[u8; 32]) -> u32 {
+    map.iter().map(|byte| byte.count_ones()).sum()
+}
+
+// lowest free id, so ids of removed owners are handed out again first
+pub fn first_clear_bit(map: &[u8; 32]) -> Option<u8> {
+    (0..=u8::MAX).find(|id| !is_bit_set(map, *id))
+}
@@ -0,0 +1,285 @@
+use super::accounts::{load_mint_decimals, store};
+use super::pda::{CLAIM, ESCROW, UNWRAP};
+use crate::error::WalletError;
+use crate::state::{Claim, Escrow, ExecutionSummary};
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::{rent::Rent, Sysvar},
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{
+    instruction as token_instruction, native_mint, native_mint::ID as NATIVE_MINT, state::Account,
+    ID as TOKEN_PROGRAM_ID,
+};
+
+// invokes the wallet's callback program with the summary of an execution
+pub(super) fn execution_callback<'a>(
+    callback_program: &AccountInfo<'a>,
+    wallet_config: &AccountInfo<'a>,
+    proposal: &AccountInfo<'a>,
+    summary: &ExecutionSummary,
+) -> ProgramResult {
+    invoke(
+        &Instruction {
+            program_id: *callback_program.key,
+            accounts: vec![
+                AccountMeta::new_readonly(*wallet_config.key, false),
+                AccountMeta::new_readonly(*proposal.key, false),
+            ],
+            data: summary.try_to_vec()?,
+        },
+        &[
+            wallet_config.clone(),
+            proposal.clone(),
+            callback_program.clone(),
+        ],
+    )
+}
+
+// pays out the escrowed tokens to receive_account and closes the escrow, its rent goes to the wallet authority
+#[allow(clippy::too_many_arguments)]
+pub(super) fn close_escrow<'a>(
+    wallet_config: &Pubkey,
+    escrow: &AccountInfo<'a>,
+    mut escrow_details: Escrow,
+    escrow_token_account: &AccountInfo<'a>,
+    receive_account: &AccountInfo<'a>,
+    wallet_authority: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+) -> ProgramResult {
+    if *escrow_token_account.key != get_associated_token_address(escrow.key, &escrow_details.mint) {
+        return Err(WalletError::IncorrectSendAccount.into());
+    }
+    if *token_program.key != TOKEN_PROGRAM_ID {
+        return Err(WalletError::IncorrectTokenProgram.into());
+    }
+    let decimals = load_mint_decimals(mint, &escrow_details.mint)?;
+    let escrow_seeds: &[&[u8]] = &[
+        ESCROW.as_bytes(),
+        wallet_config.as_ref(),
+        &escrow_details.sequence.to_le_bytes(),
+        &[escrow_details.bump],
+    ];
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            token_program.key,
+            escrow_token_account.key,
+            mint.key,
+            receive_account.key,
+            escrow.key,
+            &[],
+            escrow_details.amount,
+            decimals,
+        )?,
+        &[
+            escrow_token_account.clone(),
+            mint.clone(),
+            receive_account.clone(),
+            escrow.clone(),
+        ],
+        &[escrow_seeds],
+    )?;
+    invoke_signed(
+        &token_instruction::close_account(
+            token_program.key,
+            escrow_token_account.key,
+            wallet_authority.key,
+            escrow.key,
+            &[],
+        )?,
+        &[
+            escrow_token_account.clone(),
+            wallet_authority.clone(),
+            escrow.clone(),
+        ],
+        &[escrow_seeds],
+    )?;
+    escrow_details.is_initialized = false;
+    store(&escrow_details, escrow)?;
+    let balance = escrow.lamports();
+    **escrow.try_borrow_mut_lamports()? -= balance;
+    **wallet_authority.try_borrow_mut_lamports()? += balance;
+    Ok(())
+}
+
+// pays out the claimed tokens to receive_account and closes the claim, its rent goes to the wallet authority
+#[allow(clippy::too_many_arguments)]
+pub(super) fn close_claim<'a>(
+    wallet_config: &Pubkey,
+    claim: &AccountInfo<'a>,
+    mut claim_details: Claim,
+    claim_token_account: &AccountInfo<'a>,
+    receive_account: &AccountInfo<'a>,
+    wallet_authority: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+) -> ProgramResult {
+    if *claim_token_account.key != get_associated_token_address(claim.key, &claim_details.mint) {
+        return Err(WalletError::IncorrectSendAccount.into());
+    }
+    if *token_program.key != TOKEN_PROGRAM_ID {
+        return Err(WalletError::IncorrectTokenProgram.into());
+    }
+    let decimals = load_mint_decimals(mint, &claim_details.mint)?;
+    claim_details.is_initialized = false;
+    store(&claim_details, claim)?;
+    let claim_seeds: &[&[u8]] = &[
+        CLAIM.as_bytes(),
+        wallet_config.as_ref(),
+        &claim_details.sequence.to_le_bytes(),
+        &[claim_details.bump],
+    ];
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            token_program.key,
+            claim_token_account.key,
+            mint.key,
+            receive_account.key,
+            claim.key,
+            &[],
+            claim_details.amount,
+            decimals,
+        )?,
+        &[
+            claim_token_account.clone(),
+            mint.clone(),
+            receive_account.clone(),
+            claim.clone(),
+        ],
+        &[claim_seeds],
+    )?;
+    invoke_signed(
+        &token_instruction::close_account(
+            token_program.key,
+            claim_token_account.key,
+            wallet_authority.key,
+            claim.key,
+            &[],
+        )?,
+        &[
+            claim_token_account.clone(),
+            wallet_authority.clone(),
+            claim.clone(),
+        ],
+        &[claim_seeds],
+    )?;
+    let balance = claim.lamports();
+    **claim.try_borrow_mut_lamports()? -= balance;
+    **wallet_authority.try_borrow_mut_lamports()? += balance;
+    Ok(())
+}
+
+// the wrapped SOL goes through a temporary token account that is closed back into the authority, which
+// fronts its rent, and the lamports are then paid out to the destination
+#[allow(clippy::too_many_arguments)]
+pub(super) fn unwrap_transfer<'a>(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    amount: u64,
+    signer_seeds: &[&[u8]],
+    source_account: &AccountInfo<'a>,
+    destination_account: &AccountInfo<'a>,
+    wallet_authority: &AccountInfo<'a>,
+    unwrap_account: &AccountInfo<'a>,
+    native_mint: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (unwrap_key, bump) = Pubkey::find_program_address(
+        &[
+            UNWRAP.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+        ],
+        program_id,
+    );
+    if *unwrap_account.key != unwrap_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if *native_mint.key != NATIVE_MINT {
+        return Err(WalletError::InvalidUnwrap.into());
+    }
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        return Err(WalletError::IncorrectSystemProgram.into());
+    }
+    let account_size: u64 = Account::LEN.try_into().unwrap();
+    let rent_amount = Rent::get()?.minimum_balance(Account::LEN);
+    invoke_signed(
+        &system_instruction::create_account(
+            wallet_authority.key,
+            unwrap_account.key,
+            rent_amount,
+            account_size,
+            token_program.key,
+        ),
+        &[wallet_authority.clone(), unwrap_account.clone()],
+        &[
+            signer_seeds,
+            &[
+                UNWRAP.as_bytes(),
+                wallet_config.as_ref(),
+                &sequence.to_le_bytes(),
+                &[bump],
+            ],
+        ],
+    )?;
+    invoke(
+        &token_instruction::initialize_account3(
+            token_program.key,
+            unwrap_account.key,
+            native_mint.key,
+            wallet_authority.key,
+        )?,
+        &[unwrap_account.clone(), native_mint.clone()],
+    )?;
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            token_program.key,
+            source_account.key,
+            native_mint.key,
+            unwrap_account.key,
+            wallet_authority.key,
+            &[],
+            amount,
+            native_mint::DECIMALS,
+        )?,
+        &[
+            source_account.clone(),
+            native_mint.clone(),
+            unwrap_account.clone(),
+            wallet_authority.clone(),
+        ],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &token_instruction::close_account(
+            token_program.key,
+            unwrap_account.key,
+            wallet_authority.key,
+            wallet_authority.key,
+            &[],
+        )?,
+        &[unwrap_account.clone(), wallet_authority.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::transfer(wallet_authority.key, destination_account.key, amount),
+        &[
+            wallet_authority.clone(),
+            destination_account.clone(),
+            system_program.clone(),
+        ],
+        &[signer_seeds],
+    )
+}
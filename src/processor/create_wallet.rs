@@ -0,0 +1,457 @@
+use super::accounts::{check_rent_exempt, load, load_owner, store};
+use super::bitmap::{is_bit_set, set_bit};
+use super::pda::{authority_address, treasury_address, AUTHORITY, OWNER, TREASURY};
+use super::proposal::{check_lifetime, check_lifetime_bounds};
+use crate::error::WalletError;
+use crate::event::WalletEvent;
+use crate::state::{
+    AccountType, PendingChange, WalletAuth, WalletConfig, WalletInfo, DEFAULT_MAX_OPEN_PROPOSALS,
+    DEFAULT_ROTATION_DELAY, DEFAULT_TIMELOCK, MAX_EXECUTORS, ROLE_ALL,
+};
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed, set_return_data},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_wallet(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    m: u8,
+    n: u8,
+    owners: &[Pubkey],
+    proposal_lifetime: i64,
+    min_proposal_lifetime: i64,
+    max_proposal_lifetime: i64,
+) -> ProgramResult {
+    if m == 0 || m > n {
+        return Err(WalletError::InvalidWalletParameters.into());
+    }
+    check_lifetime_bounds(min_proposal_lifetime, max_proposal_lifetime)?;
+    check_lifetime(
+        proposal_lifetime,
+        min_proposal_lifetime,
+        max_proposal_lifetime,
+    )?;
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let mut wallet_auth = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer || !wallet_config.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (mut wallet_auth_key, mut bump) = Pubkey::find_program_address(
+        &[
+            OWNER.as_bytes(),
+            wallet_config.key.as_ref(),
+            user.key.as_ref(),
+        ],
+        program_id,
+    );
+    if *wallet_auth.key != wallet_auth_key {
+        return Err(WalletError::InvalidWalletAuth.into());
+    }
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        return Err(WalletError::IncorrectSystemProgram.into());
+    }
+    if owners.len() != accounts_iter.len() {
+        return Err(WalletError::OwnerWalletAuthCountMismatch.into());
+    }
+    // create user's wallet auth account
+    let account_size: u64 = WalletAuth::LEN.try_into().unwrap();
+    let rent = Rent::get()?;
+    let rent_amount = rent.minimum_balance(WalletAuth::LEN);
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            wallet_auth.key,
+            rent_amount,
+            account_size,
+            program_id,
+        ),
+        &[user.clone(), wallet_auth.clone()],
+        &[&[
+            OWNER.as_bytes(),
+            wallet_config.key.as_ref(),
+            user.key.as_ref(),
+            &[bump],
+        ]],
+    )?;
+    check_rent_exempt(wallet_auth, &rent)?;
+    // initialize user's wallet auth account
+    let current_time = Clock::get()?.unix_timestamp;
+    let mut user_details = WalletAuth {
+        discriminator: AccountType::WalletAuth,
+        owner: *user.key,
+        wallet: *wallet_config.key,
+        added_time: current_time,
+        id: 0,
+        last_active_at: current_time,
+        last_vote_at: current_time,
+        proposals_created: 0,
+        votes_cast: 0,
+        nft_bound: false,
+        roles: ROLE_ALL,
+        bump,
+        is_initialized: true,
+    };
+    store(&user_details, wallet_auth)?;
+    // create and initialize wallet auth accounts for other owners
+    for (id, owner) in (1..).zip(owners.iter()) {
+        wallet_auth = next_account_info(accounts_iter)?;
+        (wallet_auth_key, bump) = Pubkey::find_program_address(
+            &[
+                OWNER.as_bytes(),
+                wallet_config.key.as_ref(),
+                owner.as_ref(),
+            ],
+            program_id,
+        );
+        if *wallet_auth.key != wallet_auth_key {
+            return Err(WalletError::InvalidWalletAuth.into());
+        }
+        invoke_signed(
+            &system_instruction::create_account(
+                user.key,
+                wallet_auth.key,
+                rent_amount,
+                account_size,
+                program_id,
+            ),
+            &[user.clone(), wallet_auth.clone()],
+            &[&[
+                OWNER.as_bytes(),
+                wallet_config.key.as_ref(),
+                owner.as_ref(),
+                &[bump],
+            ]],
+        )?;
+        check_rent_exempt(wallet_auth, &rent)?;
+        user_details.owner = *owner;
+        user_details.id = id;
+        user_details.bump = bump;
+        store(&user_details, wallet_auth)?;
+    }
+    // create wallet config account
+    let account_size: u64 = WalletConfig::LEN.try_into().unwrap();
+    let rent_amount = rent.minimum_balance(WalletConfig::LEN);
+    invoke(
+        &system_instruction::create_account(
+            user.key,
+            wallet_config.key,
+            rent_amount,
+            account_size,
+            program_id,
+        ),
+        &[user.clone(), wallet_config.clone()],
+    )?;
+    check_rent_exempt(wallet_config, &rent)?;
+    // initialize wallet config account
+    let owner_count = 1 + owners.len();
+    let mut identities = [0u8; 32];
+    for id in 0..owner_count {
+        set_bit(&mut identities, id.try_into().unwrap());
+    }
+    let (_, authority_bump) = Pubkey::find_program_address(
+        &[AUTHORITY.as_bytes(), wallet_config.key.as_ref()],
+        program_id,
+    );
+    let (_, treasury_bump) = Pubkey::find_program_address(
+        &[TREASURY.as_bytes(), wallet_config.key.as_ref()],
+        program_id,
+    );
+    let mut wallet_info = WalletConfig {
+        discriminator: AccountType::WalletConfig,
+        m,
+        n,
+        owners: owner_count.try_into().unwrap(),
+        owner_identities: identities,
+        proposal_lifetime,
+        rent_floor: rent.minimum_balance(0),
+        total_deposited: 0,
+        total_withdrawn: 0,
+        proposal_count: 0,
+        executors: [Pubkey::default(); MAX_EXECUTORS],
+        epoch: 0,
+        timelock: DEFAULT_TIMELOCK,
+        pending_change: PendingChange::None,
+        pending_change_at: 0,
+        min_proposal_lifetime,
+        max_proposal_lifetime,
+        rotation_delay: DEFAULT_ROTATION_DELAY,
+        // until the owners set a policy urgent proposals need every owner but run in the shortest window
+        emergency_m: n,
+        emergency_lifetime: min_proposal_lifetime,
+        mandatory_approvers: [0; 32],
+        event_count: 0,
+        open_proposals: 0,
+        max_open_proposals: DEFAULT_MAX_OPEN_PROPOSALS,
+        proposal_deposit: 0,
+        keeper_tip: 0,
+        last_activity: current_time,
+        beneficiary: Pubkey::default(),
+        inactivity_period: 0,
+        authority_bump,
+        treasury_bump,
+        frozen: false,
+        fixed_threshold: false,
+        quorum: 0,
+        gate_mint: Pubkey::default(),
+        gate_amount: 0,
+        callback_program: Pubkey::default(),
+        optimistic_delay: 0,
+        open_accounts: 0,
+        is_initialized: true,
+    };
+    WalletEvent::WalletCreated {
+        wallet: *wallet_config.key,
+        creator: *user.key,
+        m,
+        n,
+        owners: wallet_info.owners,
+        proposal_lifetime,
+    }
+    .emit(&mut wallet_info);
+    store(&wallet_info, wallet_config)
+}
+
+// creates the wallet auth of owner in the new wallet, with the id and roles it has in the source one
+fn clone_owner<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    wallet_config: &Pubkey,
+    wallet_auth: &AccountInfo<'a>,
+    source_details: &WalletAuth,
+    rent: &Rent,
+    current_time: i64,
+) -> ProgramResult {
+    let (wallet_auth_key, bump) = Pubkey::find_program_address(
+        &[
+            OWNER.as_bytes(),
+            wallet_config.as_ref(),
+            source_details.owner.as_ref(),
+        ],
+        program_id,
+    );
+    if *wallet_auth.key != wallet_auth_key {
+        return Err(WalletError::InvalidWalletAuth.into());
+    }
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            wallet_auth.key,
+            rent.minimum_balance(WalletAuth::LEN),
+            WalletAuth::LEN.try_into().unwrap(),
+            program_id,
+        ),
+        &[payer.clone(), wallet_auth.clone()],
+        &[&[
+            OWNER.as_bytes(),
+            wallet_config.as_ref(),
+            source_details.owner.as_ref(),
+            &[bump],
+        ]],
+    )?;
+    check_rent_exempt(wallet_auth, rent)?;
+    let user_details = WalletAuth {
+        discriminator: AccountType::WalletAuth,
+        owner: source_details.owner,
+        wallet: *wallet_config,
+        added_time: current_time,
+        id: source_details.id,
+        last_active_at: current_time,
+        last_vote_at: current_time,
+        proposals_created: 0,
+        votes_cast: 0,
+        nft_bound: source_details.nft_bound,
+        roles: source_details.roles,
+        bump,
+        is_initialized: true,
+    };
+    store(&user_details, wallet_auth)
+}
+
+pub fn clone_wallet(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let source_wallet_config = next_account_info(accounts_iter)?;
+    let source_wallet_auth = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer || !wallet_config.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let source_details = load::<WalletConfig>(source_wallet_config, program_id)?;
+    let user_details = load_owner(
+        program_id,
+        source_wallet_config.key,
+        user.key,
+        source_wallet_auth,
+    )?;
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        return Err(WalletError::IncorrectSystemProgram.into());
+    }
+    // every owner of the source wallet is cloned, each once
+    if accounts_iter.len() != 2 * (source_details.owners as usize - 1) {
+        return Err(WalletError::OwnerWalletAuthCountMismatch.into());
+    }
+    let rent = Rent::get()?;
+    let current_time = Clock::get()?.unix_timestamp;
+    clone_owner(
+        program_id,
+        user,
+        wallet_config.key,
+        wallet_auth,
+        &user_details,
+        &rent,
+        current_time,
+    )?;
+    let mut cloned = [0u8; 32];
+    set_bit(&mut cloned, user_details.id);
+    while accounts_iter.len() > 0 {
+        let source_wallet_auth = next_account_info(accounts_iter)?;
+        let wallet_auth = next_account_info(accounts_iter)?;
+        let owner_details = load::<WalletAuth>(source_wallet_auth, program_id)?;
+        let owner_details = load_owner(
+            program_id,
+            source_wallet_config.key,
+            &owner_details.owner,
+            source_wallet_auth,
+        )?;
+        if is_bit_set(&cloned, owner_details.id) {
+            return Err(WalletError::OwnerWalletAuthCountMismatch.into());
+        }
+        set_bit(&mut cloned, owner_details.id);
+        clone_owner(
+            program_id,
+            user,
+            wallet_config.key,
+            wallet_auth,
+            &owner_details,
+            &rent,
+            current_time,
+        )?;
+    }
+    // create wallet config account
+    invoke(
+        &system_instruction::create_account(
+            user.key,
+            wallet_config.key,
+            rent.minimum_balance(WalletConfig::LEN),
+            WalletConfig::LEN.try_into().unwrap(),
+            program_id,
+        ),
+        &[user.clone(), wallet_config.clone()],
+    )?;
+    check_rent_exempt(wallet_config, &rent)?;
+    let (_, authority_bump) = Pubkey::find_program_address(
+        &[AUTHORITY.as_bytes(), wallet_config.key.as_ref()],
+        program_id,
+    );
+    let (_, treasury_bump) = Pubkey::find_program_address(
+        &[TREASURY.as_bytes(), wallet_config.key.as_ref()],
+        program_id,
+    );
+    let mut wallet_info = WalletConfig {
+        discriminator: AccountType::WalletConfig,
+        m: source_details.m,
+        n: source_details.n,
+        owners: source_details.owners,
+        owner_identities: source_details.owner_identities,
+        proposal_lifetime: source_details.proposal_lifetime,
+        rent_floor: rent.minimum_balance(0),
+        total_deposited: 0,
+        total_withdrawn: 0,
+        proposal_count: 0,
+        executors: source_details.executors,
+        epoch: 0,
+        timelock: source_details.timelock,
+        pending_change: PendingChange::None,
+        pending_change_at: 0,
+        min_proposal_lifetime: source_details.min_proposal_lifetime,
+        max_proposal_lifetime: source_details.max_proposal_lifetime,
+        rotation_delay: source_details.rotation_delay,
+        emergency_m: source_details.emergency_m,
+        emergency_lifetime: source_details.emergency_lifetime,
+        mandatory_approvers: source_details.mandatory_approvers,
+        event_count: 0,
+        open_proposals: 0,
+        max_open_proposals: source_details.max_open_proposals,
+        proposal_deposit: source_details.proposal_deposit,
+        keeper_tip: source_details.keeper_tip,
+        last_activity: current_time,
+        beneficiary: source_details.beneficiary,
+        inactivity_period: source_details.inactivity_period,
+        authority_bump,
+        treasury_bump,
+        frozen: false,
+        fixed_threshold: source_details.fixed_threshold,
+        quorum: source_details.quorum,
+        gate_mint: source_details.gate_mint,
+        gate_amount: source_details.gate_amount,
+        callback_program: source_details.callback_program,
+        optimistic_delay: source_details.optimistic_delay,
+        open_accounts: 0,
+        is_initialized: true,
+    };
+    WalletEvent::WalletCloned {
+        wallet: *wallet_config.key,
+        source: *source_wallet_config.key,
+        creator: *user.key,
+        owners: wallet_info.owners,
+    }
+    .emit(&mut wallet_info);
+    store(&wallet_info, wallet_config)
+}
+
+pub fn get_wallet_info(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let wallet_config = next_account_info(accounts_iter)?;
+
+    let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let wallet_info = WalletInfo {
+        m: wallet_details.m,
+        n: wallet_details.n,
+        owners: wallet_details.owners,
+        threshold: wallet_details.threshold(wallet_details.m, wallet_details.n),
+        proposal_lifetime: wallet_details.proposal_lifetime,
+        proposal_count: wallet_details.proposal_count,
+        epoch: wallet_details.epoch,
+        authority: authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?,
+        executors: wallet_details.executors,
+        beneficiary: wallet_details.beneficiary,
+        inactivity_period: wallet_details.inactivity_period,
+        last_activity: wallet_details.last_activity,
+        emergency_threshold: wallet_details.threshold(wallet_details.emergency_m, wallet_details.n),
+        emergency_lifetime: wallet_details.emergency_lifetime,
+        event_count: wallet_details.event_count,
+        open_proposals: wallet_details.open_proposals,
+        max_open_proposals: wallet_details.max_open_proposals,
+        proposal_deposit: wallet_details.proposal_deposit,
+        keeper_tip: wallet_details.keeper_tip,
+        treasury: treasury_address(program_id, wallet_config.key, wallet_details.treasury_bump)?,
+        frozen: wallet_details.frozen,
+        fixed_threshold: wallet_details.fixed_threshold,
+        quorum: wallet_details.quorum,
+        gate_mint: wallet_details.gate_mint,
+        gate_amount: wallet_details.gate_amount,
+        callback_program: wallet_details.callback_program,
+        optimistic_delay: wallet_details.optimistic_delay,
+    };
+    set_return_data(&wallet_info.try_to_vec()?);
+
+    Ok(())
+}
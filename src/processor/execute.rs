@@ -0,0 +1,4134 @@
+use super::accounts::{
+    check_rent_exempt, check_role, load, load_blacklist, load_escrow, load_mint_decimals,
+    load_owner, load_recurring_swap, load_registry, load_stream, load_token_account,
+    load_treasury_stats, load_vault, store, CloseProposalAccounts, ProposalAccounts,
+};
+use super::audit::append_audit_record;
+use super::bitmap::{clear_bit, first_clear_bit, set_bit};
+use super::cpi::{close_escrow, execution_callback, unwrap_transfer};
+use super::metadata::{
+    set_and_verify_collection, update_metadata_accounts_v2, Metadata, TOKEN_METADATA_PROGRAM_ID,
+};
+use super::name_service::{self, name_owner, NAME_SERVICE_PROGRAM_ID};
+use super::oracle::check_price_condition;
+use super::ownership::rotate_owner_key;
+use super::pda::{
+    allowance_address, authority_address, aux_token_address, proposal_address, template_address,
+    treasury_address, vault_authority_address, vote_count_address, ALLOWANCE, AUDIT, AUTHORITY,
+    BLACKLIST, CLAIM, ESCROW, OWNER, REGISTRY, SPLIT_STAKE, STATS, STREAM, SWAP, TEMPLATE,
+    TOKEN_MULTISIG, TREASURY, VAULT, VESTING, WALLET_STATS,
+};
+use super::proposal::{mandatory_approved, quorum_reached};
+use super::stake_pool::{self, STAKE_POOL_PROGRAM_ID};
+use super::stats::record_stats;
+use crate::error::WalletError;
+use crate::event::WalletEvent;
+use crate::instruction::WalletInstruction;
+use crate::state::{
+    AccountType, Allowance, AuditAction, AuditLog, Blacklist, Claim, Escrow, ExecutionSummary,
+    Payment, PendingChange, ProgramRegistry, Proposal, ProposalType, ProposalValidation,
+    RecurringSwap, Stream, Template, TreasuryStats, Vault, Vesting, VoteCount, WalletAccount,
+    WalletAuth, WalletConfig, WalletStats, BASIS_POINTS, DISCRIMINATOR_LEN, MAX_BLACKLISTED,
+    MAX_REGISTERED_PROGRAMS, ROLE_ALL, ROLE_EXECUTE, ROLE_OPTIMISTIC, SPEND_WINDOW_DAY,
+    SPEND_WINDOW_WEEK, TRANSFER_ALL,
+};
+use borsh::BorshSerialize;
+use solana_address_lookup_table_program::{
+    instruction as lookup_table_instruction, ID as LOOKUP_TABLE_PROGRAM_ID,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{invoke, invoke_signed, set_return_data},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    stake::{
+        instruction as stake_instruction, program::ID as STAKE_PROGRAM_ID, state::StakeStateV2,
+    },
+    system_instruction,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::{rent::Rent, Sysvar},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+    ID as ASSOCIATED_TOKEN_PROGRAM_ID,
+};
+use spl_token::{
+    instruction as token_instruction,
+    native_mint::ID as NATIVE_MINT,
+    state::{Account, Mint, Multisig},
+    ID as TOKEN_PROGRAM_ID,
+};
+use spl_token_2022::{
+    extension::{
+        default_account_state::instruction::update_default_account_state,
+        interest_bearing_mint::instruction::update_rate,
+        transfer_fee::instruction::set_transfer_fee,
+    },
+    instruction as token_2022_instruction,
+    state::AccountState,
+    ID as TOKEN_2022_PROGRAM_ID,
+};
+
+// fails if the destination, or the owner of a destination token account, is blacklisted, nothing is blacklisted
+// before the first BlacklistAddress creates the account
+fn check_destination(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    blacklist: &AccountInfo,
+    destination: &AccountInfo,
+) -> ProgramResult {
+    if blacklist.owner != program_id {
+        let (blacklist_key, _) = Pubkey::find_program_address(
+            &[BLACKLIST.as_bytes(), wallet_config.as_ref()],
+            program_id,
+        );
+        if *blacklist.key != blacklist_key {
+            return Err(WalletError::InvalidBlacklist.into());
+        }
+        return Ok(());
+    }
+    let blacklist_details = load_blacklist(program_id, wallet_config, blacklist)?;
+    let mut destinations = vec![*destination.key];
+    if *destination.owner == TOKEN_PROGRAM_ID {
+        if let Ok(account) = Account::unpack(&destination.data.borrow()) {
+            destinations.push(account.owner);
+        }
+    }
+    if destinations
+        .iter()
+        .any(|address| blacklist_details.addresses.contains(address))
+    {
+        return Err(WalletError::DestinationBlacklisted.into());
+    }
+    Ok(())
+}
+
+fn percent_of(balance: u64, basis_points: u16) -> u64 {
+    (balance as u128 * basis_points as u128 / BASIS_POINTS as u128) as u64
+}
+
+// the amounts each payment of a SplitPayment moves out of balance, and their total
+fn split_amounts(
+    balance: u64,
+    by_share: bool,
+    payments: &[Payment],
+) -> Result<(Vec<u64>, u64), ProgramError> {
+    let amounts: Vec<u64> = payments
+        .iter()
+        .map(|payment| {
+            if by_share {
+                percent_of(balance, payment.amount.min(BASIS_POINTS as u64) as u16)
+            } else {
+                payment.amount
+            }
+        })
+        .collect();
+    let total = amounts
+        .iter()
+        .try_fold(0u64, |total, amount| total.checked_add(*amount))
+        .ok_or(ProgramError::InsufficientFunds)?;
+    if total > balance {
+        return Err(ProgramError::InsufficientFunds);
+    }
+    Ok((amounts, total))
+}
+
+fn apply_pending_limits(stats_details: &mut TreasuryStats, current_time: i64) {
+    if stats_details.pending_at != 0 && current_time >= stats_details.pending_at {
+        stats_details.daily_cap = stats_details.pending_daily_cap;
+        stats_details.weekly_cap = stats_details.pending_weekly_cap;
+        stats_details.pending_at = 0;
+    }
+}
+
+// adds amount to the outflow of mint and checks it against the caps, the returned stats are what has to be stored
+// and None means no limit was ever set for the mint
+fn track_outflow(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    mint: &Pubkey,
+    treasury_stats: &AccountInfo,
+    amount: u64,
+) -> Result<Option<TreasuryStats>, ProgramError> {
+    if treasury_stats.owner != program_id {
+        let (treasury_stats_key, _) = Pubkey::find_program_address(
+            &[STATS.as_bytes(), wallet_config.as_ref(), mint.as_ref()],
+            program_id,
+        );
+        if *treasury_stats.key != treasury_stats_key {
+            return Err(WalletError::InvalidTreasuryStats.into());
+        }
+        return Ok(None);
+    }
+    let mut stats_details = load_treasury_stats(program_id, wallet_config, mint, treasury_stats)?;
+    let current_time = Clock::get()?.unix_timestamp;
+    apply_pending_limits(&mut stats_details, current_time);
+    if current_time >= stats_details.day_start.saturating_add(SPEND_WINDOW_DAY) {
+        stats_details.day_start = current_time;
+        stats_details.day_spent = 0;
+    }
+    if current_time >= stats_details.week_start.saturating_add(SPEND_WINDOW_WEEK) {
+        stats_details.week_start = current_time;
+        stats_details.week_spent = 0;
+    }
+    stats_details.day_spent = stats_details.day_spent.saturating_add(amount);
+    stats_details.week_spent = stats_details.week_spent.saturating_add(amount);
+    if (stats_details.daily_cap != 0 && stats_details.day_spent > stats_details.daily_cap)
+        || (stats_details.weekly_cap != 0 && stats_details.week_spent > stats_details.weekly_cap)
+    {
+        return Err(WalletError::SpendLimitExceeded.into());
+    }
+    Ok(Some(stats_details))
+}
+
+// accounts closed by an execution with the account their rent goes to
+type RentRefunds<'a, 'info> = Vec<(&'a AccountInfo<'info>, &'a AccountInfo<'info>)>;
+
+// closes a per-wallet account of seed to destination, nothing is closed if it was never created
+fn close_wallet_singleton<'a, 'info, T: WalletAccount>(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    seed: &str,
+    account: &'a AccountInfo<'info>,
+    destination: &'a AccountInfo<'info>,
+    invalid: WalletError,
+    rent_refunds: &mut RentRefunds<'a, 'info>,
+) -> ProgramResult {
+    let (account_key, _) =
+        Pubkey::find_program_address(&[seed.as_bytes(), wallet_config.as_ref()], program_id);
+    if *account.key != account_key {
+        return Err(invalid.into());
+    }
+    if account.owner != program_id {
+        return Ok(());
+    }
+    load::<T>(account, program_id)?;
+    close_program_account(account, destination, rent_refunds);
+    Ok(())
+}
+
+// an account of the wallet that CloseWallet closes itself, a vault is returned so its authority can sign
+fn load_dependent_account(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    account: &AccountInfo,
+) -> Result<Option<Vault>, ProgramError> {
+    let account_type = account.data.borrow().get(DISCRIMINATOR_LEN).copied();
+    let (wallet, vault) = match account_type {
+        Some(kind) if kind == AccountType::Vault as u8 => {
+            let vault_details = load::<Vault>(account, program_id)?;
+            (vault_details.wallet, Some(vault_details))
+        }
+        Some(kind) if kind == AccountType::Template as u8 => {
+            (load::<Template>(account, program_id)?.wallet, None)
+        }
+        Some(kind) if kind == AccountType::TreasuryStats as u8 => {
+            (load::<TreasuryStats>(account, program_id)?.wallet, None)
+        }
+        Some(kind) if kind == AccountType::Allowance as u8 => {
+            (load::<Allowance>(account, program_id)?.wallet, None)
+        }
+        Some(kind) if kind == AccountType::RecurringSwap as u8 => {
+            (load::<RecurringSwap>(account, program_id)?.wallet, None)
+        }
+        _ => return Err(WalletError::AccountsStillOpen.into()),
+    };
+    if wallet != *wallet_config {
+        return Err(WalletError::AccountWalletMismatch.into());
+    }
+    Ok(vault)
+}
+
+// zeroed so nothing loads the account again within the transaction, its rent goes to destination with the other
+// rent refunds
+fn close_program_account<'a, 'info>(
+    account: &'a AccountInfo<'info>,
+    destination: &'a AccountInfo<'info>,
+    rent_refunds: &mut RentRefunds<'a, 'info>,
+) {
+    account.data.borrow_mut().fill(0);
+    rent_refunds.push((account, destination));
+}
+
+// a lamport move made directly ahead of a cpi that passes only one side of it fails the instruction as unbalanced,
+// so rent is moved once every cpi of the execution is made
+fn refund_rent<'info>(
+    rent_refunds: &[(&AccountInfo<'info>, &AccountInfo<'info>)],
+) -> ProgramResult {
+    for (account, destination) in rent_refunds.iter() {
+        let balance = account.lamports();
+        **account.try_borrow_mut_lamports()? -= balance;
+        **destination.try_borrow_mut_lamports()? += balance;
+    }
+    Ok(())
+}
+
+// the forfeited deposit goes to the treasury and the tip to the keeper, the rest of the proposal's and vote count's
+// rent to refund_destination
+#[allow(clippy::too_many_arguments)]
+fn refund_proposal_rent<'info>(
+    proposal: &AccountInfo<'info>,
+    vote_count: &AccountInfo<'info>,
+    treasury: &AccountInfo,
+    closer: &AccountInfo,
+    refund_destination: &AccountInfo<'info>,
+    forfeited_deposit: u64,
+    keeper_tip: u64,
+) -> ProgramResult {
+    **proposal.try_borrow_mut_lamports()? -= forfeited_deposit;
+    **treasury.try_borrow_mut_lamports()? += forfeited_deposit;
+    **proposal.try_borrow_mut_lamports()? -= keeper_tip;
+    **closer.try_borrow_mut_lamports()? += keeper_tip;
+    refund_rent(&[
+        (proposal, refund_destination),
+        (vote_count, refund_destination),
+    ])
+}
+
+pub fn close_proposal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    // only one of the wallet's executors or an owner allowed to execute can close the proposal
+    let CloseProposalAccounts {
+        closer,
+        closer_wallet_auth,
+        refund_destination,
+        wallet_config,
+        proposal:
+            ProposalAccounts {
+                proposal,
+                vote_count,
+                mut proposal_details,
+                mut voting_details,
+            },
+        audit_log,
+        treasury,
+        wallet_stats,
+        mut wallet_details,
+        keeper,
+    } = CloseProposalAccounts::load_and_validate(program_id, accounts_iter)?;
+
+    if proposal_details.executed {
+        return Err(WalletError::AlreadyExecuted.into());
+    }
+    // an expired proposal, or one voted on by a previous owner set, is only closed
+    // otherwise it must have enough votes to be executed
+    let threshold = voting_details.threshold;
+    let expired = Clock::get()?.unix_timestamp > voting_details.expires_at;
+    let stale = voting_details.epoch != wallet_details.epoch;
+    // a draft is only discarded, by its proposer until it expires
+    if proposal_details.draft && !expired && *closer.key != proposal_details.proposer {
+        return Err(WalletError::NotProposer.into());
+    }
+    let executable = !expired && !stale && !proposal_details.draft;
+    // a proposer can close their own proposal when that does not execute it, executing always takes the execute role
+    let discarded_by_proposer = !executable && *closer.key == proposal_details.proposer;
+    if !keeper && !discarded_by_proposer {
+        let closer_details = load_owner(
+            program_id,
+            wallet_config.key,
+            closer.key,
+            closer_wallet_auth,
+        )
+        .map_err(|_| WalletError::UnauthorizedCloser)?;
+        check_role(&closer_details, ROLE_EXECUTE)?;
+    }
+    // an optimistic proposal nobody objected to needs no votes once its challenge period is over, a vault still
+    // holds a transfer to its own threshold
+    let unchallenged = proposal_details.challenge_ends_at != 0
+        && Clock::get()?.unix_timestamp >= proposal_details.challenge_ends_at;
+    // a transfer out of a vault is held to the vault's threshold snapshotted when it was proposed, checked where the
+    // vault is read
+    let vault_transfer = matches!(
+        proposal_details.proposal,
+        ProposalType::Transfer { vault, .. }
+            | ProposalType::TransferToWallet { vault, .. }
+            | ProposalType::TransferPercent { vault, .. }
+            | ProposalType::SplitPayment { vault, .. }
+            if vault != 0
+    );
+    if executable && !unchallenged && !vault_transfer && voting_details.votes < threshold {
+        return Err(WalletError::InsufficientVotes.into());
+    }
+    if executable && !unchallenged && !mandatory_approved(&voting_details) {
+        return Err(WalletError::MissingMandatoryApproval.into());
+    }
+    if executable && !unchallenged && !quorum_reached(&voting_details) {
+        return Err(WalletError::QuorumNotReached.into());
+    }
+    if executable
+        && wallet_details.frozen
+        && !matches!(proposal_details.proposal, ProposalType::Unfreeze)
+    {
+        return Err(WalletError::WalletFrozen.into());
+    }
+    // the callback and price feed come ahead of the proposal type's accounts as those may run on to the end of the
+    // list, the callback is the one set when execution starts
+    let callback_program = if executable && wallet_details.callback_program != Pubkey::default() {
+        let callback_program = next_account_info(accounts_iter)?;
+        if *callback_program.key != wallet_details.callback_program {
+            return Err(WalletError::InvalidCallbackProgram.into());
+        }
+        Some(callback_program)
+    } else {
+        None
+    };
+    // an unmet condition fails the close so the proposal stays open for the price to be reached
+    if executable && proposal_details.price_condition.feed != Pubkey::default() {
+        let price_feed = next_account_info(accounts_iter)?;
+        check_price_condition(&proposal_details.price_condition, price_feed)?;
+    }
+
+    // close proposal and vote count accounts, recording the execution before any cpi is made
+    proposal_details.executed = executable;
+    proposal_details.is_initialized = false;
+    store(&proposal_details, proposal)?;
+    // stale proposals are not the proposer's fault, their deposit is refunded, the accounts' rent moves last
+    let forfeited_deposit =
+        if expired && !stale && voting_details.votes <= proposal_details.initial_votes {
+            proposal_details.deposit
+        } else {
+            0
+        };
+    if forfeited_deposit != 0 && !proposal_details.treasury_funded {
+        wallet_details.total_deposited = wallet_details
+            .total_deposited
+            .checked_add(forfeited_deposit)
+            .ok_or(WalletError::Overflow)?;
+    }
+    let keeper_tip = if keeper {
+        proposal_details.keeper_tip
+    } else {
+        0
+    };
+    voting_details.is_initialized = false;
+    store(&voting_details, vote_count)?;
+    wallet_details.open_proposals = wallet_details.open_proposals.saturating_sub(1);
+
+    if !executable {
+        if expired {
+            record_stats(
+                program_id,
+                wallet_config.key,
+                wallet_stats,
+                |stats_details| stats_details.proposals_expired += 1,
+            )?;
+        }
+        WalletEvent::ProposalClosed {
+            wallet: *wallet_config.key,
+            proposal: *proposal.key,
+            sequence: proposal_details.sequence,
+        }
+        .emit(&mut wallet_details);
+        store(&wallet_details, wallet_config)?;
+        return refund_proposal_rent(
+            proposal,
+            vote_count,
+            treasury,
+            closer,
+            refund_destination,
+            forfeited_deposit,
+            keeper_tip,
+        );
+    }
+    // the transfer itself fails the whole instruction if it can not be made, so it is counted up front,
+    // a transfer of everything is counted once its balance is read
+    let transferred = match proposal_details.proposal {
+        ProposalType::Transfer {
+            token_mint, amount, ..
+        }
+        | ProposalType::TransferToWallet {
+            token_mint, amount, ..
+        } if amount != TRANSFER_ALL => Some((token_mint, amount)),
+        ProposalType::TransferSol { amount, .. }
+        | ProposalType::TreasuryWithdraw { amount, .. } => Some((NATIVE_MINT, amount)),
+        _ => None,
+    };
+    let slot = Clock::get()?.slot;
+    record_stats(
+        program_id,
+        wallet_config.key,
+        wallet_stats,
+        |stats_details| {
+            stats_details.proposals_executed += 1;
+            stats_details.last_execution_slot = slot;
+            if let Some((mint, amount)) = transferred {
+                stats_details.add_transferred(&mint, amount);
+            }
+        },
+    )?;
+    append_audit_record(
+        program_id,
+        wallet_config.key,
+        audit_log,
+        AuditAction::ProposalExecuted,
+        proposal_details.proposal.kind(),
+        proposal_details.sequence,
+        *proposal.key,
+    )?;
+    WalletEvent::Executed {
+        wallet: *wallet_config.key,
+        proposal: *proposal.key,
+        sequence: proposal_details.sequence,
+    }
+    .emit(&mut wallet_details);
+    let proposal_type = proposal_details.proposal.kind();
+    let mut rent_refunds = RentRefunds::new();
+    match proposal_details.proposal {
+        ProposalType::Transfer {
+            token_mint,
+            receive_account,
+            amount,
+            vault,
+            unwrap,
+            source,
+        } => {
+            let source_account = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+
+            let decimals = load_mint_decimals(mint, &token_mint)?;
+
+            let source_account_details =
+                load_token_account(source_account, &token_mint, wallet_authority.key)?;
+            if let Some(seed) = source {
+                if *source_account.key
+                    != aux_token_address(
+                        program_id,
+                        wallet_config.key,
+                        wallet_authority.key,
+                        &token_mint,
+                        seed,
+                    )
+                {
+                    return Err(WalletError::IncorrectSendAccount.into());
+                }
+            }
+            let amount = if amount == TRANSFER_ALL {
+                let balance = source_account_details.amount;
+                record_stats(
+                    program_id,
+                    wallet_config.key,
+                    wallet_stats,
+                    |stats_details| stats_details.add_transferred(&token_mint, balance),
+                )?;
+                balance
+            } else {
+                amount
+            };
+            if source_account_details.amount < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            if *destination_account.key != receive_account {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            let bump = if vault == 0 {
+                wallet_details.authority_bump
+            } else {
+                let vault_account = next_account_info(accounts_iter)?;
+                let vault_details =
+                    load_vault(program_id, wallet_config.key, vault, vault_account)?;
+                // held to the vault's threshold snapshotted when the transfer was proposed
+                if voting_details.votes < voting_details.threshold {
+                    return Err(WalletError::InsufficientVotes.into());
+                }
+                vault_details.authority_bump
+            };
+            let wallet_authority_key =
+                vault_authority_address(program_id, wallet_config.key, vault, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *token_program.key != TOKEN_PROGRAM_ID {
+                return Err(WalletError::IncorrectTokenProgram.into());
+            }
+            check_destination(
+                program_id,
+                wallet_config.key,
+                blacklist,
+                destination_account,
+            )?;
+            if let Some(stats_details) = track_outflow(
+                program_id,
+                wallet_config.key,
+                &token_mint,
+                treasury_stats,
+                amount,
+            )? {
+                store(&stats_details, treasury_stats)?;
+            }
+            let vault_seed = [vault];
+            let bump_seed = [bump];
+            let mut signer_seeds: Vec<&[u8]> =
+                vec![AUTHORITY.as_bytes(), wallet_config.key.as_ref()];
+            if vault != 0 {
+                signer_seeds.push(&vault_seed);
+            }
+            signer_seeds.push(&bump_seed);
+            if unwrap {
+                let unwrap_account = next_account_info(accounts_iter)?;
+                let native_mint = next_account_info(accounts_iter)?;
+                let system_program = next_account_info(accounts_iter)?;
+                unwrap_transfer(
+                    program_id,
+                    wallet_config.key,
+                    proposal_details.sequence,
+                    amount,
+                    &signer_seeds,
+                    source_account,
+                    destination_account,
+                    wallet_authority,
+                    unwrap_account,
+                    native_mint,
+                    token_program,
+                    system_program,
+                )?;
+            } else {
+                invoke_signed(
+                    &token_instruction::transfer_checked(
+                        token_program.key,
+                        source_account.key,
+                        mint.key,
+                        destination_account.key,
+                        wallet_authority.key,
+                        &[],
+                        amount,
+                        decimals,
+                    )?,
+                    &[
+                        source_account.clone(),
+                        mint.clone(),
+                        destination_account.clone(),
+                        wallet_authority.clone(),
+                    ],
+                    &[&signer_seeds],
+                )?;
+            }
+
+            WalletEvent::TokensTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                mint: token_mint,
+                source: *source_account.key,
+                destination: *destination_account.key,
+                amount,
+            }
+            .emit(&mut wallet_details);
+        }
+        ProposalType::TransferPercent {
+            token_mint,
+            receive_account,
+            basis_points,
+            vault,
+        } => {
+            let source_account = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+
+            let decimals = load_mint_decimals(mint, &token_mint)?;
+
+            let source_account_details =
+                load_token_account(source_account, &token_mint, wallet_authority.key)?;
+            // the share is taken of the balance at execution, rounded down
+            let amount = percent_of(source_account_details.amount, basis_points);
+            record_stats(
+                program_id,
+                wallet_config.key,
+                wallet_stats,
+                |stats_details| stats_details.add_transferred(&token_mint, amount),
+            )?;
+            if *destination_account.key != receive_account {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            let bump = if vault == 0 {
+                wallet_details.authority_bump
+            } else {
+                let vault_account = next_account_info(accounts_iter)?;
+                let vault_details =
+                    load_vault(program_id, wallet_config.key, vault, vault_account)?;
+                // held to the vault's threshold snapshotted when the transfer was proposed
+                if voting_details.votes < voting_details.threshold {
+                    return Err(WalletError::InsufficientVotes.into());
+                }
+                vault_details.authority_bump
+            };
+            let wallet_authority_key =
+                vault_authority_address(program_id, wallet_config.key, vault, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *token_program.key != TOKEN_PROGRAM_ID {
+                return Err(WalletError::IncorrectTokenProgram.into());
+            }
+            check_destination(
+                program_id,
+                wallet_config.key,
+                blacklist,
+                destination_account,
+            )?;
+            if let Some(stats_details) = track_outflow(
+                program_id,
+                wallet_config.key,
+                &token_mint,
+                treasury_stats,
+                amount,
+            )? {
+                store(&stats_details, treasury_stats)?;
+            }
+            let vault_seed = [vault];
+            let bump_seed = [bump];
+            let mut signer_seeds: Vec<&[u8]> =
+                vec![AUTHORITY.as_bytes(), wallet_config.key.as_ref()];
+            if vault != 0 {
+                signer_seeds.push(&vault_seed);
+            }
+            signer_seeds.push(&bump_seed);
+            invoke_signed(
+                &token_instruction::transfer_checked(
+                    token_program.key,
+                    source_account.key,
+                    mint.key,
+                    destination_account.key,
+                    wallet_authority.key,
+                    &[],
+                    amount,
+                    decimals,
+                )?,
+                &[
+                    source_account.clone(),
+                    mint.clone(),
+                    destination_account.clone(),
+                    wallet_authority.clone(),
+                ],
+                &[&signer_seeds],
+            )?;
+
+            WalletEvent::TokensTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                mint: token_mint,
+                source: *source_account.key,
+                destination: *destination_account.key,
+                amount,
+            }
+            .emit(&mut wallet_details);
+        }
+        ProposalType::SplitPayment {
+            token_mint,
+            vault,
+            by_share,
+            payments,
+        } => {
+            let source_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+
+            let decimals = load_mint_decimals(mint, &token_mint)?;
+
+            let source_account_details =
+                load_token_account(source_account, &token_mint, wallet_authority.key)?;
+            let (amounts, total) =
+                split_amounts(source_account_details.amount, by_share, &payments)?;
+            record_stats(
+                program_id,
+                wallet_config.key,
+                wallet_stats,
+                |stats_details| stats_details.add_transferred(&token_mint, total),
+            )?;
+            let bump = if vault == 0 {
+                wallet_details.authority_bump
+            } else {
+                let vault_account = next_account_info(accounts_iter)?;
+                let vault_details =
+                    load_vault(program_id, wallet_config.key, vault, vault_account)?;
+                // held to the vault's threshold snapshotted when the transfer was proposed
+                if voting_details.votes < voting_details.threshold {
+                    return Err(WalletError::InsufficientVotes.into());
+                }
+                vault_details.authority_bump
+            };
+            let wallet_authority_key =
+                vault_authority_address(program_id, wallet_config.key, vault, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *token_program.key != TOKEN_PROGRAM_ID {
+                return Err(WalletError::IncorrectTokenProgram.into());
+            }
+            if accounts_iter.len() != payments.len() {
+                return Err(WalletError::AccountCountMismatch.into());
+            }
+            if let Some(stats_details) = track_outflow(
+                program_id,
+                wallet_config.key,
+                &token_mint,
+                treasury_stats,
+                total,
+            )? {
+                store(&stats_details, treasury_stats)?;
+            }
+            let vault_seed = [vault];
+            let bump_seed = [bump];
+            let mut signer_seeds: Vec<&[u8]> =
+                vec![AUTHORITY.as_bytes(), wallet_config.key.as_ref()];
+            if vault != 0 {
+                signer_seeds.push(&vault_seed);
+            }
+            signer_seeds.push(&bump_seed);
+            for (payment, amount) in payments.iter().zip(amounts) {
+                let destination_account = next_account_info(accounts_iter)?;
+                if *destination_account.key != payment.receive_account {
+                    return Err(WalletError::IncorrectReceiveAccount.into());
+                }
+                check_destination(
+                    program_id,
+                    wallet_config.key,
+                    blacklist,
+                    destination_account,
+                )?;
+                invoke_signed(
+                    &token_instruction::transfer_checked(
+                        token_program.key,
+                        source_account.key,
+                        mint.key,
+                        destination_account.key,
+                        wallet_authority.key,
+                        &[],
+                        amount,
+                        decimals,
+                    )?,
+                    &[
+                        source_account.clone(),
+                        mint.clone(),
+                        destination_account.clone(),
+                        wallet_authority.clone(),
+                    ],
+                    &[&signer_seeds],
+                )?;
+
+                WalletEvent::TokensTransferred {
+                    wallet: *wallet_config.key,
+                    proposal: *proposal.key,
+                    proposal_type,
+                    mint: token_mint,
+                    source: *source_account.key,
+                    destination: *destination_account.key,
+                    amount,
+                }
+                .emit(&mut wallet_details);
+            }
+        }
+        ProposalType::TransferToWallet {
+            token_mint,
+            recipient,
+            amount,
+            vault,
+            treasury_funded,
+        } => {
+            let source_account = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let recipient_account = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+            let payer = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let associated_token_program = next_account_info(accounts_iter)?;
+
+            let source_account_details =
+                load_token_account(source_account, &token_mint, wallet_authority.key)?;
+            let amount = if amount == TRANSFER_ALL {
+                let balance = source_account_details.amount;
+                record_stats(
+                    program_id,
+                    wallet_config.key,
+                    wallet_stats,
+                    |stats_details| stats_details.add_transferred(&token_mint, balance),
+                )?;
+                balance
+            } else {
+                amount
+            };
+            if source_account_details.amount < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            if *recipient_account.key != recipient
+                || *mint.key != token_mint
+                || *destination_account.key != get_associated_token_address(&recipient, &token_mint)
+            {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            let decimals = load_mint_decimals(mint, &token_mint)?;
+            let bump = if vault == 0 {
+                wallet_details.authority_bump
+            } else {
+                let vault_account = next_account_info(accounts_iter)?;
+                let vault_details =
+                    load_vault(program_id, wallet_config.key, vault, vault_account)?;
+                // held to the vault's threshold snapshotted when the transfer was proposed
+                if voting_details.votes < voting_details.threshold {
+                    return Err(WalletError::InsufficientVotes.into());
+                }
+                vault_details.authority_bump
+            };
+            let wallet_authority_key =
+                vault_authority_address(program_id, wallet_config.key, vault, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID
+                || *token_program.key != TOKEN_PROGRAM_ID
+                || *associated_token_program.key != ASSOCIATED_TOKEN_PROGRAM_ID
+            {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let authority_bump = wallet_details.authority_bump;
+            if treasury_funded {
+                if *payer.key != authority_address(program_id, wallet_config.key, authority_bump)? {
+                    return Err(WalletError::InvalidWalletAuthority.into());
+                }
+            } else if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            check_destination(program_id, wallet_config.key, blacklist, recipient_account)?;
+            if let Some(stats_details) = track_outflow(
+                program_id,
+                wallet_config.key,
+                &token_mint,
+                treasury_stats,
+                amount,
+            )? {
+                store(&stats_details, treasury_stats)?;
+            }
+            // the receive account is created by the associated token program, the authority seeds are unused
+            // unless the treasury pays
+            if destination_account.data_is_empty() {
+                invoke_signed(
+                    &create_associated_token_account(
+                        payer.key,
+                        &recipient,
+                        &token_mint,
+                        token_program.key,
+                    ),
+                    &[
+                        payer.clone(),
+                        destination_account.clone(),
+                        recipient_account.clone(),
+                        mint.clone(),
+                        system_program.clone(),
+                        token_program.clone(),
+                        associated_token_program.clone(),
+                    ],
+                    &[&[
+                        AUTHORITY.as_bytes(),
+                        wallet_config.key.as_ref(),
+                        &[authority_bump],
+                    ]],
+                )?;
+                if treasury_funded && payer.lamports() < wallet_details.rent_floor {
+                    return Err(WalletError::BelowRentFloor.into());
+                }
+            }
+            let vault_seed = [vault];
+            let bump_seed = [bump];
+            let mut signer_seeds: Vec<&[u8]> =
+                vec![AUTHORITY.as_bytes(), wallet_config.key.as_ref()];
+            if vault != 0 {
+                signer_seeds.push(&vault_seed);
+            }
+            signer_seeds.push(&bump_seed);
+            invoke_signed(
+                &token_instruction::transfer_checked(
+                    token_program.key,
+                    source_account.key,
+                    mint.key,
+                    destination_account.key,
+                    wallet_authority.key,
+                    &[],
+                    amount,
+                    decimals,
+                )?,
+                &[
+                    source_account.clone(),
+                    mint.clone(),
+                    destination_account.clone(),
+                    wallet_authority.clone(),
+                ],
+                &[&signer_seeds],
+            )?;
+
+            WalletEvent::TokensTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                mint: token_mint,
+                source: *source_account.key,
+                destination: *destination_account.key,
+                amount,
+            }
+            .emit(&mut wallet_details);
+        }
+        ProposalType::AddOwner { user } | ProposalType::AddNftOwner { mint: user } => {
+            let payer = next_account_info(accounts_iter)?;
+            let wallet_auth = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let (wallet_auth_key, bump) = Pubkey::find_program_address(
+                &[OWNER.as_bytes(), wallet_config.key.as_ref(), user.as_ref()],
+                program_id,
+            );
+            if *wallet_auth.key != wallet_auth_key {
+                return Err(WalletError::InvalidWalletAuth.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            // the seat follows a single token, a mint that can split it or mint more would hand out copies of the
+            // seat and a freeze authority could take it from its holder
+            if let ProposalType::AddNftOwner { mint } = proposal_details.proposal {
+                let mint_account = next_account_info(accounts_iter)?;
+                if *mint_account.key != mint || *mint_account.owner != TOKEN_PROGRAM_ID {
+                    return Err(WalletError::InvalidMint.into());
+                }
+                let mint_details = Mint::unpack(&mint_account.data.borrow())
+                    .map_err(|_| WalletError::InvalidMint)?;
+                if mint_details.decimals != 0
+                    || mint_details.supply != 1
+                    || mint_details.mint_authority.is_some()
+                    || mint_details.freeze_authority.is_some()
+                {
+                    return Err(WalletError::NotNftMint.into());
+                }
+            }
+
+            if wallet_details.owners == 255 {
+                return Err(WalletError::MaximumOwnersReached.into());
+            }
+            let id = first_clear_bit(&wallet_details.owner_identities)
+                .ok_or(WalletError::MaximumOwnersReached)?;
+            set_bit(&mut wallet_details.owner_identities, id);
+            wallet_details.owners += 1;
+            wallet_details.epoch += 1;
+            store(&wallet_details, wallet_config)?;
+
+            // create wallet auth
+            let account_size: u64 = WalletAuth::LEN.try_into().unwrap();
+            let rent = Rent::get()?;
+            let rent_amount = rent.minimum_balance(WalletAuth::LEN);
+
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    wallet_auth.key,
+                    rent_amount,
+                    account_size,
+                    program_id,
+                ),
+                &[payer.clone(), wallet_auth.clone()],
+                &[&[
+                    OWNER.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    user.as_ref(),
+                    &[bump],
+                ]],
+            )?;
+            check_rent_exempt(wallet_auth, &rent)?;
+            // initialize wallet auth
+            let current_time = Clock::get()?.unix_timestamp;
+            let user_details = WalletAuth {
+                discriminator: AccountType::WalletAuth,
+                owner: user,
+                wallet: *wallet_config.key,
+                added_time: current_time,
+                id,
+                last_active_at: current_time,
+                last_vote_at: current_time,
+                proposals_created: 0,
+                votes_cast: 0,
+                nft_bound: matches!(proposal_details.proposal, ProposalType::AddNftOwner { .. }),
+                roles: ROLE_ALL,
+                bump,
+                is_initialized: true,
+            };
+            store(&user_details, wallet_auth)?;
+
+            WalletEvent::OwnerAdded {
+                wallet: *wallet_config.key,
+                owner: user,
+                id: user_details.id,
+            }
+            .emit(&mut wallet_details);
+        }
+        // lifetime, lifetime bound, threshold, threshold mode, timelock, rotation delay and emergency policy changes only take effect through ApplyPendingChange once the
+        // timelock has passed, a change approved while another is pending replaces it
+        ProposalType::ChangeProposalLifetime { duration } => {
+            queue_change(
+                &mut wallet_details,
+                PendingChange::ProposalLifetime { duration },
+            )?;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::ChangeThreshold { m, n } => {
+            queue_change(&mut wallet_details, PendingChange::Threshold { m, n })?;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::ChangeTimelock { delay } => {
+            queue_change(&mut wallet_details, PendingChange::Timelock { delay })?;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetLifetimeBounds { min, max } => {
+            queue_change(
+                &mut wallet_details,
+                PendingChange::LifetimeBounds { min, max },
+            )?;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::ChangeRotationDelay { delay } => {
+            queue_change(&mut wallet_details, PendingChange::RotationDelay { delay })?;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetEmergencyPolicy { m, lifetime } => {
+            queue_change(
+                &mut wallet_details,
+                PendingChange::EmergencyPolicy { m, lifetime },
+            )?;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetThresholdMode { fixed } => {
+            queue_change(&mut wallet_details, PendingChange::ThresholdMode { fixed })?;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::FreezeAccount { mint, account }
+        | ProposalType::ThawAccount { mint, account } => {
+            let token_account = next_account_info(accounts_iter)?;
+            let mint_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+
+            if *token_account.key != account {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if *mint_account.key != mint {
+                return Err(WalletError::InvalidMint.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *token_program.key != TOKEN_PROGRAM_ID {
+                return Err(WalletError::IncorrectTokenProgram.into());
+            }
+            let mint_details = Mint::unpack(&mint_account.data.borrow())?;
+            if !mint_details.freeze_authority.contains(wallet_authority.key) {
+                return Err(WalletError::NotFreezeAuthority.into());
+            }
+            let instruction = if matches!(
+                proposal_details.proposal,
+                ProposalType::FreezeAccount { .. }
+            ) {
+                token_instruction::freeze_account
+            } else {
+                token_instruction::thaw_account
+            };
+            invoke_signed(
+                &instruction(
+                    token_program.key,
+                    token_account.key,
+                    mint_account.key,
+                    wallet_authority.key,
+                    &[],
+                )?,
+                &[
+                    token_account.clone(),
+                    mint_account.clone(),
+                    wallet_authority.clone(),
+                ],
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+        }
+        // the token program checks that the wallet authority is the authority the change needs
+        ProposalType::CloseMint { mint }
+        | ProposalType::SetTransferFee { mint, .. }
+        | ProposalType::SetInterestRate { mint, .. }
+        | ProposalType::SetDefaultAccountState { mint, .. } => {
+            let mint_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+
+            if *mint_account.key != mint {
+                return Err(WalletError::InvalidMint.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *token_program.key != TOKEN_2022_PROGRAM_ID {
+                return Err(WalletError::IncorrectTokenProgram.into());
+            }
+            let instruction = match proposal_details.proposal {
+                ProposalType::CloseMint { .. } => token_2022_instruction::close_account(
+                    token_program.key,
+                    mint_account.key,
+                    wallet_authority.key,
+                    wallet_authority.key,
+                    &[],
+                )?,
+                ProposalType::SetTransferFee {
+                    basis_points,
+                    maximum_fee,
+                    ..
+                } => set_transfer_fee(
+                    token_program.key,
+                    mint_account.key,
+                    wallet_authority.key,
+                    &[],
+                    basis_points,
+                    maximum_fee,
+                )?,
+                ProposalType::SetInterestRate { rate, .. } => update_rate(
+                    token_program.key,
+                    mint_account.key,
+                    wallet_authority.key,
+                    &[],
+                    rate,
+                )?,
+                _ => {
+                    let state = if matches!(
+                        proposal_details.proposal,
+                        ProposalType::SetDefaultAccountState { frozen: true, .. }
+                    ) {
+                        AccountState::Frozen
+                    } else {
+                        AccountState::Initialized
+                    };
+                    update_default_account_state(
+                        token_program.key,
+                        mint_account.key,
+                        wallet_authority.key,
+                        &[],
+                        &state,
+                    )?
+                }
+            };
+            invoke_signed(
+                &instruction,
+                &[mint_account.clone(), wallet_authority.clone()],
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+        }
+        // the metadata program checks the collection accounts, the wallet authority must already be the update
+        // authority of the metadata
+        ProposalType::UpdateMetadata { metadata, .. }
+        | ProposalType::SetAndVerifyCollection { metadata, .. }
+        | ProposalType::TransferUpdateAuthority { metadata, .. } => {
+            let metadata_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let metadata_program = next_account_info(accounts_iter)?;
+
+            if *metadata_account.key != metadata
+                || *metadata_account.owner != TOKEN_METADATA_PROGRAM_ID
+            {
+                return Err(WalletError::InvalidMetadata.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *metadata_program.key != TOKEN_METADATA_PROGRAM_ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let current = Metadata::unpack(&metadata_account.data.borrow())?;
+            if current.update_authority != wallet_authority_key {
+                return Err(WalletError::InvalidMetadata.into());
+            }
+            let mut account_infos = vec![metadata_account.clone(), wallet_authority.clone()];
+            let instruction = match proposal_details.proposal {
+                ProposalType::UpdateMetadata {
+                    name,
+                    symbol,
+                    uri,
+                    seller_fee_basis_points,
+                    ..
+                } => update_metadata_accounts_v2(
+                    metadata_account.key,
+                    wallet_authority.key,
+                    None,
+                    Some(current.data_v2(&name, &symbol, &uri, seller_fee_basis_points)),
+                ),
+                ProposalType::SetAndVerifyCollection {
+                    collection_mint,
+                    sized,
+                    ..
+                } => {
+                    let collection_mint_account = next_account_info(accounts_iter)?;
+                    let collection_metadata = next_account_info(accounts_iter)?;
+                    let collection_master_edition = next_account_info(accounts_iter)?;
+
+                    if *collection_mint_account.key != collection_mint {
+                        return Err(WalletError::InvalidMint.into());
+                    }
+                    account_infos.extend([
+                        collection_mint_account.clone(),
+                        collection_metadata.clone(),
+                        collection_master_edition.clone(),
+                    ]);
+                    set_and_verify_collection(
+                        metadata_account.key,
+                        wallet_authority.key,
+                        collection_mint_account.key,
+                        collection_metadata.key,
+                        collection_master_edition.key,
+                        sized,
+                    )
+                }
+                ProposalType::TransferUpdateAuthority { new_authority, .. } => {
+                    update_metadata_accounts_v2(
+                        metadata_account.key,
+                        wallet_authority.key,
+                        Some(new_authority),
+                        None,
+                    )
+                }
+                _ => unreachable!(),
+            };
+            invoke_signed(
+                &instruction,
+                &account_infos,
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+        }
+        // the name service checks the record fits in the name account
+        ProposalType::TransferDomain { name_account, .. }
+        | ProposalType::UpdateDomainRecord { name_account, .. } => {
+            let name_account_info = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let name_program = next_account_info(accounts_iter)?;
+
+            if *name_account_info.key != name_account
+                || *name_account_info.owner != NAME_SERVICE_PROGRAM_ID
+            {
+                return Err(WalletError::InvalidNameAccount.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *name_program.key != NAME_SERVICE_PROGRAM_ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            if name_owner(&name_account_info.data.borrow())? != wallet_authority_key {
+                return Err(WalletError::InvalidNameAccount.into());
+            }
+            let instruction = match proposal_details.proposal {
+                ProposalType::TransferDomain { new_owner, .. } => {
+                    name_service::transfer(name_account_info.key, wallet_authority.key, &new_owner)
+                }
+                ProposalType::UpdateDomainRecord { offset, data, .. } => {
+                    name_service::update(name_account_info.key, wallet_authority.key, offset, &data)
+                }
+                _ => unreachable!(),
+            };
+            invoke_signed(
+                &instruction,
+                &[name_account_info.clone(), wallet_authority.clone()],
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+        }
+        ProposalType::CreateTokenMultisig { m, signers } => {
+            let payer = next_account_info(accounts_iter)?;
+            let multisig = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let sequence = proposal_details.sequence;
+            let (multisig_key, bump) = Pubkey::find_program_address(
+                &[
+                    TOKEN_MULTISIG.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                ],
+                program_id,
+            );
+            if *multisig.key != multisig_key {
+                return Err(WalletError::InvalidTokenMultisig.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            if *token_program.key != TOKEN_PROGRAM_ID {
+                return Err(WalletError::IncorrectTokenProgram.into());
+            }
+            // initialize_multisig2 takes the signers as accounts too
+            let mut account_infos = vec![multisig.clone()];
+            for signer in signers.iter() {
+                let signer_account = next_account_info(accounts_iter)?;
+                if signer_account.key != signer {
+                    return Err(WalletError::InvalidTokenMultisig.into());
+                }
+                account_infos.push(signer_account.clone());
+            }
+            let account_size: u64 = Multisig::LEN.try_into().unwrap();
+            let rent = Rent::get()?;
+            let rent_amount = rent.minimum_balance(Multisig::LEN);
+
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    multisig.key,
+                    rent_amount,
+                    account_size,
+                    token_program.key,
+                ),
+                &[payer.clone(), multisig.clone()],
+                &[&[
+                    TOKEN_MULTISIG.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                    &[bump],
+                ]],
+            )?;
+            let signer_keys: Vec<&Pubkey> = signers.iter().collect();
+            invoke(
+                &token_instruction::initialize_multisig2(
+                    token_program.key,
+                    multisig.key,
+                    &signer_keys,
+                    m,
+                )?,
+                &account_infos,
+            )?;
+        }
+        ProposalType::MultisigTransfer {
+            multisig,
+            token_mint,
+            receive_account,
+            amount,
+        } => {
+            let source_account = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let multisig_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+
+            let decimals = load_mint_decimals(mint, &token_mint)?;
+            if *multisig_account.key != multisig || *multisig_account.owner != TOKEN_PROGRAM_ID {
+                return Err(WalletError::InvalidTokenMultisig.into());
+            }
+            let multisig_details = Multisig::unpack(&multisig_account.data.borrow())?;
+            let source_account_details = Account::unpack(&source_account.data.borrow())?;
+            if source_account_details.mint != token_mint || source_account_details.owner != multisig
+            {
+                return Err(WalletError::IncorrectSendAccount.into());
+            }
+            if source_account_details.amount < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            if *destination_account.key != receive_account {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key
+                || !multisig_details.signers[..multisig_details.n as usize]
+                    .contains(wallet_authority.key)
+            {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *token_program.key != TOKEN_PROGRAM_ID {
+                return Err(WalletError::IncorrectTokenProgram.into());
+            }
+            // the wallet authority signs here, the rest of the multisig's threshold signs the transaction
+            let mut signers = vec![wallet_authority];
+            signers.extend(accounts_iter.by_ref());
+            let signer_keys: Vec<&Pubkey> = signers.iter().map(|signer| signer.key).collect();
+            let mut account_infos = vec![
+                source_account.clone(),
+                mint.clone(),
+                destination_account.clone(),
+                multisig_account.clone(),
+            ];
+            account_infos.extend(signers.iter().map(|signer| (*signer).clone()));
+            invoke_signed(
+                &token_instruction::transfer_checked(
+                    token_program.key,
+                    source_account.key,
+                    mint.key,
+                    destination_account.key,
+                    multisig_account.key,
+                    &signer_keys,
+                    amount,
+                    decimals,
+                )?,
+                &account_infos,
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+
+            WalletEvent::TokensTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                mint: token_mint,
+                source: *source_account.key,
+                destination: *destination_account.key,
+                amount,
+            }
+            .emit(&mut wallet_details);
+        }
+        ProposalType::ChangeOwnerKey {
+            old_owner,
+            new_owner,
+        } => {
+            let payer = next_account_info(accounts_iter)?;
+            let old_wallet_auth = next_account_info(accounts_iter)?;
+            let new_wallet_auth = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+
+            rotate_owner_key(
+                program_id,
+                wallet_config,
+                &mut wallet_details,
+                payer,
+                old_wallet_auth,
+                new_wallet_auth,
+                system_program,
+                old_owner,
+                new_owner,
+            )?;
+        }
+        ProposalType::SetSpendLimit {
+            mint,
+            daily_cap,
+            weekly_cap,
+        } => {
+            let payer = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            let current_time = Clock::get()?.unix_timestamp;
+            if treasury_stats.owner != program_id {
+                let (treasury_stats_key, bump) = Pubkey::find_program_address(
+                    &[STATS.as_bytes(), wallet_config.key.as_ref(), mint.as_ref()],
+                    program_id,
+                );
+                if *treasury_stats.key != treasury_stats_key {
+                    return Err(WalletError::InvalidTreasuryStats.into());
+                }
+                let account_size: u64 = TreasuryStats::LEN.try_into().unwrap();
+                let rent = Rent::get()?;
+                let rent_amount = rent.minimum_balance(TreasuryStats::LEN);
+                invoke_signed(
+                    &system_instruction::create_account(
+                        payer.key,
+                        treasury_stats.key,
+                        rent_amount,
+                        account_size,
+                        program_id,
+                    ),
+                    &[payer.clone(), treasury_stats.clone()],
+                    &[&[
+                        STATS.as_bytes(),
+                        wallet_config.key.as_ref(),
+                        mint.as_ref(),
+                        &[bump],
+                    ]],
+                )?;
+                check_rent_exempt(treasury_stats, &rent)?;
+                wallet_details.open_accounts += 1;
+                let stats_details = TreasuryStats {
+                    discriminator: AccountType::TreasuryStats,
+                    wallet: *wallet_config.key,
+                    mint,
+                    daily_cap,
+                    weekly_cap,
+                    pending_daily_cap: 0,
+                    pending_weekly_cap: 0,
+                    pending_at: 0,
+                    day_start: current_time,
+                    day_spent: 0,
+                    week_start: current_time,
+                    week_spent: 0,
+                    bump,
+                    is_initialized: true,
+                };
+                store(&stats_details, treasury_stats)?;
+            } else {
+                let mut stats_details =
+                    load_treasury_stats(program_id, wallet_config.key, &mint, treasury_stats)?;
+                apply_pending_limits(&mut stats_details, current_time);
+                // tighter caps apply at once, looser ones wait out the wallet's timelock so a compromised quorum
+                // can not lift the caps and drain the wallet in one go
+                let looser = |new: u64, old: u64| old != 0 && (new == 0 || new > old);
+                if looser(daily_cap, stats_details.daily_cap)
+                    || looser(weekly_cap, stats_details.weekly_cap)
+                {
+                    stats_details.pending_daily_cap = daily_cap;
+                    stats_details.pending_weekly_cap = weekly_cap;
+                    stats_details.pending_at = current_time.saturating_add(wallet_details.timelock);
+                } else {
+                    stats_details.daily_cap = daily_cap;
+                    stats_details.weekly_cap = weekly_cap;
+                    stats_details.pending_at = 0;
+                }
+                store(&stats_details, treasury_stats)?;
+            }
+        }
+        ProposalType::BlacklistAddress { address } => {
+            let payer = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+
+            let mut blacklist_details = if blacklist.data_is_empty() {
+                if !payer.is_signer {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                let (blacklist_key, bump) = Pubkey::find_program_address(
+                    &[BLACKLIST.as_bytes(), wallet_config.key.as_ref()],
+                    program_id,
+                );
+                if *blacklist.key != blacklist_key {
+                    return Err(WalletError::InvalidBlacklist.into());
+                }
+                if *system_program.key != SYSTEM_PROGRAM_ID {
+                    return Err(WalletError::IncorrectSystemProgram.into());
+                }
+                // create blacklist
+                let account_size: u64 = Blacklist::LEN.try_into().unwrap();
+                let rent = Rent::get()?;
+                let rent_amount = rent.minimum_balance(Blacklist::LEN);
+                invoke_signed(
+                    &system_instruction::create_account(
+                        payer.key,
+                        blacklist.key,
+                        rent_amount,
+                        account_size,
+                        program_id,
+                    ),
+                    &[payer.clone(), blacklist.clone()],
+                    &[&[BLACKLIST.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+                )?;
+                check_rent_exempt(blacklist, &rent)?;
+                Blacklist {
+                    discriminator: AccountType::Blacklist,
+                    wallet: *wallet_config.key,
+                    addresses: [Pubkey::default(); MAX_BLACKLISTED],
+                    bump,
+                    is_initialized: true,
+                }
+            } else {
+                load_blacklist(program_id, wallet_config.key, blacklist)?
+            };
+            if blacklist_details.addresses.contains(&address) {
+                return Err(WalletError::AddressAlreadyBlacklisted.into());
+            }
+            let slot = blacklist_details
+                .addresses
+                .iter_mut()
+                .find(|slot| **slot == Pubkey::default())
+                .ok_or(WalletError::BlacklistFull)?;
+            *slot = address;
+            store(&blacklist_details, blacklist)?;
+        }
+        ProposalType::UnblacklistAddress { address } => {
+            let blacklist = next_account_info(accounts_iter)?;
+
+            let mut blacklist_details = load_blacklist(program_id, wallet_config.key, blacklist)?;
+            let slot = blacklist_details
+                .addresses
+                .iter_mut()
+                .find(|slot| **slot == address)
+                .ok_or(WalletError::AddressNotBlacklisted)?;
+            *slot = Pubkey::default();
+            store(&blacklist_details, blacklist)?;
+        }
+        ProposalType::CancelPendingChange => {
+            if wallet_details.pending_change == PendingChange::None {
+                return Err(WalletError::NoPendingChange.into());
+            }
+            wallet_details.pending_change = PendingChange::None;
+            wallet_details.pending_change_at = 0;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::TransferSol {
+            receive_account,
+            amount,
+        } => {
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *destination_account.key != receive_account {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            // the authority must keep at least its rent floor after the transfer
+            let balance = wallet_authority.lamports();
+            if balance < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            if balance - amount < wallet_details.rent_floor {
+                return Err(WalletError::BelowRentFloor.into());
+            }
+            check_destination(
+                program_id,
+                wallet_config.key,
+                blacklist,
+                destination_account,
+            )?;
+            if let Some(stats_details) = track_outflow(
+                program_id,
+                wallet_config.key,
+                &Pubkey::default(),
+                treasury_stats,
+                amount,
+            )? {
+                store(&stats_details, treasury_stats)?;
+            }
+            invoke_signed(
+                &system_instruction::transfer(
+                    wallet_authority.key,
+                    destination_account.key,
+                    amount,
+                ),
+                &[
+                    wallet_authority.clone(),
+                    destination_account.clone(),
+                    system_program.clone(),
+                ],
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+
+            WalletEvent::SolTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                destination: *destination_account.key,
+                amount,
+            }
+            .emit(&mut wallet_details);
+            wallet_details.total_withdrawn += amount;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::TreasuryWithdraw {
+            receive_account,
+            amount,
+        } => {
+            let treasury = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+
+            let bump = wallet_details.treasury_bump;
+            if *treasury.key != treasury_address(program_id, wallet_config.key, bump)? {
+                return Err(WalletError::InvalidTreasury.into());
+            }
+            if *destination_account.key != receive_account {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            let balance = treasury.lamports();
+            if balance < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            if balance - amount != 0 && balance - amount < wallet_details.rent_floor {
+                return Err(WalletError::BelowRentFloor.into());
+            }
+            check_destination(
+                program_id,
+                wallet_config.key,
+                blacklist,
+                destination_account,
+            )?;
+            if let Some(stats_details) = track_outflow(
+                program_id,
+                wallet_config.key,
+                &Pubkey::default(),
+                treasury_stats,
+                amount,
+            )? {
+                store(&stats_details, treasury_stats)?;
+            }
+            invoke_signed(
+                &system_instruction::transfer(treasury.key, destination_account.key, amount),
+                &[
+                    treasury.clone(),
+                    destination_account.clone(),
+                    system_program.clone(),
+                ],
+                &[&[TREASURY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+
+            WalletEvent::SolTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                destination: *destination_account.key,
+                amount,
+            }
+            .emit(&mut wallet_details);
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::CloseWallet { destination } => {
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let treasury = next_account_info(accounts_iter)?;
+            let registry = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+
+            // the rent and deposits of any other proposal could never be refunded once the wallet is gone, this
+            // one has already been counted as closed
+            if wallet_details.open_proposals != 0 {
+                return Err(WalletError::ProposalsStillOpen.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *destination_account.key != destination {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            if *token_program.key != TOKEN_PROGRAM_ID {
+                return Err(WalletError::IncorrectTokenProgram.into());
+            }
+            let treasury_bump = wallet_details.treasury_bump;
+            if *treasury.key != treasury_address(program_id, wallet_config.key, treasury_bump)? {
+                return Err(WalletError::InvalidTreasury.into());
+            }
+            close_wallet_singleton::<ProgramRegistry>(
+                program_id,
+                wallet_config.key,
+                REGISTRY,
+                registry,
+                destination_account,
+                WalletError::InvalidRegistry,
+                &mut rent_refunds,
+            )?;
+            close_wallet_singleton::<Blacklist>(
+                program_id,
+                wallet_config.key,
+                BLACKLIST,
+                blacklist,
+                destination_account,
+                WalletError::InvalidBlacklist,
+                &mut rent_refunds,
+            )?;
+            close_wallet_singleton::<AuditLog>(
+                program_id,
+                wallet_config.key,
+                AUDIT,
+                audit_log,
+                destination_account,
+                WalletError::InvalidAuditLog,
+                &mut rent_refunds,
+            )?;
+            close_wallet_singleton::<WalletStats>(
+                program_id,
+                wallet_config.key,
+                WALLET_STATS,
+                wallet_stats,
+                destination_account,
+                WalletError::InvalidWalletStats,
+                &mut rent_refunds,
+            )?;
+            if accounts_iter.len() < wallet_details.owners.into() {
+                return Err(WalletError::OwnerWalletAuthCountMismatch.into());
+            }
+            // close the wallet auth account of every owner
+            for _ in 0..wallet_details.owners {
+                let wallet_auth = next_account_info(accounts_iter)?;
+                let mut user_details = load::<WalletAuth>(wallet_auth, program_id)?;
+                if user_details.wallet != *wallet_config.key {
+                    return Err(WalletError::InvalidWalletAuth.into());
+                }
+                user_details.is_initialized = false;
+                store(&user_details, wallet_auth)?;
+                rent_refunds.push((wallet_auth, destination_account));
+            }
+            // close every other account the wallet opened, the program owned accounts come ahead of the token
+            // accounts, a closed vault's authority signs for the sweep of the vault's token accounts below
+            let mut vault_authorities = Vec::new();
+            while accounts_iter
+                .as_slice()
+                .first()
+                .map_or(false, |account| account.owner == program_id)
+            {
+                let account = next_account_info(accounts_iter)?;
+                if let Some(vault_details) =
+                    load_dependent_account(program_id, wallet_config.key, account)?
+                {
+                    let vault_authority = next_account_info(accounts_iter)?;
+                    if *vault_authority.key
+                        != vault_authority_address(
+                            program_id,
+                            wallet_config.key,
+                            vault_details.index,
+                            vault_details.authority_bump,
+                        )?
+                    {
+                        return Err(WalletError::InvalidWalletAuthority.into());
+                    }
+                    vault_authorities.push((
+                        vault_authority,
+                        vault_details.index,
+                        vault_details.authority_bump,
+                    ));
+                }
+                close_program_account(account, destination_account, &mut rent_refunds);
+                wallet_details.open_accounts = wallet_details.open_accounts.saturating_sub(1);
+            }
+            // escrows, streams, claims and vestings still owe their tokens to someone and are never closed here
+            if wallet_details.open_accounts != 0 {
+                return Err(WalletError::AccountsStillOpen.into());
+            }
+            // sweep and close every token account passed, each with its mint
+            while accounts_iter.len() > 0 {
+                let send_account = next_account_info(accounts_iter)?;
+                let receive_account = next_account_info(accounts_iter)?;
+                let mint = next_account_info(accounts_iter)?;
+                let send_account_details = Account::unpack(&send_account.data.borrow())?;
+                let (authority, vault, authority_bump) =
+                    if send_account_details.owner == *wallet_authority.key {
+                        (wallet_authority, 0, bump)
+                    } else {
+                        *vault_authorities
+                            .iter()
+                            .find(|(vault_authority, ..)| {
+                                *vault_authority.key == send_account_details.owner
+                            })
+                            .ok_or(WalletError::IncorrectSendAccount)?
+                    };
+                let decimals = load_mint_decimals(mint, &send_account_details.mint)?;
+                let receive_account_details = Account::unpack(&receive_account.data.borrow())?;
+                if receive_account_details.owner != destination
+                    || receive_account_details.mint != send_account_details.mint
+                {
+                    return Err(WalletError::IncorrectReceiveAccount.into());
+                }
+                let vault_seed = [vault];
+                let bump_seed = [authority_bump];
+                let mut signer_seeds: Vec<&[u8]> =
+                    vec![AUTHORITY.as_bytes(), wallet_config.key.as_ref()];
+                if vault != 0 {
+                    signer_seeds.push(&vault_seed);
+                }
+                signer_seeds.push(&bump_seed);
+                invoke_signed(
+                    &token_instruction::transfer_checked(
+                        token_program.key,
+                        send_account.key,
+                        mint.key,
+                        receive_account.key,
+                        authority.key,
+                        &[],
+                        send_account_details.amount,
+                        decimals,
+                    )?,
+                    &[
+                        send_account.clone(),
+                        mint.clone(),
+                        receive_account.clone(),
+                        authority.clone(),
+                    ],
+                    &[&signer_seeds],
+                )?;
+                invoke_signed(
+                    &token_instruction::close_account(
+                        token_program.key,
+                        send_account.key,
+                        destination_account.key,
+                        authority.key,
+                        &[],
+                    )?,
+                    &[
+                        send_account.clone(),
+                        destination_account.clone(),
+                        authority.clone(),
+                    ],
+                    &[&signer_seeds],
+                )?;
+
+                WalletEvent::TokensTransferred {
+                    wallet: *wallet_config.key,
+                    proposal: *proposal.key,
+                    proposal_type,
+                    mint: send_account_details.mint,
+                    source: *send_account.key,
+                    destination: *receive_account.key,
+                    amount: send_account_details.amount,
+                }
+                .emit(&mut wallet_details);
+            }
+            // move all SOL held by the treasury and the authority
+            let balance = treasury.lamports();
+            if balance > 0 {
+                invoke_signed(
+                    &system_instruction::transfer(treasury.key, destination_account.key, balance),
+                    &[
+                        treasury.clone(),
+                        destination_account.clone(),
+                        system_program.clone(),
+                    ],
+                    &[&[
+                        TREASURY.as_bytes(),
+                        wallet_config.key.as_ref(),
+                        &[treasury_bump],
+                    ]],
+                )?;
+
+                WalletEvent::SolTransferred {
+                    wallet: *wallet_config.key,
+                    proposal: *proposal.key,
+                    proposal_type,
+                    destination: *destination_account.key,
+                    amount: balance,
+                }
+                .emit(&mut wallet_details);
+            }
+            let balance = wallet_authority.lamports();
+            if balance > 0 {
+                invoke_signed(
+                    &system_instruction::transfer(
+                        wallet_authority.key,
+                        destination_account.key,
+                        balance,
+                    ),
+                    &[
+                        wallet_authority.clone(),
+                        destination_account.clone(),
+                        system_program.clone(),
+                    ],
+                    &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+                )?;
+
+                WalletEvent::SolTransferred {
+                    wallet: *wallet_config.key,
+                    proposal: *proposal.key,
+                    proposal_type,
+                    destination: *destination_account.key,
+                    amount: balance,
+                }
+                .emit(&mut wallet_details);
+            }
+            // close wallet config account
+            wallet_details.is_initialized = false;
+            store(&wallet_details, wallet_config)?;
+            rent_refunds.push((wallet_config, destination_account));
+
+            WalletEvent::WalletClosed {
+                wallet: *wallet_config.key,
+            }
+            .emit(&mut wallet_details);
+        }
+        ProposalType::SetRecovery {
+            beneficiary,
+            inactivity_period,
+        } => {
+            wallet_details.beneficiary = beneficiary;
+            wallet_details.inactivity_period = inactivity_period;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetExecutors { executors } => {
+            wallet_details.executors = executors;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetMaxOpenProposals { max } => {
+            wallet_details.max_open_proposals = max;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetProposalDeposit { amount } => {
+            wallet_details.proposal_deposit = amount;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetKeeperTip { amount } => {
+            wallet_details.keeper_tip = amount;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::Unfreeze => {
+            wallet_details.frozen = false;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetQuorum { basis_points } => {
+            wallet_details.quorum = basis_points;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetOptimisticDelay { delay } => {
+            wallet_details.optimistic_delay = delay;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetExecutionCallback { program } => {
+            if program == *program_id {
+                return Err(WalletError::InvalidCallbackProgram.into());
+            }
+            wallet_details.callback_program = program;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetVoteGate { mint, amount } => {
+            wallet_details.gate_mint = mint;
+            wallet_details.gate_amount = amount;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::RemoveInactiveOwners {
+            inactive_for,
+            owners,
+        } => {
+            let wallet_authority = next_account_info(accounts_iter)?;
+
+            if *wallet_authority.key
+                != authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?
+            {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            let cutoff = Clock::get()?.unix_timestamp.saturating_sub(inactive_for);
+            for owner in owners.iter() {
+                let wallet_auth = next_account_info(accounts_iter)?;
+                let mut user_details =
+                    load_owner(program_id, wallet_config.key, owner, wallet_auth)?;
+                if user_details.last_vote_at > cutoff {
+                    return Err(WalletError::OwnerNotInactive.into());
+                }
+                // the wallet keeps at least one owner whatever the owner count was at creation
+                if wallet_details.owners == 1 {
+                    return Err(WalletError::InvalidOwnerRemoval.into());
+                }
+                clear_bit(&mut wallet_details.owner_identities, user_details.id);
+                clear_bit(&mut wallet_details.mandatory_approvers, user_details.id);
+                wallet_details.owners -= 1;
+                user_details.is_initialized = false;
+                store(&user_details, wallet_auth)?;
+                let balance = wallet_auth.lamports();
+                **wallet_auth.try_borrow_mut_lamports()? -= balance;
+                **wallet_authority.try_borrow_mut_lamports()? += balance;
+                WalletEvent::InactiveOwnerRemoved {
+                    wallet: *wallet_config.key,
+                    owner: *owner,
+                    last_vote_at: user_details.last_vote_at,
+                }
+                .emit(&mut wallet_details);
+            }
+            // votes cast by the previous owner set do not carry over
+            wallet_details.epoch += 1;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetMandatoryApprovers { approvers } => {
+            let mut mandatory_approvers = [0; 32];
+            for approver in approvers.iter() {
+                let wallet_auth = next_account_info(accounts_iter)?;
+                let user_details =
+                    load_owner(program_id, wallet_config.key, approver, wallet_auth)?;
+                set_bit(&mut mandatory_approvers, user_details.id);
+            }
+            wallet_details.mandatory_approvers = mandatory_approvers;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::SetRoles { user, roles } => {
+            let wallet_auth = next_account_info(accounts_iter)?;
+
+            if roles & !(ROLE_ALL | ROLE_OPTIMISTIC) != 0 {
+                return Err(WalletError::InvalidWalletParameters.into());
+            }
+            let mut user_details = load_owner(program_id, wallet_config.key, &user, wallet_auth)?;
+            user_details.roles = roles;
+            store(&user_details, wallet_auth)?;
+        }
+        ProposalType::SetAllowance {
+            user,
+            token_mint,
+            amount,
+        } => {
+            let payer = next_account_info(accounts_iter)?;
+            let allowance = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+
+            // an existing allowance only has its limit changed
+            if !allowance.data_is_empty() {
+                let mut allowance_details = load::<Allowance>(allowance, program_id)?;
+                let allowance_key = allowance_address(
+                    program_id,
+                    wallet_config.key,
+                    &user,
+                    &token_mint,
+                    allowance_details.bump,
+                )?;
+                if *allowance.key != allowance_key {
+                    return Err(WalletError::InvalidAllowance.into());
+                }
+                allowance_details.limit = amount;
+                store(&allowance_details, allowance)?;
+            } else {
+                if !payer.is_signer {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                let (allowance_key, bump) = Pubkey::find_program_address(
+                    &[
+                        ALLOWANCE.as_bytes(),
+                        wallet_config.key.as_ref(),
+                        user.as_ref(),
+                        token_mint.as_ref(),
+                    ],
+                    program_id,
+                );
+                if *allowance.key != allowance_key {
+                    return Err(WalletError::InvalidAllowance.into());
+                }
+                if *system_program.key != SYSTEM_PROGRAM_ID {
+                    return Err(WalletError::IncorrectSystemProgram.into());
+                }
+                // create allowance
+                let account_size: u64 = Allowance::LEN.try_into().unwrap();
+                let rent = Rent::get()?;
+                let rent_amount = rent.minimum_balance(Allowance::LEN);
+
+                invoke_signed(
+                    &system_instruction::create_account(
+                        payer.key,
+                        allowance.key,
+                        rent_amount,
+                        account_size,
+                        program_id,
+                    ),
+                    &[payer.clone(), allowance.clone()],
+                    &[&[
+                        ALLOWANCE.as_bytes(),
+                        wallet_config.key.as_ref(),
+                        user.as_ref(),
+                        token_mint.as_ref(),
+                        &[bump],
+                    ]],
+                )?;
+                check_rent_exempt(allowance, &rent)?;
+                wallet_details.open_accounts += 1;
+                // initialize allowance
+                let allowance_details = Allowance {
+                    discriminator: AccountType::Allowance,
+                    wallet: *wallet_config.key,
+                    owner: user,
+                    mint: token_mint,
+                    limit: amount,
+                    spent: 0,
+                    bump,
+                    is_initialized: true,
+                };
+                store(&allowance_details, allowance)?;
+            }
+        }
+        ProposalType::CreateTemplate { id, template } => {
+            let payer = next_account_info(accounts_iter)?;
+            let template_account = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let (template_key, bump) = Pubkey::find_program_address(
+                &[
+                    TEMPLATE.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &id.to_le_bytes(),
+                ],
+                program_id,
+            );
+            if *template_account.key != template_key {
+                return Err(WalletError::InvalidTemplate.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            // create template
+            let template_details = Template {
+                discriminator: AccountType::Template,
+                wallet: *wallet_config.key,
+                id,
+                bump,
+                proposal: *template.0,
+                is_initialized: true,
+            };
+            let template_len = DISCRIMINATOR_LEN + template_details.try_to_vec()?.len();
+            let account_size: u64 = template_len.try_into().unwrap();
+            let rent = Rent::get()?;
+            let rent_amount = rent.minimum_balance(template_len);
+
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    template_account.key,
+                    rent_amount,
+                    account_size,
+                    program_id,
+                ),
+                &[payer.clone(), template_account.clone()],
+                &[&[
+                    TEMPLATE.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &id.to_le_bytes(),
+                    &[bump],
+                ]],
+            )?;
+            check_rent_exempt(template_account, &rent)?;
+            wallet_details.open_accounts += 1;
+            // initialize template
+            store(&template_details, template_account)?;
+        }
+        ProposalType::DeleteTemplate { id } => {
+            let template_account = next_account_info(accounts_iter)?;
+
+            let mut template_details = load::<Template>(template_account, program_id)?;
+            let template_key =
+                template_address(program_id, wallet_config.key, id, template_details.bump)?;
+            if *template_account.key != template_key {
+                return Err(WalletError::InvalidTemplate.into());
+            }
+            // close template, its rent follows the proposal's rent
+            template_details.is_initialized = false;
+            store(&template_details, template_account)?;
+            wallet_details.open_accounts = wallet_details.open_accounts.saturating_sub(1);
+            let balance = template_account.lamports();
+            **template_account.try_borrow_mut_lamports()? -= balance;
+            **refund_destination.try_borrow_mut_lamports()? += balance;
+        }
+        ProposalType::CreateVault { index, m, n } => {
+            let payer = next_account_info(accounts_iter)?;
+            let vault = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let (vault_key, bump) = Pubkey::find_program_address(
+                &[VAULT.as_bytes(), wallet_config.key.as_ref(), &[index]],
+                program_id,
+            );
+            if *vault.key != vault_key {
+                return Err(WalletError::InvalidVault.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            let (_, authority_bump) = Pubkey::find_program_address(
+                &[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[index]],
+                program_id,
+            );
+            // create vault
+            let account_size: u64 = Vault::LEN.try_into().unwrap();
+            let rent = Rent::get()?;
+            let rent_amount = rent.minimum_balance(Vault::LEN);
+
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    vault.key,
+                    rent_amount,
+                    account_size,
+                    program_id,
+                ),
+                &[payer.clone(), vault.clone()],
+                &[&[
+                    VAULT.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &[index],
+                    &[bump],
+                ]],
+            )?;
+            check_rent_exempt(vault, &rent)?;
+            wallet_details.open_accounts += 1;
+            // initialize vault
+            let vault_details = Vault {
+                discriminator: AccountType::Vault,
+                wallet: *wallet_config.key,
+                index,
+                m,
+                n,
+                authority_bump,
+                bump,
+                is_initialized: true,
+            };
+            store(&vault_details, vault)?;
+        }
+        ProposalType::EscrowTransfer {
+            token_mint,
+            receive_account,
+            amount,
+            unlock_time,
+        } => {
+            let payer = next_account_info(accounts_iter)?;
+            let source_account = next_account_info(accounts_iter)?;
+            let escrow = next_account_info(accounts_iter)?;
+            let escrow_token_account = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let associated_token_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let source_account_details =
+                load_token_account(source_account, &token_mint, wallet_authority.key)?;
+            if source_account_details.amount < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            let authority_bump = wallet_details.authority_bump;
+            let wallet_authority_key =
+                authority_address(program_id, wallet_config.key, authority_bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            let sequence = proposal_details.sequence;
+            let (escrow_key, bump) = Pubkey::find_program_address(
+                &[
+                    ESCROW.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                ],
+                program_id,
+            );
+            if *escrow.key != escrow_key {
+                return Err(WalletError::InvalidEscrow.into());
+            }
+            let decimals = load_mint_decimals(mint, &token_mint)?;
+            if *escrow_token_account.key != get_associated_token_address(escrow.key, mint.key) {
+                return Err(WalletError::IncorrectAssociatedTokenAccount.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID
+                || *token_program.key != TOKEN_PROGRAM_ID
+                || *associated_token_program.key != ASSOCIATED_TOKEN_PROGRAM_ID
+            {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            // create escrow and its token account
+            let account_size: u64 = Escrow::LEN.try_into().unwrap();
+            let rent = Rent::get()?;
+            let rent_amount = rent.minimum_balance(Escrow::LEN);
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    escrow.key,
+                    rent_amount,
+                    account_size,
+                    program_id,
+                ),
+                &[payer.clone(), escrow.clone()],
+                &[&[
+                    ESCROW.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                    &[bump],
+                ]],
+            )?;
+            check_rent_exempt(escrow, &rent)?;
+            wallet_details.open_accounts += 1;
+            invoke(
+                &create_associated_token_account(
+                    payer.key,
+                    escrow.key,
+                    mint.key,
+                    token_program.key,
+                ),
+                &[
+                    payer.clone(),
+                    escrow_token_account.clone(),
+                    escrow.clone(),
+                    mint.clone(),
+                    system_program.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+            // lock the tokens up
+            invoke_signed(
+                &token_instruction::transfer_checked(
+                    token_program.key,
+                    source_account.key,
+                    mint.key,
+                    escrow_token_account.key,
+                    wallet_authority.key,
+                    &[],
+                    amount,
+                    decimals,
+                )?,
+                &[
+                    source_account.clone(),
+                    mint.clone(),
+                    escrow_token_account.clone(),
+                    wallet_authority.clone(),
+                ],
+                &[&[
+                    AUTHORITY.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &[authority_bump],
+                ]],
+            )?;
+
+            WalletEvent::TokensTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                mint: token_mint,
+                source: *source_account.key,
+                destination: *escrow_token_account.key,
+                amount,
+            }
+            .emit(&mut wallet_details);
+            // initialize escrow
+            let escrow_details = Escrow {
+                discriminator: AccountType::Escrow,
+                wallet: *wallet_config.key,
+                sequence,
+                mint: token_mint,
+                receive_account,
+                amount,
+                unlock_time,
+                bump,
+                is_initialized: true,
+            };
+            store(&escrow_details, escrow)?;
+        }
+        ProposalType::CreateVesting {
+            token_mint,
+            receive_account,
+            amount,
+            start_time,
+            cliff_time,
+            end_time,
+        } => {
+            let payer = next_account_info(accounts_iter)?;
+            let source_account = next_account_info(accounts_iter)?;
+            let vesting = next_account_info(accounts_iter)?;
+            let vesting_token_account = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let associated_token_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let source_account_details =
+                load_token_account(source_account, &token_mint, wallet_authority.key)?;
+            if source_account_details.amount < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            let authority_bump = wallet_details.authority_bump;
+            let wallet_authority_key =
+                authority_address(program_id, wallet_config.key, authority_bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            let sequence = proposal_details.sequence;
+            let (vesting_key, bump) = Pubkey::find_program_address(
+                &[
+                    VESTING.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                ],
+                program_id,
+            );
+            if *vesting.key != vesting_key {
+                return Err(WalletError::InvalidVesting.into());
+            }
+            let decimals = load_mint_decimals(mint, &token_mint)?;
+            if *vesting_token_account.key != get_associated_token_address(vesting.key, mint.key) {
+                return Err(WalletError::IncorrectAssociatedTokenAccount.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID
+                || *token_program.key != TOKEN_PROGRAM_ID
+                || *associated_token_program.key != ASSOCIATED_TOKEN_PROGRAM_ID
+            {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            // create the vesting and its token account
+            let account_size: u64 = Vesting::LEN.try_into().unwrap();
+            let rent = Rent::get()?;
+            let rent_amount = rent.minimum_balance(Vesting::LEN);
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    vesting.key,
+                    rent_amount,
+                    account_size,
+                    program_id,
+                ),
+                &[payer.clone(), vesting.clone()],
+                &[&[
+                    VESTING.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                    &[bump],
+                ]],
+            )?;
+            check_rent_exempt(vesting, &rent)?;
+            wallet_details.open_accounts += 1;
+            invoke(
+                &create_associated_token_account(
+                    payer.key,
+                    vesting.key,
+                    mint.key,
+                    token_program.key,
+                ),
+                &[
+                    payer.clone(),
+                    vesting_token_account.clone(),
+                    vesting.clone(),
+                    mint.clone(),
+                    system_program.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+            // lock the tokens up
+            invoke_signed(
+                &token_instruction::transfer_checked(
+                    token_program.key,
+                    source_account.key,
+                    mint.key,
+                    vesting_token_account.key,
+                    wallet_authority.key,
+                    &[],
+                    amount,
+                    decimals,
+                )?,
+                &[
+                    source_account.clone(),
+                    mint.clone(),
+                    vesting_token_account.clone(),
+                    wallet_authority.clone(),
+                ],
+                &[&[
+                    AUTHORITY.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &[authority_bump],
+                ]],
+            )?;
+
+            WalletEvent::TokensTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                mint: token_mint,
+                source: *source_account.key,
+                destination: *vesting_token_account.key,
+                amount,
+            }
+            .emit(&mut wallet_details);
+            // initialize the vesting
+            let vesting_details = Vesting {
+                discriminator: AccountType::Vesting,
+                wallet: *wallet_config.key,
+                sequence,
+                mint: token_mint,
+                receive_account,
+                amount,
+                start_time,
+                cliff_time,
+                end_time,
+                released: 0,
+                bump,
+                is_initialized: true,
+            };
+            store(&vesting_details, vesting)?;
+        }
+        ProposalType::CancelEscrow { sequence } => {
+            let escrow = next_account_info(accounts_iter)?;
+            let escrow_token_account = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+
+            let escrow_details = load_escrow(program_id, wallet_config.key, escrow)?;
+            if escrow_details.sequence != sequence {
+                return Err(WalletError::InvalidEscrow.into());
+            }
+            let wallet_authority_key =
+                authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            // the tokens go back to the wallet
+            let destination_account_details = Account::unpack(&destination_account.data.borrow())?;
+            if destination_account_details.owner != *wallet_authority.key {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            WalletEvent::TokensTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                mint: escrow_details.mint,
+                source: *escrow_token_account.key,
+                destination: *destination_account.key,
+                amount: escrow_details.amount,
+            }
+            .emit(&mut wallet_details);
+            wallet_details.open_accounts = wallet_details.open_accounts.saturating_sub(1);
+            close_escrow(
+                wallet_config.key,
+                escrow,
+                escrow_details,
+                escrow_token_account,
+                destination_account,
+                wallet_authority,
+                token_program,
+                mint,
+            )?;
+        }
+        ProposalType::CreateStream {
+            token_mint,
+            receive_account,
+            rate,
+            duration,
+        } => {
+            let payer = next_account_info(accounts_iter)?;
+            let stream = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let sequence = proposal_details.sequence;
+            let (stream_key, bump) = Pubkey::find_program_address(
+                &[
+                    STREAM.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                ],
+                program_id,
+            );
+            if *stream.key != stream_key {
+                return Err(WalletError::InvalidStream.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            // create stream
+            let account_size: u64 = Stream::LEN.try_into().unwrap();
+            let rent = Rent::get()?;
+            let rent_amount = rent.minimum_balance(Stream::LEN);
+
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    stream.key,
+                    rent_amount,
+                    account_size,
+                    program_id,
+                ),
+                &[payer.clone(), stream.clone()],
+                &[&[
+                    STREAM.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                    &[bump],
+                ]],
+            )?;
+            check_rent_exempt(stream, &rent)?;
+            wallet_details.open_accounts += 1;
+            // initialize stream, vesting starts at execution
+            let start_time = Clock::get()?.unix_timestamp;
+            let stream_details = Stream {
+                discriminator: AccountType::Stream,
+                wallet: *wallet_config.key,
+                sequence,
+                mint: token_mint,
+                receive_account,
+                rate,
+                start_time,
+                end_time: start_time + duration,
+                withdrawn: 0,
+                bump,
+                is_initialized: true,
+            };
+            store(&stream_details, stream)?;
+        }
+        ProposalType::CancelStream { sequence } => {
+            let stream = next_account_info(accounts_iter)?;
+
+            let mut stream_details = load_stream(program_id, wallet_config.key, stream)?;
+            if stream_details.sequence != sequence {
+                return Err(WalletError::InvalidStream.into());
+            }
+            // close stream, whatever has not been withdrawn stays with the wallet
+            stream_details.is_initialized = false;
+            store(&stream_details, stream)?;
+            wallet_details.open_accounts = wallet_details.open_accounts.saturating_sub(1);
+            let balance = stream.lamports();
+            **stream.try_borrow_mut_lamports()? -= balance;
+            **refund_destination.try_borrow_mut_lamports()? += balance;
+        }
+        ProposalType::ClaimableTransfer {
+            token_mint,
+            recipient,
+            amount,
+            claim_period,
+        } => {
+            let payer = next_account_info(accounts_iter)?;
+            let source_account = next_account_info(accounts_iter)?;
+            let claim = next_account_info(accounts_iter)?;
+            let claim_token_account = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let associated_token_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let source_account_details =
+                load_token_account(source_account, &token_mint, wallet_authority.key)?;
+            if source_account_details.amount < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            let authority_bump = wallet_details.authority_bump;
+            let wallet_authority_key =
+                authority_address(program_id, wallet_config.key, authority_bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            let sequence = proposal_details.sequence;
+            let (claim_key, bump) = Pubkey::find_program_address(
+                &[
+                    CLAIM.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                ],
+                program_id,
+            );
+            if *claim.key != claim_key {
+                return Err(WalletError::InvalidClaim.into());
+            }
+            let decimals = load_mint_decimals(mint, &token_mint)?;
+            if *claim_token_account.key != get_associated_token_address(claim.key, mint.key) {
+                return Err(WalletError::IncorrectAssociatedTokenAccount.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID
+                || *token_program.key != TOKEN_PROGRAM_ID
+                || *associated_token_program.key != ASSOCIATED_TOKEN_PROGRAM_ID
+            {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            // create claim and its token account
+            let account_size: u64 = Claim::LEN.try_into().unwrap();
+            let rent = Rent::get()?;
+            let rent_amount = rent.minimum_balance(Claim::LEN);
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    claim.key,
+                    rent_amount,
+                    account_size,
+                    program_id,
+                ),
+                &[payer.clone(), claim.clone()],
+                &[&[
+                    CLAIM.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                    &[bump],
+                ]],
+            )?;
+            check_rent_exempt(claim, &rent)?;
+            wallet_details.open_accounts += 1;
+            invoke(
+                &create_associated_token_account(payer.key, claim.key, mint.key, token_program.key),
+                &[
+                    payer.clone(),
+                    claim_token_account.clone(),
+                    claim.clone(),
+                    mint.clone(),
+                    system_program.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+            // move the tokens into the claim
+            invoke_signed(
+                &token_instruction::transfer_checked(
+                    token_program.key,
+                    source_account.key,
+                    mint.key,
+                    claim_token_account.key,
+                    wallet_authority.key,
+                    &[],
+                    amount,
+                    decimals,
+                )?,
+                &[
+                    source_account.clone(),
+                    mint.clone(),
+                    claim_token_account.clone(),
+                    wallet_authority.clone(),
+                ],
+                &[&[
+                    AUTHORITY.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &[authority_bump],
+                ]],
+            )?;
+
+            WalletEvent::TokensTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                mint: token_mint,
+                source: *source_account.key,
+                destination: *claim_token_account.key,
+                amount,
+            }
+            .emit(&mut wallet_details);
+            // initialize claim
+            let claim_details = Claim {
+                discriminator: AccountType::Claim,
+                wallet: *wallet_config.key,
+                sequence,
+                mint: token_mint,
+                recipient,
+                amount,
+                expires_at: Clock::get()?.unix_timestamp + claim_period,
+                bump,
+                is_initialized: true,
+            };
+            store(&claim_details, claim)?;
+        }
+        ProposalType::CreateLookupTable { recent_slot } => {
+            let payer = next_account_info(accounts_iter)?;
+            let lookup_table = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let lookup_table_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID
+                || *lookup_table_program.key != LOOKUP_TABLE_PROGRAM_ID
+            {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let (instruction, lookup_table_key) =
+                lookup_table_instruction::create_lookup_table_signed(
+                    *wallet_authority.key,
+                    *payer.key,
+                    recent_slot,
+                );
+            if *lookup_table.key != lookup_table_key {
+                return Err(WalletError::InvalidLookupTable.into());
+            }
+            invoke_signed(
+                &instruction,
+                &[
+                    lookup_table.clone(),
+                    wallet_authority.clone(),
+                    payer.clone(),
+                    system_program.clone(),
+                ],
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+        }
+        ProposalType::ExtendLookupTable { table, addresses } => {
+            let payer = next_account_info(accounts_iter)?;
+            let lookup_table = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let lookup_table_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if *lookup_table.key != table {
+                return Err(WalletError::InvalidLookupTable.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID
+                || *lookup_table_program.key != LOOKUP_TABLE_PROGRAM_ID
+            {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            // the payer tops up the table's rent for the added addresses
+            invoke_signed(
+                &lookup_table_instruction::extend_lookup_table(
+                    table,
+                    *wallet_authority.key,
+                    Some(*payer.key),
+                    addresses,
+                ),
+                &[
+                    lookup_table.clone(),
+                    wallet_authority.clone(),
+                    payer.clone(),
+                    system_program.clone(),
+                ],
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+        }
+        ProposalType::FreezeLookupTable { table } => {
+            let lookup_table = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let lookup_table_program = next_account_info(accounts_iter)?;
+
+            if *lookup_table.key != table {
+                return Err(WalletError::InvalidLookupTable.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *lookup_table_program.key != LOOKUP_TABLE_PROGRAM_ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            invoke_signed(
+                &lookup_table_instruction::freeze_lookup_table(table, *wallet_authority.key),
+                &[lookup_table.clone(), wallet_authority.clone()],
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+        }
+        ProposalType::StakePoolDeposit { stake_pool, amount } => {
+            let stake_pool_account = next_account_info(accounts_iter)?;
+            let stake_pool_withdraw_authority = next_account_info(accounts_iter)?;
+            let reserve_stake = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let pool_token_account = next_account_info(accounts_iter)?;
+            let manager_fee_account = next_account_info(accounts_iter)?;
+            let pool_mint = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let stake_pool_program = next_account_info(accounts_iter)?;
+
+            if *stake_pool_account.key != stake_pool
+                || *stake_pool_account.owner != STAKE_POOL_PROGRAM_ID
+            {
+                return Err(WalletError::InvalidStakePool.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            let pool_token_details = Account::unpack(&pool_token_account.data.borrow())?;
+            if pool_token_details.owner != *wallet_authority.key
+                || pool_token_details.mint != *pool_mint.key
+            {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID
+                || *token_program.key != TOKEN_PROGRAM_ID
+                || *stake_pool_program.key != STAKE_POOL_PROGRAM_ID
+            {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            // the authority must keep at least its rent floor after the deposit
+            let balance = wallet_authority.lamports();
+            if balance < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            if balance - amount < wallet_details.rent_floor {
+                return Err(WalletError::BelowRentFloor.into());
+            }
+            // the wallet is its own referrer so the referral fee comes back to it
+            invoke_signed(
+                &stake_pool::deposit_sol(
+                    stake_pool_account.key,
+                    stake_pool_withdraw_authority.key,
+                    reserve_stake.key,
+                    wallet_authority.key,
+                    pool_token_account.key,
+                    manager_fee_account.key,
+                    pool_token_account.key,
+                    pool_mint.key,
+                    token_program.key,
+                    amount,
+                ),
+                &[
+                    stake_pool_account.clone(),
+                    stake_pool_withdraw_authority.clone(),
+                    reserve_stake.clone(),
+                    wallet_authority.clone(),
+                    pool_token_account.clone(),
+                    manager_fee_account.clone(),
+                    pool_mint.clone(),
+                    system_program.clone(),
+                    token_program.clone(),
+                ],
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+
+            WalletEvent::SolTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                destination: *reserve_stake.key,
+                amount,
+            }
+            .emit(&mut wallet_details);
+            wallet_details.total_withdrawn += amount;
+            store(&wallet_details, wallet_config)?;
+        }
+        ProposalType::StakePoolWithdraw {
+            stake_pool,
+            pool_tokens,
+        } => {
+            let stake_pool_account = next_account_info(accounts_iter)?;
+            let stake_pool_withdraw_authority = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let pool_token_account = next_account_info(accounts_iter)?;
+            let reserve_stake = next_account_info(accounts_iter)?;
+            let manager_fee_account = next_account_info(accounts_iter)?;
+            let pool_mint = next_account_info(accounts_iter)?;
+            let clock_sysvar = next_account_info(accounts_iter)?;
+            let stake_history_sysvar = next_account_info(accounts_iter)?;
+            let stake_program = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let stake_pool_program = next_account_info(accounts_iter)?;
+
+            if *stake_pool_account.key != stake_pool
+                || *stake_pool_account.owner != STAKE_POOL_PROGRAM_ID
+            {
+                return Err(WalletError::InvalidStakePool.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            let pool_token_details = Account::unpack(&pool_token_account.data.borrow())?;
+            if pool_token_details.owner != *wallet_authority.key
+                || pool_token_details.mint != *pool_mint.key
+            {
+                return Err(WalletError::IncorrectSendAccount.into());
+            }
+            if pool_token_details.amount < pool_tokens {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            if *token_program.key != TOKEN_PROGRAM_ID
+                || *stake_pool_program.key != STAKE_POOL_PROGRAM_ID
+            {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            // the authority burns the pool tokens and receives the SOL
+            let balance = wallet_authority.lamports();
+            invoke_signed(
+                &stake_pool::withdraw_sol(
+                    stake_pool_account.key,
+                    stake_pool_withdraw_authority.key,
+                    wallet_authority.key,
+                    pool_token_account.key,
+                    reserve_stake.key,
+                    wallet_authority.key,
+                    manager_fee_account.key,
+                    pool_mint.key,
+                    token_program.key,
+                    pool_tokens,
+                ),
+                &[
+                    stake_pool_account.clone(),
+                    stake_pool_withdraw_authority.clone(),
+                    wallet_authority.clone(),
+                    pool_token_account.clone(),
+                    reserve_stake.clone(),
+                    manager_fee_account.clone(),
+                    pool_mint.clone(),
+                    clock_sysvar.clone(),
+                    stake_history_sysvar.clone(),
+                    stake_program.clone(),
+                    token_program.clone(),
+                ],
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+
+            WalletEvent::TokensTransferred {
+                wallet: *wallet_config.key,
+                proposal: *proposal.key,
+                proposal_type,
+                mint: *pool_mint.key,
+                source: *pool_token_account.key,
+                destination: *reserve_stake.key,
+                amount: pool_tokens,
+            }
+            .emit(&mut wallet_details);
+            wallet_details.total_deposited = wallet_details
+                .total_deposited
+                .checked_add(wallet_authority.lamports() - balance)
+                .ok_or(WalletError::Overflow)?;
+            store(&wallet_details, wallet_config)?;
+        }
+        // the stake program checks the wallet authority is the staker
+        ProposalType::SplitStake {
+            stake_account,
+            lamports,
+        } => {
+            let payer = next_account_info(accounts_iter)?;
+            let stake_account_info = next_account_info(accounts_iter)?;
+            let split_stake = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let stake_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if *stake_account_info.key != stake_account
+                || *stake_account_info.owner != STAKE_PROGRAM_ID
+            {
+                return Err(WalletError::InvalidStakeAccount.into());
+            }
+            let sequence = proposal_details.sequence;
+            let (split_stake_key, split_bump) = Pubkey::find_program_address(
+                &[
+                    SPLIT_STAKE.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                ],
+                program_id,
+            );
+            if *split_stake.key != split_stake_key {
+                return Err(WalletError::InvalidStakeAccount.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            if *stake_program.key != STAKE_PROGRAM_ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            // the split account has to be rent exempt before the stake program moves lamports into it
+            let rent_amount = Rent::get()?.minimum_balance(StakeStateV2::size_of());
+            invoke(
+                &system_instruction::transfer(payer.key, split_stake.key, rent_amount),
+                &[payer.clone(), split_stake.clone(), system_program.clone()],
+            )?;
+            // allocate and assign the split account, then split into it
+            for instruction in stake_instruction::split(
+                stake_account_info.key,
+                wallet_authority.key,
+                lamports,
+                split_stake.key,
+            ) {
+                invoke_signed(
+                    &instruction,
+                    &[
+                        stake_account_info.clone(),
+                        split_stake.clone(),
+                        wallet_authority.clone(),
+                        system_program.clone(),
+                        stake_program.clone(),
+                    ],
+                    &[
+                        &[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]],
+                        &[
+                            SPLIT_STAKE.as_bytes(),
+                            wallet_config.key.as_ref(),
+                            &sequence.to_le_bytes(),
+                            &[split_bump],
+                        ],
+                    ],
+                )?;
+            }
+        }
+        ProposalType::MergeStake {
+            destination,
+            source,
+        } => {
+            let destination_account = next_account_info(accounts_iter)?;
+            let source_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let clock_sysvar = next_account_info(accounts_iter)?;
+            let stake_history_sysvar = next_account_info(accounts_iter)?;
+            let stake_program = next_account_info(accounts_iter)?;
+
+            if *destination_account.key != destination
+                || *source_account.key != source
+                || *destination_account.owner != STAKE_PROGRAM_ID
+                || *source_account.owner != STAKE_PROGRAM_ID
+            {
+                return Err(WalletError::InvalidStakeAccount.into());
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *stake_program.key != STAKE_PROGRAM_ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            for instruction in stake_instruction::merge(
+                destination_account.key,
+                source_account.key,
+                wallet_authority.key,
+            ) {
+                invoke_signed(
+                    &instruction,
+                    &[
+                        destination_account.clone(),
+                        source_account.clone(),
+                        clock_sysvar.clone(),
+                        stake_history_sysvar.clone(),
+                        wallet_authority.clone(),
+                        stake_program.clone(),
+                    ],
+                    &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+                )?;
+            }
+        }
+        ProposalType::RegisterProgram { program } => {
+            let payer = next_account_info(accounts_iter)?;
+            let registry = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+
+            // registering this program would let proposals skip its own checks
+            if program == *program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let mut registry_details = if registry.data_is_empty() {
+                if !payer.is_signer {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                let (registry_key, bump) = Pubkey::find_program_address(
+                    &[REGISTRY.as_bytes(), wallet_config.key.as_ref()],
+                    program_id,
+                );
+                if *registry.key != registry_key {
+                    return Err(WalletError::InvalidRegistry.into());
+                }
+                if *system_program.key != SYSTEM_PROGRAM_ID {
+                    return Err(WalletError::IncorrectSystemProgram.into());
+                }
+                // create registry
+                let account_size: u64 = ProgramRegistry::LEN.try_into().unwrap();
+                let rent = Rent::get()?;
+                let rent_amount = rent.minimum_balance(ProgramRegistry::LEN);
+                invoke_signed(
+                    &system_instruction::create_account(
+                        payer.key,
+                        registry.key,
+                        rent_amount,
+                        account_size,
+                        program_id,
+                    ),
+                    &[payer.clone(), registry.clone()],
+                    &[&[REGISTRY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+                )?;
+                check_rent_exempt(registry, &rent)?;
+                ProgramRegistry {
+                    discriminator: AccountType::ProgramRegistry,
+                    wallet: *wallet_config.key,
+                    programs: [Pubkey::default(); MAX_REGISTERED_PROGRAMS],
+                    bump,
+                    is_initialized: true,
+                }
+            } else {
+                load_registry(program_id, wallet_config.key, registry)?
+            };
+            if registry_details.programs.contains(&program) {
+                return Err(WalletError::ProgramAlreadyRegistered.into());
+            }
+            let slot = registry_details
+                .programs
+                .iter_mut()
+                .find(|slot| **slot == Pubkey::default())
+                .ok_or(WalletError::RegistryFull)?;
+            *slot = program;
+            store(&registry_details, registry)?;
+        }
+        ProposalType::UnregisterProgram { program } => {
+            let registry = next_account_info(accounts_iter)?;
+
+            let mut registry_details = load_registry(program_id, wallet_config.key, registry)?;
+            let slot = registry_details
+                .programs
+                .iter_mut()
+                .find(|slot| **slot == program)
+                .ok_or(WalletError::ProgramNotRegistered)?;
+            *slot = Pubkey::default();
+            store(&registry_details, registry)?;
+        }
+        ProposalType::ExecuteInstruction {
+            program,
+            accounts,
+            data,
+        } => {
+            let registry = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let target_program = next_account_info(accounts_iter)?;
+
+            // a wallet authority can be an owner of another wallet of this program, calls back into it are
+            // limited to what an owner can do so they need no registry
+            let owner_action = program == *program_id
+                && matches!(
+                    WalletInstruction::unpack(&data),
+                    Ok(WalletInstruction::CreateProposal { .. }
+                        | WalletInstruction::Vote
+                        | WalletInstruction::VoteMany { .. }
+                        | WalletInstruction::CloseProposal)
+                );
+            if !owner_action {
+                let registry_details = load_registry(program_id, wallet_config.key, registry)?;
+                if program == Pubkey::default() || !registry_details.programs.contains(&program) {
+                    return Err(WalletError::ProgramNotRegistered.into());
+                }
+            }
+            if *target_program.key != program {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            let bump = wallet_details.authority_bump;
+            let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            let mut account_metas = Vec::new();
+            let mut account_infos = vec![wallet_authority.clone(), target_program.clone()];
+            for account in accounts.iter() {
+                let account_info = next_account_info(accounts_iter)?;
+                if *account_info.key != account.pubkey {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                account_metas.push(if account.is_writable {
+                    AccountMeta::new(account.pubkey, account.is_signer)
+                } else {
+                    AccountMeta::new_readonly(account.pubkey, account.is_signer)
+                });
+                account_infos.push(account_info.clone());
+            }
+            invoke_signed(
+                &Instruction {
+                    program_id: program,
+                    accounts: account_metas,
+                    data,
+                },
+                &account_infos,
+                &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+            )?;
+            // whatever the instruction did, the authority must still hold its rent floor
+            if wallet_authority.lamports() < wallet_details.rent_floor {
+                return Err(WalletError::BelowRentFloor.into());
+            }
+        }
+        ProposalType::CreateRecurringSwap {
+            swap_program,
+            input_mint,
+            output_mint,
+            amount,
+            min_output,
+            interval,
+            swaps,
+        } => {
+            let payer = next_account_info(accounts_iter)?;
+            let swap = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+
+            if !payer.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let sequence = proposal_details.sequence;
+            let (swap_key, bump) = Pubkey::find_program_address(
+                &[
+                    SWAP.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                ],
+                program_id,
+            );
+            if *swap.key != swap_key {
+                return Err(WalletError::InvalidRecurringSwap.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            // create recurring swap
+            let account_size: u64 = RecurringSwap::LEN.try_into().unwrap();
+            let rent = Rent::get()?;
+            let rent_amount = rent.minimum_balance(RecurringSwap::LEN);
+
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    swap.key,
+                    rent_amount,
+                    account_size,
+                    program_id,
+                ),
+                &[payer.clone(), swap.clone()],
+                &[&[
+                    SWAP.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &sequence.to_le_bytes(),
+                    &[bump],
+                ]],
+            )?;
+            check_rent_exempt(swap, &rent)?;
+            wallet_details.open_accounts += 1;
+            // initialize recurring swap, the first swap is due right away
+            let swap_details = RecurringSwap {
+                discriminator: AccountType::RecurringSwap,
+                wallet: *wallet_config.key,
+                sequence,
+                swap_program,
+                input_mint,
+                output_mint,
+                amount,
+                min_output,
+                interval,
+                next_swap_at: Clock::get()?.unix_timestamp,
+                remaining: swaps,
+                bump,
+                is_initialized: true,
+            };
+            store(&swap_details, swap)?;
+        }
+        ProposalType::CancelRecurringSwap { sequence } => {
+            let swap = next_account_info(accounts_iter)?;
+
+            let mut swap_details = load_recurring_swap(program_id, wallet_config.key, swap)?;
+            if swap_details.sequence != sequence {
+                return Err(WalletError::InvalidRecurringSwap.into());
+            }
+            swap_details.is_initialized = false;
+            store(&swap_details, swap)?;
+            wallet_details.open_accounts = wallet_details.open_accounts.saturating_sub(1);
+            let balance = swap.lamports();
+            **swap.try_borrow_mut_lamports()? -= balance;
+            **refund_destination.try_borrow_mut_lamports()? += balance;
+        }
+    }
+    // the events above bumped the event count
+    store(&wallet_details, wallet_config)?;
+    // the callback runs in the same transaction, a failing callback fails the execution
+    if let Some(callback_program) = callback_program {
+        let summary = ExecutionSummary {
+            wallet: *wallet_config.key,
+            proposal: *proposal.key,
+            sequence: proposal_details.sequence,
+            proposal_type,
+            proposer: proposal_details.proposer,
+            executed_at: Clock::get()?.unix_timestamp,
+        };
+        execution_callback(callback_program, wallet_config, proposal, &summary)?;
+    }
+    refund_rent(&rent_refunds)?;
+    refund_proposal_rent(
+        proposal,
+        vote_count,
+        treasury,
+        closer,
+        refund_destination,
+        forfeited_deposit,
+        keeper_tip,
+    )
+}
+
+fn queue_change(wallet_details: &mut WalletConfig, change: PendingChange) -> ProgramResult {
+    wallet_details.pending_change = change;
+    wallet_details.pending_change_at = Clock::get()?
+        .unix_timestamp
+        .saturating_add(wallet_details.timelock);
+    Ok(())
+}
+
+// permissionless, anyone can apply a pending change once its timelock has passed
+pub fn apply_pending_change(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let wallet_config = next_account_info(accounts_iter)?;
+    let audit_log = next_account_info(accounts_iter)?;
+
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    if wallet_details.frozen {
+        return Err(WalletError::WalletFrozen.into());
+    }
+    if Clock::get()?.unix_timestamp < wallet_details.pending_change_at {
+        return Err(WalletError::TimelockNotElapsed.into());
+    }
+    match wallet_details.pending_change {
+        PendingChange::None => return Err(WalletError::NoPendingChange.into()),
+        PendingChange::Threshold { m, n } => {
+            // votes cast under the old threshold do not carry over
+            wallet_details.m = m;
+            wallet_details.n = n;
+            wallet_details.emergency_m = wallet_details.emergency_m.min(n);
+            wallet_details.epoch += 1;
+        }
+        // the bounds may have changed while the lifetime was pending, it is kept within the current ones
+        PendingChange::ProposalLifetime { duration } => {
+            wallet_details.proposal_lifetime = duration.clamp(
+                wallet_details.min_proposal_lifetime,
+                wallet_details.max_proposal_lifetime,
+            )
+        }
+        PendingChange::Timelock { delay } => wallet_details.timelock = delay,
+        PendingChange::LifetimeBounds { min, max } => {
+            wallet_details.min_proposal_lifetime = min;
+            wallet_details.max_proposal_lifetime = max;
+            wallet_details.proposal_lifetime = wallet_details.proposal_lifetime.clamp(min, max);
+            wallet_details.emergency_lifetime = wallet_details.emergency_lifetime.min(max);
+        }
+        PendingChange::RotationDelay { delay } => wallet_details.rotation_delay = delay,
+        // the threshold may have changed n while the policy was pending
+        PendingChange::EmergencyPolicy { m, lifetime } => {
+            wallet_details.emergency_m = m.min(wallet_details.n);
+            wallet_details.emergency_lifetime = lifetime.min(wallet_details.max_proposal_lifetime);
+        }
+        // votes cast under the old mode do not carry over
+        PendingChange::ThresholdMode { fixed } => {
+            wallet_details.fixed_threshold = fixed;
+            wallet_details.epoch += 1;
+        }
+    }
+    let change = wallet_details.pending_change.kind();
+    wallet_details.pending_change = PendingChange::None;
+    wallet_details.pending_change_at = 0;
+    store(&wallet_details, wallet_config)?;
+
+    append_audit_record(
+        program_id,
+        wallet_config.key,
+        audit_log,
+        AuditAction::ChangeApplied,
+        change,
+        0,
+        *wallet_config.key,
+    )
+}
+
+// runs the checks close_proposal would make without changing anything, the outcome is returned instead of failing
+pub fn validate_proposal_execution(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let result = check_execution(program_id, accounts);
+    if let Err(error) = &result {
+        msg!("proposal would fail to execute: {}", error);
+    }
+    let validation = ProposalValidation {
+        executable: result.is_ok(),
+        error: result.err().map(u64::from).unwrap_or(0),
+    };
+    set_return_data(&validation.try_to_vec()?);
+
+    Ok(())
+}
+
+fn check_execution(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let wallet_config = next_account_info(accounts_iter)?;
+    let proposal = next_account_info(accounts_iter)?;
+    let vote_count = next_account_info(accounts_iter)?;
+
+    let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let proposal_details = load::<Proposal>(proposal, program_id)?;
+    if proposal_details.wallet != *wallet_config.key {
+        return Err(WalletError::ProposalWalletMismatch.into());
+    }
+    let proposal_key = proposal_address(
+        program_id,
+        wallet_config.key,
+        proposal_details.sequence,
+        proposal_details.bump,
+    )?;
+    if *proposal.key != proposal_key {
+        return Err(WalletError::InvalidProposal.into());
+    }
+    let voting_details = load::<VoteCount>(vote_count, program_id)?;
+    let vote_count_key = vote_count_address(
+        program_id,
+        wallet_config.key,
+        proposal.key,
+        voting_details.bump,
+    )?;
+    if *vote_count.key != vote_count_key {
+        return Err(WalletError::InvalidVoteCount.into());
+    }
+    if proposal_details.executed {
+        return Err(WalletError::AlreadyExecuted.into());
+    }
+    if proposal_details.draft {
+        return Err(WalletError::ProposalIsDraft.into());
+    }
+    let threshold = voting_details.threshold;
+    if Clock::get()?.unix_timestamp > voting_details.expires_at {
+        return Err(WalletError::ProposalExpired.into());
+    }
+    if voting_details.epoch != wallet_details.epoch {
+        return Err(WalletError::EpochMismatch.into());
+    }
+    let vault_transfer = matches!(
+        proposal_details.proposal,
+        ProposalType::Transfer { vault, .. }
+            | ProposalType::TransferToWallet { vault, .. }
+            | ProposalType::TransferPercent { vault, .. }
+            | ProposalType::SplitPayment { vault, .. }
+            if vault != 0
+    );
+    let unchallenged = proposal_details.challenge_ends_at != 0
+        && Clock::get()?.unix_timestamp >= proposal_details.challenge_ends_at;
+    if !unchallenged && !vault_transfer && voting_details.votes < threshold {
+        return Err(WalletError::InsufficientVotes.into());
+    }
+    if !unchallenged && !mandatory_approved(&voting_details) {
+        return Err(WalletError::MissingMandatoryApproval.into());
+    }
+    if !unchallenged && !quorum_reached(&voting_details) {
+        return Err(WalletError::QuorumNotReached.into());
+    }
+    if wallet_details.frozen && !matches!(proposal_details.proposal, ProposalType::Unfreeze) {
+        return Err(WalletError::WalletFrozen.into());
+    }
+    // the price feed comes ahead of the proposal type's accounts as it does for close_proposal
+    if proposal_details.price_condition.feed != Pubkey::default() {
+        let price_feed = next_account_info(accounts_iter)?;
+        check_price_condition(&proposal_details.price_condition, price_feed)?;
+    }
+
+    // the accounts and balances of transfers, other proposal types only get the checks above
+    match proposal_details.proposal {
+        ProposalType::Transfer {
+            token_mint,
+            receive_account,
+            amount,
+            vault,
+            unwrap,
+            source,
+        } => {
+            let source_account = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+
+            load_mint_decimals(mint, &token_mint)?;
+
+            let source_account_details =
+                load_token_account(source_account, &token_mint, wallet_authority.key)?;
+            if let Some(seed) = source {
+                if *source_account.key
+                    != aux_token_address(
+                        program_id,
+                        wallet_config.key,
+                        wallet_authority.key,
+                        &token_mint,
+                        seed,
+                    )
+                {
+                    return Err(WalletError::IncorrectSendAccount.into());
+                }
+            }
+            let amount = if amount == TRANSFER_ALL {
+                source_account_details.amount
+            } else {
+                amount
+            };
+            if source_account_details.amount < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            if *destination_account.key != receive_account {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            // the token program would only find out about a missing or mismatched receive account during the cpi,
+            // an unwrapped transfer pays lamports to any account
+            if !unwrap {
+                let destination_account_details =
+                    Account::unpack(&destination_account.data.borrow())?;
+                if destination_account_details.mint != token_mint {
+                    return Err(WalletError::IncorrectReceiveAccount.into());
+                }
+            }
+            let bump = if vault == 0 {
+                wallet_details.authority_bump
+            } else {
+                let vault_account = next_account_info(accounts_iter)?;
+                let vault_details =
+                    load_vault(program_id, wallet_config.key, vault, vault_account)?;
+                // held to the vault's threshold snapshotted when the transfer was proposed
+                if voting_details.votes < voting_details.threshold {
+                    return Err(WalletError::InsufficientVotes.into());
+                }
+                vault_details.authority_bump
+            };
+            let wallet_authority_key =
+                vault_authority_address(program_id, wallet_config.key, vault, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *token_program.key != TOKEN_PROGRAM_ID {
+                return Err(WalletError::IncorrectTokenProgram.into());
+            }
+            check_destination(
+                program_id,
+                wallet_config.key,
+                blacklist,
+                destination_account,
+            )?;
+            track_outflow(
+                program_id,
+                wallet_config.key,
+                &token_mint,
+                treasury_stats,
+                amount,
+            )?;
+        }
+        ProposalType::TransferPercent {
+            token_mint,
+            receive_account,
+            basis_points,
+            vault,
+        } => {
+            let source_account = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+
+            load_mint_decimals(mint, &token_mint)?;
+
+            let source_account_details =
+                load_token_account(source_account, &token_mint, wallet_authority.key)?;
+            let amount = percent_of(source_account_details.amount, basis_points);
+            if *destination_account.key != receive_account {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            let destination_account_details = Account::unpack(&destination_account.data.borrow())?;
+            if destination_account_details.mint != token_mint {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            let bump = if vault == 0 {
+                wallet_details.authority_bump
+            } else {
+                let vault_account = next_account_info(accounts_iter)?;
+                let vault_details =
+                    load_vault(program_id, wallet_config.key, vault, vault_account)?;
+                // held to the vault's threshold snapshotted when the transfer was proposed
+                if voting_details.votes < voting_details.threshold {
+                    return Err(WalletError::InsufficientVotes.into());
+                }
+                vault_details.authority_bump
+            };
+            let wallet_authority_key =
+                vault_authority_address(program_id, wallet_config.key, vault, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *token_program.key != TOKEN_PROGRAM_ID {
+                return Err(WalletError::IncorrectTokenProgram.into());
+            }
+            check_destination(
+                program_id,
+                wallet_config.key,
+                blacklist,
+                destination_account,
+            )?;
+            track_outflow(
+                program_id,
+                wallet_config.key,
+                &token_mint,
+                treasury_stats,
+                amount,
+            )?;
+        }
+        ProposalType::TransferToWallet {
+            token_mint,
+            recipient,
+            amount,
+            vault,
+            treasury_funded,
+        } => {
+            let source_account = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let recipient_account = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+            let payer = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let associated_token_program = next_account_info(accounts_iter)?;
+
+            let source_account_details =
+                load_token_account(source_account, &token_mint, wallet_authority.key)?;
+            let amount = if amount == TRANSFER_ALL {
+                source_account_details.amount
+            } else {
+                amount
+            };
+            if source_account_details.amount < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            if *recipient_account.key != recipient
+                || *mint.key != token_mint
+                || *destination_account.key != get_associated_token_address(&recipient, &token_mint)
+            {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            load_mint_decimals(mint, &token_mint)?;
+            let bump = if vault == 0 {
+                wallet_details.authority_bump
+            } else {
+                let vault_account = next_account_info(accounts_iter)?;
+                let vault_details =
+                    load_vault(program_id, wallet_config.key, vault, vault_account)?;
+                // held to the vault's threshold snapshotted when the transfer was proposed
+                if voting_details.votes < voting_details.threshold {
+                    return Err(WalletError::InsufficientVotes.into());
+                }
+                vault_details.authority_bump
+            };
+            let wallet_authority_key =
+                vault_authority_address(program_id, wallet_config.key, vault, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID
+                || *token_program.key != TOKEN_PROGRAM_ID
+                || *associated_token_program.key != ASSOCIATED_TOKEN_PROGRAM_ID
+            {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            // a treasury funded creation has to leave the rent floor behind, the payer is not a signer here
+            if destination_account.data_is_empty() {
+                if treasury_funded {
+                    let authority_key = authority_address(
+                        program_id,
+                        wallet_config.key,
+                        wallet_details.authority_bump,
+                    )?;
+                    if *payer.key != authority_key {
+                        return Err(WalletError::InvalidWalletAuthority.into());
+                    }
+                    let rent = Rent::get()?.minimum_balance(Account::LEN);
+                    if payer.lamports() < rent.saturating_add(wallet_details.rent_floor) {
+                        return Err(WalletError::BelowRentFloor.into());
+                    }
+                }
+            } else {
+                let destination_account_details =
+                    Account::unpack(&destination_account.data.borrow())?;
+                if destination_account_details.mint != token_mint {
+                    return Err(WalletError::IncorrectReceiveAccount.into());
+                }
+            }
+            check_destination(program_id, wallet_config.key, blacklist, recipient_account)?;
+            track_outflow(
+                program_id,
+                wallet_config.key,
+                &token_mint,
+                treasury_stats,
+                amount,
+            )?;
+        }
+        ProposalType::SplitPayment {
+            token_mint,
+            vault,
+            by_share,
+            payments,
+        } => {
+            let source_account = next_account_info(accounts_iter)?;
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+            let mint = next_account_info(accounts_iter)?;
+
+            load_mint_decimals(mint, &token_mint)?;
+
+            let source_account_details =
+                load_token_account(source_account, &token_mint, wallet_authority.key)?;
+            let (_, total) = split_amounts(source_account_details.amount, by_share, &payments)?;
+            let bump = if vault == 0 {
+                wallet_details.authority_bump
+            } else {
+                let vault_account = next_account_info(accounts_iter)?;
+                let vault_details =
+                    load_vault(program_id, wallet_config.key, vault, vault_account)?;
+                // held to the vault's threshold snapshotted when the transfer was proposed
+                if voting_details.votes < voting_details.threshold {
+                    return Err(WalletError::InsufficientVotes.into());
+                }
+                vault_details.authority_bump
+            };
+            let wallet_authority_key =
+                vault_authority_address(program_id, wallet_config.key, vault, bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *token_program.key != TOKEN_PROGRAM_ID {
+                return Err(WalletError::IncorrectTokenProgram.into());
+            }
+            if accounts_iter.len() != payments.len() {
+                return Err(WalletError::AccountCountMismatch.into());
+            }
+            for payment in payments.iter() {
+                let destination_account = next_account_info(accounts_iter)?;
+                if *destination_account.key != payment.receive_account {
+                    return Err(WalletError::IncorrectReceiveAccount.into());
+                }
+                let destination_account_details =
+                    Account::unpack(&destination_account.data.borrow())?;
+                if destination_account_details.mint != token_mint {
+                    return Err(WalletError::IncorrectReceiveAccount.into());
+                }
+                check_destination(
+                    program_id,
+                    wallet_config.key,
+                    blacklist,
+                    destination_account,
+                )?;
+            }
+            track_outflow(
+                program_id,
+                wallet_config.key,
+                &token_mint,
+                treasury_stats,
+                total,
+            )?;
+        }
+        ProposalType::TransferSol {
+            receive_account,
+            amount,
+        } => {
+            let wallet_authority = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+
+            let wallet_authority_key =
+                authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?;
+            if *wallet_authority.key != wallet_authority_key {
+                return Err(WalletError::InvalidWalletAuthority.into());
+            }
+            if *destination_account.key != receive_account {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            let balance = wallet_authority.lamports();
+            if balance < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            if balance - amount < wallet_details.rent_floor {
+                return Err(WalletError::BelowRentFloor.into());
+            }
+            check_destination(
+                program_id,
+                wallet_config.key,
+                blacklist,
+                destination_account,
+            )?;
+            track_outflow(
+                program_id,
+                wallet_config.key,
+                &Pubkey::default(),
+                treasury_stats,
+                amount,
+            )?;
+        }
+        ProposalType::TreasuryWithdraw {
+            receive_account,
+            amount,
+        } => {
+            let treasury = next_account_info(accounts_iter)?;
+            let destination_account = next_account_info(accounts_iter)?;
+            let system_program = next_account_info(accounts_iter)?;
+            let treasury_stats = next_account_info(accounts_iter)?;
+            let blacklist = next_account_info(accounts_iter)?;
+
+            let treasury_key =
+                treasury_address(program_id, wallet_config.key, wallet_details.treasury_bump)?;
+            if *treasury.key != treasury_key {
+                return Err(WalletError::InvalidTreasury.into());
+            }
+            if *destination_account.key != receive_account {
+                return Err(WalletError::IncorrectReceiveAccount.into());
+            }
+            if *system_program.key != SYSTEM_PROGRAM_ID {
+                return Err(WalletError::IncorrectSystemProgram.into());
+            }
+            let balance = treasury.lamports();
+            if balance < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            if balance - amount != 0 && balance - amount < wallet_details.rent_floor {
+                return Err(WalletError::BelowRentFloor.into());
+            }
+            check_destination(
+                program_id,
+                wallet_config.key,
+                blacklist,
+                destination_account,
+            )?;
+            track_outflow(
+                program_id,
+                wallet_config.key,
+                &Pubkey::default(),
+                treasury_stats,
+                amount,
+            )?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -0,0 +1,189 @@
+// the few token metadata instructions the wallet authority signs as update authority, built by hand so the
+// metaplex crate and its dependency tree stay out of the program
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+};
+
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub(super) const MAX_NAME_LENGTH: usize = 32;
+pub(super) const MAX_SYMBOL_LENGTH: usize = 10;
+pub(super) const MAX_URI_LENGTH: usize = 200;
+
+// positions of the instructions in the metadata program's instruction enum
+const UPDATE_METADATA_ACCOUNT_V2: u8 = 15;
+const SET_AND_VERIFY_COLLECTION: u8 = 25;
+const SET_AND_VERIFY_SIZED_COLLECTION_ITEM: u8 = 32;
+
+// borsh sizes of the values inside the optional fields the wallet carries over
+const CREATOR_LEN: usize = 34;
+const COLLECTION_LEN: usize = 33;
+const USES_LEN: usize = 17;
+
+// the parts of a metadata account the wallet reads, the creators, collection and uses are kept in their borsh
+// encoding, option tag included, so an update can carry them over unchanged
+pub(super) struct Metadata {
+    pub update_authority: Pubkey,
+    creators: Vec<u8>,
+    collection: Vec<u8>,
+    uses: Vec<u8>,
+}
+
+impl Metadata {
+    pub(super) fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let offset = &mut 0;
+        // key
+        take(data, offset, 1)?;
+        let update_authority = Pubkey::new_from_array(
+            take(data, offset, 32)?
+                .try_into()
+                .map_err(|_| ProgramError::InvalidAccountData)?,
+        );
+        // mint, name, symbol and uri, seller fee basis points
+        take(data, offset, 32)?;
+        for _ in 0..3 {
+            let len = take_len(data, offset)?;
+            take(data, offset, len)?;
+        }
+        take(data, offset, 2)?;
+        let start = *offset;
+        if take(data, offset, 1)?[0] != 0 {
+            let count = take_len(data, offset)?;
+            take(data, offset, count * CREATOR_LEN)?;
+        }
+        let creators = data[start..*offset].to_vec();
+        // primary sale happened, is mutable, edition nonce and token standard
+        take(data, offset, 2)?;
+        take_option(data, offset, 1)?;
+        take_option(data, offset, 1)?;
+        let collection = take_option(data, offset, COLLECTION_LEN)?.to_vec();
+        let uses = take_option(data, offset, USES_LEN)?.to_vec();
+        Ok(Metadata {
+            update_authority,
+            creators,
+            collection,
+            uses,
+        })
+    }
+
+    // the DataV2 of an update that only changes the name, symbol, uri and fee
+    pub(super) fn data_v2(
+        &self,
+        name: &str,
+        symbol: &str,
+        uri: &str,
+        seller_fee_basis_points: u16,
+    ) -> Vec<u8> {
+        let mut data = Vec::new();
+        for field in [name, symbol, uri] {
+            data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            data.extend_from_slice(field.as_bytes());
+        }
+        data.extend_from_slice(&seller_fee_basis_points.to_le_bytes());
+        data.extend_from_slice(&self.creators);
+        data.extend_from_slice(&self.collection);
+        data.extend_from_slice(&self.uses);
+        data
+    }
+}
+
+fn take<'a>(data: &'a [u8], offset: &mut usize, len: usize) -> Result<&'a [u8], ProgramError> {
+    let bytes = data
+        .get(*offset..*offset + len)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    *offset += len;
+    Ok(bytes)
+}
+
+// the u32 length prefix of a borsh string or vec
+fn take_len(data: &[u8], offset: &mut usize) -> Result<usize, ProgramError> {
+    let len = take(data, offset, 4)?;
+    Ok(u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize)
+}
+
+// a borsh option whose value is len bytes, returned with its tag
+fn take_option<'a>(
+    data: &'a [u8],
+    offset: &mut usize,
+    len: usize,
+) -> Result<&'a [u8], ProgramError> {
+    let start = *offset;
+    if take(data, offset, 1)?[0] != 0 {
+        take(data, offset, len)?;
+    }
+    Ok(&data[start..*offset])
+}
+
+// data is the borsh encoding of a DataV2, see Metadata::data_v2, the primary sale and mutability flags are left
+// unchanged
+pub(super) fn update_metadata_accounts_v2(
+    metadata: &Pubkey,
+    update_authority: &Pubkey,
+    new_update_authority: Option<Pubkey>,
+    data: Option<Vec<u8>>,
+) -> Instruction {
+    let mut instruction_data = vec![UPDATE_METADATA_ACCOUNT_V2];
+    match data {
+        Some(data) => {
+            instruction_data.push(1);
+            instruction_data.extend_from_slice(&data);
+        }
+        None => instruction_data.push(0),
+    }
+    match new_update_authority {
+        Some(new_update_authority) => {
+            instruction_data.push(1);
+            instruction_data.extend_from_slice(new_update_authority.as_ref());
+        }
+        None => instruction_data.push(0),
+    }
+    instruction_data.extend_from_slice(&[0, 0]);
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*metadata, false),
+            AccountMeta::new_readonly(*update_authority, true),
+        ],
+        data: instruction_data,
+    }
+}
+
+// authority is the update authority of both the item and the collection and also pays, the collection metadata
+// only changes when the collection is sized
+pub(super) fn set_and_verify_collection(
+    metadata: &Pubkey,
+    authority: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_metadata: &Pubkey,
+    collection_master_edition: &Pubkey,
+    sized: bool,
+) -> Instruction {
+    let (variant, collection_metadata) = if sized {
+        (
+            SET_AND_VERIFY_SIZED_COLLECTION_ITEM,
+            AccountMeta::new(*collection_metadata, false),
+        )
+    } else {
+        (
+            SET_AND_VERIFY_COLLECTION,
+            AccountMeta::new_readonly(*collection_metadata, false),
+        )
+    };
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*metadata, false),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(*authority, false),
+            AccountMeta::new_readonly(*collection_mint, false),
+            collection_metadata,
+            AccountMeta::new_readonly(*collection_master_edition, false),
+        ],
+        data: vec![variant],
+    }
+}
@@ -1,8 +1,25 @@
-mod handler;
+mod accounts;
+mod audit;
+pub mod bitmap;
+mod cpi;
+mod create_wallet;
+mod execute;
+pub(crate) mod metadata;
+pub(crate) mod name_service;
+pub mod oracle;
+mod ownership;
+pub(crate) mod pda;
+mod proposal;
+pub mod stake_pool;
+mod stats;
+mod token_account;
+mod treasury;
+pub(crate) mod vote;
 
 use crate::instruction::WalletInstruction;
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
 
+// instances of Proposal, VoteCount, WalletAuth, WalletConfig are created with names proposal_details, voting_details, user_details and wallet_details respectively in the handlers
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -15,15 +32,119 @@ pub fn process_instruction(
             n,
             owners,
             proposal_lifetime,
-        } => handler::create_wallet(program_id, accounts, m, n, &owners, proposal_lifetime),
-        WalletInstruction::CreateTokenAccount => {
-            handler::create_token_account(program_id, accounts)
+            min_proposal_lifetime,
+            max_proposal_lifetime,
+        } => create_wallet::create_wallet(
+            program_id,
+            accounts,
+            m,
+            n,
+            &owners,
+            proposal_lifetime,
+            min_proposal_lifetime,
+            max_proposal_lifetime,
+        ),
+        WalletInstruction::CreateTokenAccount { vault } => {
+            token_account::create_token_account(program_id, accounts, vault)
+        }
+        WalletInstruction::GiveupOwnership => ownership::give_up_ownership(program_id, accounts),
+        WalletInstruction::CreateProposal {
+            proposal,
+            urgent,
+            treasury_funded,
+            draft,
+        } => proposal::create_proposal(
+            program_id,
+            accounts,
+            proposal,
+            urgent,
+            treasury_funded,
+            draft,
+        ),
+        WalletInstruction::Vote => vote::vote(program_id, accounts),
+        WalletInstruction::CloseProposal => execute::close_proposal(program_id, accounts),
+        WalletInstruction::DepositSol { amount } => {
+            treasury::deposit_sol(program_id, accounts, amount)
+        }
+        WalletInstruction::ClaimRecovery => ownership::claim_recovery(program_id, accounts),
+        WalletInstruction::SpendAllowance { amount } => {
+            treasury::spend_allowance(program_id, accounts, amount)
+        }
+        WalletInstruction::RegisterSessionKey {
+            session_key,
+            expires_at,
+            scope,
+        } => ownership::register_session_key(program_id, accounts, session_key, expires_at, scope),
+        WalletInstruction::RevokeSessionKey => ownership::revoke_session_key(program_id, accounts),
+        WalletInstruction::CreateProposalFromTemplate => {
+            proposal::create_proposal_from_template(program_id, accounts)
+        }
+        WalletInstruction::ReleaseEscrow => treasury::release_escrow(program_id, accounts),
+        WalletInstruction::WithdrawStream => treasury::withdraw_stream(program_id, accounts),
+        WalletInstruction::WithdrawClaim => treasury::withdraw_claim(program_id, accounts),
+        WalletInstruction::ReclaimExpiredClaim => {
+            treasury::reclaim_expired_claim(program_id, accounts)
+        }
+        WalletInstruction::GetSolBalance => treasury::get_sol_balance(program_id, accounts),
+        WalletInstruction::ExecuteRecurringSwap { data } => {
+            treasury::execute_recurring_swap(program_id, accounts, data)
+        }
+        WalletInstruction::GetWalletInfo => create_wallet::get_wallet_info(program_id, accounts),
+        WalletInstruction::GetProposalStatus => proposal::get_proposal_status(program_id, accounts),
+        WalletInstruction::ValidateProposal => {
+            execute::validate_proposal_execution(program_id, accounts)
+        }
+        WalletInstruction::VoteMany { proposals } => {
+            vote::vote_many(program_id, accounts, proposals)
+        }
+        WalletInstruction::VoteWithSignatures => vote::vote_with_signatures(program_id, accounts),
+        WalletInstruction::ApplyPendingChange => {
+            execute::apply_pending_change(program_id, accounts)
+        }
+        WalletInstruction::SweepTokenAccounts => {
+            token_account::sweep_token_accounts(program_id, accounts)
+        }
+        WalletInstruction::RequestKeyRotation { new_owner } => {
+            ownership::request_key_rotation(program_id, accounts, new_owner)
+        }
+        WalletInstruction::VetoKeyRotation => ownership::veto_key_rotation(program_id, accounts),
+        WalletInstruction::CompleteKeyRotation => {
+            ownership::complete_key_rotation(program_id, accounts)
+        }
+        WalletInstruction::SubmitVotePermit { expirations } => {
+            vote::submit_vote_permit(program_id, accounts, expirations)
+        }
+        WalletInstruction::CreateAuditLog => audit::create_audit_log(program_id, accounts),
+        WalletInstruction::CreateWalletStats => stats::create_wallet_stats(program_id, accounts),
+        WalletInstruction::CloseEmptyTokenAccounts { vault } => {
+            token_account::close_empty_token_accounts(program_id, accounts, vault)
+        }
+        WalletInstruction::DepositTreasury { amount } => {
+            treasury::deposit_treasury(program_id, accounts, amount)
+        }
+        WalletInstruction::ReleaseVesting => treasury::release_vesting(program_id, accounts),
+        WalletInstruction::EmergencyFreeze => ownership::emergency_freeze(program_id, accounts),
+        WalletInstruction::EditDraft { proposal } => {
+            proposal::edit_draft(program_id, accounts, proposal)
+        }
+        WalletInstruction::ActivateProposal => proposal::activate_proposal(program_id, accounts),
+        WalletInstruction::AmendProposal { proposal } => {
+            proposal::amend_proposal(program_id, accounts, proposal)
+        }
+        WalletInstruction::ExtendProposal => vote::extend_proposal(program_id, accounts),
+        WalletInstruction::CloneWallet => create_wallet::clone_wallet(program_id, accounts),
+        WalletInstruction::CommentVote { comment } => {
+            vote::comment_vote(program_id, accounts, comment)
+        }
+        WalletInstruction::SetContentHash { content_hash } => {
+            proposal::set_content_hash(program_id, accounts, content_hash)
+        }
+        WalletInstruction::CreateAuxTokenAccount { vault, seed } => {
+            token_account::create_aux_token_account(program_id, accounts, vault, seed)
         }
-        WalletInstruction::GiveupOwnership => handler::give_up_ownership(program_id, accounts),
-        WalletInstruction::CreateProposal { proposal } => {
-            handler::create_proposal(program_id, accounts, proposal)
+        WalletInstruction::SetPriceCondition { condition } => {
+            proposal::set_price_condition(program_id, accounts, condition)
         }
-        WalletInstruction::Vote => handler::vote(program_id, accounts),
-        WalletInstruction::CloseProposal => handler::close_proposal(program_id, accounts),
+        WalletInstruction::ObjectProposal => proposal::object_proposal(program_id, accounts),
     }
 }
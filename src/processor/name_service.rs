@@ -0,0 +1,56 @@
+// the name service instructions for domains the wallet authority owns, the .sol registrar keeps domains as plain
+// name accounts so transfers and record updates go straight to the name service
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+};
+
+pub const NAME_SERVICE_PROGRAM_ID: Pubkey = pubkey!("namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX");
+
+// positions of the instructions in the name service's instruction enum
+const UPDATE: u8 = 1;
+const TRANSFER: u8 = 2;
+
+// parent name, owner and class precede the data of every name account
+pub(super) fn name_owner(data: &[u8]) -> Result<Pubkey, ProgramError> {
+    data.get(32..64)
+        .and_then(|owner| owner.try_into().ok())
+        .map(Pubkey::new_from_array)
+        .ok_or(ProgramError::InvalidAccountData)
+}
+
+// offset is into the data after the 96 byte header
+pub(super) fn update(
+    name_account: &Pubkey,
+    owner: &Pubkey,
+    offset: u32,
+    data: &[u8],
+) -> Instruction {
+    let mut instruction_data = vec![UPDATE];
+    instruction_data.extend_from_slice(&offset.to_le_bytes());
+    instruction_data.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    instruction_data.extend_from_slice(data);
+    Instruction {
+        program_id: NAME_SERVICE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: instruction_data,
+    }
+}
+
+pub(super) fn transfer(name_account: &Pubkey, owner: &Pubkey, new_owner: &Pubkey) -> Instruction {
+    let mut instruction_data = vec![TRANSFER];
+    instruction_data.extend_from_slice(new_owner.as_ref());
+    Instruction {
+        program_id: NAME_SERVICE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: instruction_data,
+    }
+}
@@ -0,0 +1,64 @@
+// pyth v2 price accounts read by hand, only the header and the aggregate price are used
+use crate::error::WalletError;
+use crate::state::PriceCondition;
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult,
+    program_error::ProgramError, pubkey, pubkey::Pubkey, sysvar::Sysvar,
+};
+
+// price accounts are only trusted when owned by the pyth oracle program
+pub const PYTH_PROGRAM_ID: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+
+const MAGIC: u32 = 0xa1b2c3d4;
+const PRICE_ACCOUNT_TYPE: u32 = 3;
+const STATUS_TRADING: u32 = 1;
+// offsets into the price account
+const TYPE_OFFSET: usize = 8;
+const TIMESTAMP_OFFSET: usize = 96;
+const PRICE_OFFSET: usize = 208;
+const STATUS_OFFSET: usize = 224;
+
+// seconds the aggregate price may have been published before execution
+const MAX_PRICE_AGE: i64 = 60;
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], ProgramError> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| WalletError::InvalidPriceFeed.into())
+}
+
+// the aggregate price in the feed's exponent and the time it was published at, only while the feed is trading
+fn aggregate_price(data: &[u8]) -> Result<(i64, i64), ProgramError> {
+    if u32::from_le_bytes(read_bytes(data, 0)?) != MAGIC
+        || u32::from_le_bytes(read_bytes(data, TYPE_OFFSET)?) != PRICE_ACCOUNT_TYPE
+        || u32::from_le_bytes(read_bytes(data, STATUS_OFFSET)?) != STATUS_TRADING
+    {
+        return Err(WalletError::InvalidPriceFeed.into());
+    }
+    Ok((
+        i64::from_le_bytes(read_bytes(data, PRICE_OFFSET)?),
+        i64::from_le_bytes(read_bytes(data, TIMESTAMP_OFFSET)?),
+    ))
+}
+
+pub(super) fn check_price_condition(
+    condition: &PriceCondition,
+    price_feed: &AccountInfo,
+) -> ProgramResult {
+    if *price_feed.key != condition.feed || *price_feed.owner != PYTH_PROGRAM_ID {
+        return Err(WalletError::InvalidPriceFeed.into());
+    }
+    let (price, published_at) = aggregate_price(&price_feed.data.borrow())?;
+    if Clock::get()?.unix_timestamp.saturating_sub(published_at) > MAX_PRICE_AGE {
+        return Err(WalletError::InvalidPriceFeed.into());
+    }
+    let met = if condition.above {
+        price > condition.threshold
+    } else {
+        price < condition.threshold
+    };
+    if !met {
+        return Err(WalletError::PriceConditionNotMet.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,699 @@
+use super::accounts::{
+    check_refund_destination, check_rent_exempt, load, load_key_rotation, load_mint_decimals,
+    load_owner, store, VetoKeyRotationAccounts,
+};
+use super::audit::append_audit_record;
+use super::bitmap::{clear_bit, set_bit};
+use super::pda::{
+    authority_address, session_address, wallet_auth_address, AUTHORITY, OWNER, ROTATION, SESSION,
+};
+use crate::error::WalletError;
+use crate::event::WalletEvent;
+use crate::state::{
+    AccountType, AuditAction, KeyRotation, SessionKey, WalletAuth, WalletConfig, ROLE_ALL,
+    SESSION_SCOPES,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::{rent::Rent, Sysvar},
+};
+use spl_token::{instruction as token_instruction, state::Account, ID as TOKEN_PROGRAM_ID};
+
+pub fn give_up_ownership(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let refund_destination = next_account_info(accounts_iter)?;
+    let audit_log = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let mut user_details = load_owner(program_id, wallet_config.key, user.key, wallet_auth)?;
+    // the treasury is closed along with the last owner, so its rent can only go to the user
+    if wallet_details.owners == 1 && refund_destination.key != user.key {
+        return Err(WalletError::InvalidRefundDestination.into());
+    }
+    check_refund_destination(
+        program_id,
+        wallet_config.key,
+        &wallet_details,
+        refund_destination.key,
+        user.key,
+    )?;
+    user_details.is_initialized = false;
+    store(&user_details, wallet_auth)?;
+    let mut balance = wallet_auth.lamports();
+    **wallet_auth.try_borrow_mut_lamports()? -= balance;
+    **refund_destination.try_borrow_mut_lamports()? += balance;
+    WalletEvent::OwnershipRenounced {
+        wallet: *wallet_config.key,
+        owner: *user.key,
+    }
+    .emit(&mut wallet_details);
+    append_audit_record(
+        program_id,
+        wallet_config.key,
+        audit_log,
+        AuditAction::OwnerLeft,
+        0,
+        0,
+        *user.key,
+    )?;
+
+    if wallet_details.owners == 1 {
+        wallet_details.is_initialized = false;
+        WalletEvent::WalletClosed {
+            wallet: *wallet_config.key,
+        }
+        .emit(&mut wallet_details);
+        store(&wallet_details, wallet_config)?;
+        balance = wallet_config.lamports();
+        **wallet_config.try_borrow_mut_lamports()? -= balance;
+        **user.try_borrow_mut_lamports()? += balance;
+        if accounts.iter().len() == 0 {
+            return Ok(());
+        }
+
+        let wallet_authority = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+
+        let bump = wallet_details.authority_bump;
+        let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+        if *wallet_authority.key != wallet_authority_key {
+            return Err(WalletError::InvalidWalletAuthority.into());
+        }
+        if *token_program.key != TOKEN_PROGRAM_ID {
+            return Err(WalletError::IncorrectTokenProgram.into());
+        }
+        if accounts_iter.len() % 3 != 0 {
+            return Err(WalletError::AccountCountMismatch.into());
+        }
+        let mut send_account;
+        let mut receive_account;
+        let mut mint;
+        let mut send_account_details;
+        let mut decimals;
+        while accounts_iter.len() > 0 {
+            send_account = next_account_info(accounts_iter)?;
+            receive_account = next_account_info(accounts_iter)?;
+            mint = next_account_info(accounts_iter)?;
+            send_account_details = Account::unpack(&send_account.data.borrow())?;
+            decimals = load_mint_decimals(mint, &send_account_details.mint)?;
+            invoke_signed(
+                &token_instruction::transfer_checked(
+                    token_program.key,
+                    send_account.key,
+                    mint.key,
+                    receive_account.key,
+                    wallet_authority.key,
+                    &[],
+                    send_account_details.amount,
+                    decimals,
+                )?,
+                &[
+                    send_account.clone(),
+                    mint.clone(),
+                    receive_account.clone(),
+                    wallet_authority.clone(),
+                ],
+                &[&[
+                    AUTHORITY.as_bytes(),
+                    wallet_config.key.as_ref(),
+                    &[bump],
+                ]],
+            )?;
+        }
+    } else {
+        clear_bit(&mut wallet_details.owner_identities, user_details.id);
+        // the id may be handed to a new owner later, it must not carry the requirement over
+        clear_bit(&mut wallet_details.mandatory_approvers, user_details.id);
+        wallet_details.owners -= 1;
+        wallet_details.epoch += 1;
+        store(&wallet_details, wallet_config)?;
+    }
+
+    Ok(())
+}
+
+// moves an owner's wallet auth to new_owner, used by ChangeOwnerKey and CompleteKeyRotation
+#[allow(clippy::too_many_arguments)]
+pub(super) fn rotate_owner_key<'a>(
+    program_id: &Pubkey,
+    wallet_config: &AccountInfo<'a>,
+    wallet_details: &mut WalletConfig,
+    payer: &AccountInfo<'a>,
+    old_wallet_auth: &AccountInfo<'a>,
+    new_wallet_auth: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    old_owner: Pubkey,
+    new_owner: Pubkey,
+) -> ProgramResult {
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut old_details = load::<WalletAuth>(old_wallet_auth, program_id)?;
+    if old_details.wallet != *wallet_config.key
+        || old_details.owner != old_owner
+        || *old_wallet_auth.key
+            != wallet_auth_address(program_id, wallet_config.key, &old_owner, old_details.bump)?
+    {
+        return Err(WalletError::InvalidWalletAuth.into());
+    }
+    // an NFT-bound seat changes hands with its NFT
+    if old_details.nft_bound {
+        return Err(WalletError::NftBoundSeat.into());
+    }
+    let (new_wallet_auth_key, bump) = Pubkey::find_program_address(
+        &[
+            OWNER.as_bytes(),
+            wallet_config.key.as_ref(),
+            new_owner.as_ref(),
+        ],
+        program_id,
+    );
+    if *new_wallet_auth.key != new_wallet_auth_key {
+        return Err(WalletError::InvalidWalletAuth.into());
+    }
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        return Err(WalletError::IncorrectSystemProgram.into());
+    }
+
+    // close the old wallet auth, the id stays set so votes already cast by it still count
+    old_details.is_initialized = false;
+    store(&old_details, old_wallet_auth)?;
+    let balance = old_wallet_auth.lamports();
+    **old_wallet_auth.try_borrow_mut_lamports()? -= balance;
+    **payer.try_borrow_mut_lamports()? += balance;
+
+    // create the new wallet auth carrying over the old one's details
+    let account_size: u64 = WalletAuth::LEN.try_into().unwrap();
+    let rent = Rent::get()?;
+    let rent_amount = rent.minimum_balance(WalletAuth::LEN);
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            new_wallet_auth.key,
+            rent_amount,
+            account_size,
+            program_id,
+        ),
+        &[payer.clone(), new_wallet_auth.clone()],
+        &[&[
+            OWNER.as_bytes(),
+            wallet_config.key.as_ref(),
+            new_owner.as_ref(),
+            &[bump],
+        ]],
+    )?;
+    check_rent_exempt(new_wallet_auth, &rent)?;
+    let user_details = WalletAuth {
+        owner: new_owner,
+        bump,
+        is_initialized: true,
+        ..old_details
+    };
+    store(&user_details, new_wallet_auth)?;
+
+    WalletEvent::OwnerKeyChanged {
+        wallet: *wallet_config.key,
+        old_owner,
+        new_owner,
+        id: user_details.id,
+    }
+    .emit(wallet_details);
+
+    Ok(())
+}
+
+pub fn request_key_rotation(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_owner: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let key_rotation = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer || !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    load_owner(program_id, wallet_config.key, owner.key, wallet_auth)?;
+    if new_owner == *owner.key {
+        return Err(WalletError::InvalidKeyRotation.into());
+    }
+    let (key_rotation_key, bump) = Pubkey::find_program_address(
+        &[
+            ROTATION.as_bytes(),
+            wallet_config.key.as_ref(),
+            owner.key.as_ref(),
+        ],
+        program_id,
+    );
+    if *key_rotation.key != key_rotation_key {
+        return Err(WalletError::InvalidKeyRotation.into());
+    }
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        return Err(WalletError::IncorrectSystemProgram.into());
+    }
+
+    // an owner has at most one rotation pending, creating a second one fails
+    let account_size: u64 = KeyRotation::LEN.try_into().unwrap();
+    let rent = Rent::get()?;
+    let rent_amount = rent.minimum_balance(KeyRotation::LEN);
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            key_rotation.key,
+            rent_amount,
+            account_size,
+            program_id,
+        ),
+        &[payer.clone(), key_rotation.clone()],
+        &[&[
+            ROTATION.as_bytes(),
+            wallet_config.key.as_ref(),
+            owner.key.as_ref(),
+            &[bump],
+        ]],
+    )?;
+    check_rent_exempt(key_rotation, &rent)?;
+    let effective_at = Clock::get()?
+        .unix_timestamp
+        .saturating_add(wallet_details.rotation_delay);
+    let rotation_details = KeyRotation {
+        discriminator: AccountType::KeyRotation,
+        wallet: *wallet_config.key,
+        old_owner: *owner.key,
+        new_owner,
+        payer: *payer.key,
+        effective_at,
+        bump,
+        is_initialized: true,
+    };
+    store(&rotation_details, key_rotation)?;
+
+    WalletEvent::KeyRotationRequested {
+        wallet: *wallet_config.key,
+        old_owner: *owner.key,
+        new_owner,
+        effective_at,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)
+}
+
+fn close_key_rotation(
+    key_rotation: &AccountInfo,
+    mut rotation_details: KeyRotation,
+    refund_destination: &AccountInfo,
+) -> ProgramResult {
+    if *refund_destination.key != rotation_details.payer {
+        return Err(WalletError::InvalidRefundDestination.into());
+    }
+    rotation_details.is_initialized = false;
+    store(&rotation_details, key_rotation)?;
+    let balance = key_rotation.lamports();
+    **key_rotation.try_borrow_mut_lamports()? -= balance;
+    **refund_destination.try_borrow_mut_lamports()? += balance;
+    Ok(())
+}
+
+// any one owner can freeze, only an approved Unfreeze proposal lifts it
+pub fn emergency_freeze(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let audit_log = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    load_owner(program_id, wallet_config.key, owner.key, wallet_auth)?;
+    if wallet_details.frozen {
+        return Err(WalletError::WalletFrozen.into());
+    }
+    wallet_details.frozen = true;
+    WalletEvent::WalletFrozen {
+        wallet: *wallet_config.key,
+        owner: *owner.key,
+    }
+    .emit(&mut wallet_details);
+    append_audit_record(
+        program_id,
+        wallet_config.key,
+        audit_log,
+        AuditAction::WalletFrozen,
+        0,
+        0,
+        *owner.key,
+    )?;
+    store(&wallet_details, wallet_config)
+}
+
+pub fn veto_key_rotation(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let VetoKeyRotationAccounts {
+        owner,
+        wallet_config,
+        key_rotation,
+        refund_destination,
+        mut wallet_details,
+        rotation_details,
+    } = VetoKeyRotationAccounts::load_and_validate(program_id, accounts)?;
+
+    let old_owner = rotation_details.old_owner;
+    close_key_rotation(key_rotation, rotation_details, refund_destination)?;
+
+    WalletEvent::KeyRotationVetoed {
+        wallet: *wallet_config.key,
+        old_owner,
+        vetoed_by: *owner.key,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)
+}
+
+pub fn complete_key_rotation(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let old_wallet_auth = next_account_info(accounts_iter)?;
+    let new_wallet_auth = next_account_info(accounts_iter)?;
+    let key_rotation = next_account_info(accounts_iter)?;
+    let refund_destination = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let audit_log = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let rotation_details = load_key_rotation(program_id, wallet_config.key, key_rotation)?;
+    if Clock::get()?.unix_timestamp < rotation_details.effective_at {
+        return Err(WalletError::KeyRotationNotDue.into());
+    }
+    let old_owner = rotation_details.old_owner;
+    let new_owner = rotation_details.new_owner;
+    close_key_rotation(key_rotation, rotation_details, refund_destination)?;
+
+    rotate_owner_key(
+        program_id,
+        wallet_config,
+        &mut wallet_details,
+        payer,
+        old_wallet_auth,
+        new_wallet_auth,
+        system_program,
+        old_owner,
+        new_owner,
+    )?;
+    store(&wallet_details, wallet_config)?;
+
+    append_audit_record(
+        program_id,
+        wallet_config.key,
+        audit_log,
+        AuditAction::KeyRotated,
+        0,
+        0,
+        new_owner,
+    )
+}
+
+pub fn claim_recovery(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let beneficiary = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let beneficiary_wallet_auth = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let audit_log = next_account_info(accounts_iter)?;
+
+    if !beneficiary.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    if wallet_details.inactivity_period == 0 || wallet_details.beneficiary == Pubkey::default() {
+        return Err(WalletError::RecoveryNotConfigured.into());
+    }
+    if *beneficiary.key != wallet_details.beneficiary {
+        return Err(WalletError::IncorrectBeneficiary.into());
+    }
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time <= wallet_details.last_activity + wallet_details.inactivity_period {
+        return Err(WalletError::WalletStillActive.into());
+    }
+    let (wallet_auth_key, bump) = Pubkey::find_program_address(
+        &[
+            OWNER.as_bytes(),
+            wallet_config.key.as_ref(),
+            beneficiary.key.as_ref(),
+        ],
+        program_id,
+    );
+    if *beneficiary_wallet_auth.key != wallet_auth_key {
+        return Err(WalletError::InvalidWalletAuth.into());
+    }
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        return Err(WalletError::IncorrectSystemProgram.into());
+    }
+    if accounts_iter.len() != usize::from(wallet_details.owners) {
+        return Err(WalletError::OwnerWalletAuthCountMismatch.into());
+    }
+    // close the wallet auth accounts of all current owners, the beneficiary's own (if any) is reused
+    let mut beneficiary_listed = false;
+    for wallet_auth in accounts_iter {
+        let mut user_details = load::<WalletAuth>(wallet_auth, program_id)?;
+        if user_details.wallet != *wallet_config.key {
+            return Err(WalletError::InvalidWalletAuth.into());
+        }
+        if wallet_auth.key == beneficiary_wallet_auth.key {
+            if beneficiary_listed {
+                return Err(WalletError::InvalidWalletAuth.into());
+            }
+            beneficiary_listed = true;
+            continue;
+        }
+        user_details.is_initialized = false;
+        store(&user_details, wallet_auth)?;
+        let balance = wallet_auth.lamports();
+        **wallet_auth.try_borrow_mut_lamports()? -= balance;
+        **beneficiary.try_borrow_mut_lamports()? += balance;
+        WalletEvent::OwnerRemoved {
+            wallet: *wallet_config.key,
+            owner: user_details.owner,
+        }
+        .emit(&mut wallet_details);
+    }
+    // make the beneficiary the sole owner of the wallet
+    let rent = Rent::get()?;
+    if beneficiary_wallet_auth.owner != program_id {
+        let account_size: u64 = WalletAuth::LEN.try_into().unwrap();
+        let rent_amount = rent.minimum_balance(WalletAuth::LEN);
+        invoke_signed(
+            &system_instruction::create_account(
+                beneficiary.key,
+                beneficiary_wallet_auth.key,
+                rent_amount,
+                account_size,
+                program_id,
+            ),
+            &[beneficiary.clone(), beneficiary_wallet_auth.clone()],
+            &[&[
+                OWNER.as_bytes(),
+                wallet_config.key.as_ref(),
+                beneficiary.key.as_ref(),
+                &[bump],
+            ]],
+        )?;
+    }
+    check_rent_exempt(beneficiary_wallet_auth, &rent)?;
+    let user_details = WalletAuth {
+        discriminator: AccountType::WalletAuth,
+        owner: *beneficiary.key,
+        wallet: *wallet_config.key,
+        added_time: current_time,
+        id: 0,
+        last_active_at: current_time,
+        last_vote_at: current_time,
+        proposals_created: 0,
+        votes_cast: 0,
+        nft_bound: false,
+        roles: ROLE_ALL,
+        bump,
+        is_initialized: true,
+    };
+    store(&user_details, beneficiary_wallet_auth)?;
+
+    let mut identities = [0u8; 32];
+    set_bit(&mut identities, 0);
+    wallet_details.m = 1;
+    wallet_details.n = 1;
+    wallet_details.owners = 1;
+    wallet_details.epoch += 1;
+    wallet_details.owner_identities = identities;
+    wallet_details.mandatory_approvers = [0; 32];
+    wallet_details.last_activity = current_time;
+    wallet_details.beneficiary = Pubkey::default();
+    wallet_details.inactivity_period = 0;
+    WalletEvent::RecoveryClaimed {
+        wallet: *wallet_config.key,
+        beneficiary: *beneficiary.key,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)?;
+
+    append_audit_record(
+        program_id,
+        wallet_config.key,
+        audit_log,
+        AuditAction::RecoveryClaimed,
+        0,
+        0,
+        *beneficiary.key,
+    )
+}
+
+pub fn register_session_key(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    session_key: Pubkey,
+    expires_at: i64,
+    scope: u8,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let session = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    load::<WalletConfig>(wallet_config, program_id)?;
+    load_owner(program_id, wallet_config.key, owner.key, wallet_auth)?;
+    if expires_at <= Clock::get()?.unix_timestamp {
+        return Err(WalletError::InvalidSessionExpiry.into());
+    }
+    if scope & !SESSION_SCOPES != 0 {
+        return Err(WalletError::InvalidSessionScope.into());
+    }
+
+    // re-registering an existing session key only changes its expiry and scope
+    if !session.data_is_empty() {
+        let mut session_details = load::<SessionKey>(session, program_id)?;
+        let session_account_key = session_address(
+            program_id,
+            wallet_config.key,
+            owner.key,
+            &session_key,
+            session_details.bump,
+        )?;
+        if *session.key != session_account_key {
+            return Err(WalletError::InvalidSessionKey.into());
+        }
+        session_details.expires_at = expires_at;
+        session_details.scope = scope;
+        store(&session_details, session)?;
+        return Ok(());
+    }
+    let (session_account_key, bump) = Pubkey::find_program_address(
+        &[
+            SESSION.as_bytes(),
+            wallet_config.key.as_ref(),
+            owner.key.as_ref(),
+            session_key.as_ref(),
+        ],
+        program_id,
+    );
+    if *session.key != session_account_key {
+        return Err(WalletError::InvalidSessionKey.into());
+    }
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        return Err(WalletError::IncorrectSystemProgram.into());
+    }
+    // create session key account
+    let account_size: u64 = SessionKey::LEN.try_into().unwrap();
+    let rent = Rent::get()?;
+    let rent_amount = rent.minimum_balance(SessionKey::LEN);
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            session.key,
+            rent_amount,
+            account_size,
+            program_id,
+        ),
+        &[owner.clone(), session.clone()],
+        &[&[
+            SESSION.as_bytes(),
+            wallet_config.key.as_ref(),
+            owner.key.as_ref(),
+            session_key.as_ref(),
+            &[bump],
+        ]],
+    )?;
+    check_rent_exempt(session, &rent)?;
+    // initialize session key account
+    let session_details = SessionKey {
+        discriminator: AccountType::SessionKey,
+        wallet: *wallet_config.key,
+        owner: *owner.key,
+        key: session_key,
+        expires_at,
+        scope,
+        bump,
+        is_initialized: true,
+    };
+    store(&session_details, session)?;
+
+    Ok(())
+}
+
+pub fn revoke_session_key(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let session = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut session_details = load::<SessionKey>(session, program_id)?;
+    let session_account_key = session_address(
+        program_id,
+        wallet_config.key,
+        owner.key,
+        &session_details.key,
+        session_details.bump,
+    )?;
+    if *session.key != session_account_key {
+        return Err(WalletError::InvalidSessionKey.into());
+    }
+    // close session key account, a former owner can still reclaim the rent
+    session_details.is_initialized = false;
+    store(&session_details, session)?;
+    let balance = session.lamports();
+    **session.try_borrow_mut_lamports()? -= balance;
+    **owner.try_borrow_mut_lamports()? += balance;
+
+    Ok(())
+}
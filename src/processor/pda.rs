@@ -0,0 +1,309 @@
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+pub(crate) const OWNER: &str = "owner";
+pub(crate) const AUTHORITY: &str = "authority";
+pub(crate) const VOTES: &str = "votes";
+pub(crate) const PROPOSAL: &str = "proposal";
+pub(crate) const ALLOWANCE: &str = "allowance";
+pub(crate) const SESSION: &str = "session";
+pub(crate) const TEMPLATE: &str = "template";
+pub(crate) const VAULT: &str = "vault";
+pub(crate) const ESCROW: &str = "escrow";
+pub(crate) const STREAM: &str = "stream";
+pub(crate) const CLAIM: &str = "claim";
+pub(crate) const REGISTRY: &str = "registry";
+pub(crate) const SWAP: &str = "swap";
+pub(crate) const VOTE_DIGEST: &str = "multisig-vote";
+pub(crate) const VOTE_PERMIT: &str = "multisig-vote-permit";
+pub(crate) const UNWRAP: &str = "unwrap";
+pub(crate) const TOKEN_MULTISIG: &str = "token-multisig";
+pub(crate) const ROTATION: &str = "rotation";
+pub(crate) const STATS: &str = "stats";
+pub(crate) const BLACKLIST: &str = "blacklist";
+pub(crate) const AUDIT: &str = "audit";
+pub(crate) const WALLET_STATS: &str = "wallet-stats";
+pub(crate) const TREASURY: &str = "treasury";
+pub(crate) const VESTING: &str = "vesting";
+pub(crate) const COMMENT: &str = "comment";
+pub(crate) const AUX_TOKEN: &str = "aux-token";
+pub(crate) const SPLIT_STAKE: &str = "split-stake";
+
+// bumps are found once when an account is created and stored in it, later checks only recreate the address
+pub(super) fn wallet_auth_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    owner: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[
+            OWNER.as_bytes(),
+            wallet_config.as_ref(),
+            owner.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
+
+pub(super) fn authority_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[AUTHORITY.as_bytes(), wallet_config.as_ref(), &[bump]],
+        program_id,
+    )?)
+}
+
+// spendable SOL, kept apart from the authority whose lamports pay rent for the wallet's accounts
+pub(super) fn treasury_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[TREASURY.as_bytes(), wallet_config.as_ref(), &[bump]],
+        program_id,
+    )?)
+}
+
+pub(super) fn allowance_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[
+            ALLOWANCE.as_bytes(),
+            wallet_config.as_ref(),
+            owner.as_ref(),
+            mint.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
+
+pub(super) fn session_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    owner: &Pubkey,
+    session_key: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[
+            SESSION.as_bytes(),
+            wallet_config.as_ref(),
+            owner.as_ref(),
+            session_key.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
+
+// vault 0 is the wallet's own authority, every other vault adds its index to the authority seeds
+pub(super) fn vault_authority_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    vault: u8,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    if vault == 0 {
+        return authority_address(program_id, wallet_config, bump);
+    }
+    Ok(Pubkey::create_program_address(
+        &[
+            AUTHORITY.as_bytes(),
+            wallet_config.as_ref(),
+            &[vault],
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
+
+pub(super) fn vault_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    index: u8,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[VAULT.as_bytes(), wallet_config.as_ref(), &[index], &[bump]],
+        program_id,
+    )?)
+}
+
+pub(super) fn escrow_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[
+            ESCROW.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
+
+pub(super) fn stream_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[
+            STREAM.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
+
+pub(super) fn claim_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[
+            CLAIM.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
+
+pub(super) fn vesting_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[
+            VESTING.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
+
+// token accounts keep no bump, so the address is searched for
+pub(super) fn aux_token_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+    seed: u64,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            AUX_TOKEN.as_bytes(),
+            wallet_config.as_ref(),
+            authority.as_ref(),
+            mint.as_ref(),
+            &seed.to_le_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub(super) fn swap_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[
+            SWAP.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
+
+pub(super) fn registry_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[REGISTRY.as_bytes(), wallet_config.as_ref(), &[bump]],
+        program_id,
+    )?)
+}
+
+pub(super) fn template_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    id: u64,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[
+            TEMPLATE.as_bytes(),
+            wallet_config.as_ref(),
+            &id.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
+
+pub(super) fn proposal_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    sequence: u64,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[
+            PROPOSAL.as_bytes(),
+            wallet_config.as_ref(),
+            &sequence.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
+
+pub(super) fn vote_count_address(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    proposal: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Ok(Pubkey::create_program_address(
+        &[
+            VOTES.as_bytes(),
+            wallet_config.as_ref(),
+            proposal.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )?)
+}
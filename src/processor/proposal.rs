@@ -0,0 +1,1016 @@
+use super::accounts::{
+    check_rent_exempt, load, load_owner, load_signer, load_vault, next_vault_account, store,
+    CreateProposalAccounts, ProposalAccounts, ProposalStatusAccounts,
+};
+use super::bitmap::set_bit;
+use super::metadata::{MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH};
+use super::pda::{
+    proposal_address, template_address, vote_count_address, AUTHORITY, PROPOSAL, VOTES,
+};
+use super::stats::record_stats;
+use crate::error::WalletError;
+use crate::event::WalletEvent;
+use crate::state::{
+    AccountType, PriceCondition, Proposal, ProposalStatus, ProposalType, Template, VoteCount,
+    WalletAuth, WalletConfig, BASIS_POINTS, DISCRIMINATOR_LEN, MAX_PAYMENTS, ROLE_ALL,
+    ROLE_OPTIMISTIC, ROLE_PROPOSE, ROLE_VOTE,
+};
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed, set_return_data},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::{rent::Rent, Sysvar},
+};
+use spl_token::{instruction::MAX_SIGNERS, native_mint::ID as NATIVE_MINT};
+use spl_token_2022::extension::transfer_fee::MAX_FEE_BASIS_POINTS;
+
+pub(super) fn check_lifetime_bounds(min: i64, max: i64) -> ProgramResult {
+    if min <= 0 || min > max {
+        return Err(WalletError::InvalidLifetimeBounds.into());
+    }
+    Ok(())
+}
+
+pub(super) fn check_lifetime(duration: i64, min: i64, max: i64) -> ProgramResult {
+    if duration < min {
+        return Err(WalletError::TooShortLifetime.into());
+    }
+    if duration > max {
+        return Err(WalletError::TooLongLifetime.into());
+    }
+    Ok(())
+}
+
+// every mandatory approver's bit has to be set in the vote record, whatever the threshold
+pub(super) fn mandatory_approved(voting_details: &VoteCount) -> bool {
+    voting_details
+        .mandatory_approvers
+        .iter()
+        .zip(voting_details.vote_record.iter())
+        .all(|(required, votes)| required & votes == *required)
+}
+
+pub(super) fn quorum_reached(voting_details: &VoteCount) -> bool {
+    voting_details.votes >= voting_details.quorum
+}
+
+// when a proposal of an owner with roles executes without votes, 0 if it is voted on
+fn challenge_end(wallet_details: &WalletConfig, roles: u8, current_time: i64) -> i64 {
+    if roles & ROLE_OPTIMISTIC == ROLE_OPTIMISTIC && wallet_details.optimistic_delay != 0 {
+        current_time.saturating_add(wallet_details.optimistic_delay)
+    } else {
+        0
+    }
+}
+
+// a changed optimistic proposal gets a new challenge period under the wallet's current delay, so owners can object
+// to what it became
+fn restart_challenge(
+    proposal_details: &mut Proposal,
+    wallet_details: &WalletConfig,
+    current_time: i64,
+) {
+    if proposal_details.challenge_ends_at != 0 {
+        proposal_details.challenge_ends_at = if wallet_details.optimistic_delay == 0 {
+            0
+        } else {
+            current_time.saturating_add(wallet_details.optimistic_delay)
+        };
+    }
+}
+
+// who a proposal is recorded under, the holder signing for an NFT-bound seat since its mint can never sign to edit,
+// activate or close the proposal, or receive its refund
+fn seat_user(user_details: &WalletAuth, user: &Pubkey) -> Pubkey {
+    if user_details.nft_bound {
+        *user
+    } else {
+        user_details.owner
+    }
+}
+
+// the threshold and lifetime a new proposal is held to, urgent ones follow the wallet's emergency policy
+fn proposal_policy(wallet_details: &WalletConfig, urgent: bool) -> (u8, i64) {
+    if urgent {
+        (
+            wallet_details.threshold(wallet_details.emergency_m, wallet_details.n),
+            wallet_details.emergency_lifetime,
+        )
+    } else {
+        (
+            wallet_details.threshold(wallet_details.m, wallet_details.n),
+            wallet_details.proposal_lifetime,
+        )
+    }
+}
+
+// a transfer out of a vault is held to the vault's threshold as it is when proposed, like the wallet's own
+fn proposal_threshold(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    wallet_details: &WalletConfig,
+    proposal: &ProposalType,
+    policy_threshold: u8,
+    vault: Option<&AccountInfo>,
+) -> Result<u8, ProgramError> {
+    match vault {
+        Some(vault) => {
+            let vault_details = load_vault(program_id, wallet_config, proposal.vault(), vault)?;
+            Ok(wallet_details.threshold(vault_details.m, vault_details.n))
+        }
+        None => Ok(policy_threshold),
+    }
+}
+
+fn validate_proposal(proposal: &ProposalType, wallet_details: &WalletConfig) -> ProgramResult {
+    match proposal {
+        ProposalType::ChangeProposalLifetime { duration } => check_lifetime(
+            *duration,
+            wallet_details.min_proposal_lifetime,
+            wallet_details.max_proposal_lifetime,
+        ),
+        ProposalType::SetLifetimeBounds { min, max } => check_lifetime_bounds(*min, *max),
+        ProposalType::ChangeThreshold { m, n } if *m == 0 || m > n => {
+            Err(WalletError::InvalidWalletParameters.into())
+        }
+        ProposalType::ChangeTimelock { delay } if *delay < 0 => {
+            Err(WalletError::InvalidTimelock.into())
+        }
+        ProposalType::ChangeRotationDelay { delay } if *delay < 0 => {
+            Err(WalletError::InvalidKeyRotation.into())
+        }
+        ProposalType::SetEmergencyPolicy { m, .. } if *m == 0 || *m > wallet_details.n => {
+            Err(WalletError::InvalidWalletParameters.into())
+        }
+        // the emergency window may be shorter than the regular minimum, that is what it is for
+        ProposalType::SetEmergencyPolicy { lifetime, .. } => {
+            check_lifetime(*lifetime, 1, wallet_details.max_proposal_lifetime)
+        }
+        ProposalType::Transfer {
+            token_mint, unwrap, ..
+        } if *unwrap && *token_mint != NATIVE_MINT => Err(WalletError::InvalidUnwrap.into()),
+        ProposalType::TransferPercent { basis_points, .. }
+            if *basis_points == 0 || *basis_points > BASIS_POINTS =>
+        {
+            Err(WalletError::InvalidBasisPoints.into())
+        }
+        ProposalType::SetQuorum { basis_points } if *basis_points > BASIS_POINTS => {
+            Err(WalletError::InvalidBasisPoints.into())
+        }
+        ProposalType::UpdateMetadata {
+            seller_fee_basis_points,
+            ..
+        } if *seller_fee_basis_points > BASIS_POINTS => Err(WalletError::InvalidBasisPoints.into()),
+        // the lengths the metadata program allows
+        ProposalType::UpdateMetadata {
+            name, symbol, uri, ..
+        } if name.len() > MAX_NAME_LENGTH
+            || symbol.len() > MAX_SYMBOL_LENGTH
+            || uri.len() > MAX_URI_LENGTH =>
+        {
+            Err(WalletError::InvalidMetadata.into())
+        }
+        ProposalType::SetTransferFee { basis_points, .. }
+            if *basis_points > MAX_FEE_BASIS_POINTS =>
+        {
+            Err(WalletError::InvalidBasisPoints.into())
+        }
+        ProposalType::SplitPayment { payments, .. }
+            if payments.is_empty() || payments.len() > MAX_PAYMENTS =>
+        {
+            Err(WalletError::InvalidSplitPayment.into())
+        }
+        ProposalType::SplitPayment {
+            by_share: true,
+            payments,
+            ..
+        } if payments.iter().map(|payment| payment.amount).sum::<u64>() > BASIS_POINTS as u64 => {
+            Err(WalletError::InvalidBasisPoints.into())
+        }
+        ProposalType::CreateTokenMultisig { m, signers }
+            if *m == 0 || *m as usize > signers.len() || signers.len() > MAX_SIGNERS =>
+        {
+            Err(WalletError::InvalidTokenMultisig.into())
+        }
+        ProposalType::ChangeOwnerKey {
+            old_owner,
+            new_owner,
+        } if old_owner == new_owner => Err(WalletError::InvalidWalletParameters.into()),
+        // the default pubkey marks unused slots
+        ProposalType::BlacklistAddress { address } if *address == Pubkey::default() => {
+            Err(WalletError::InvalidWalletParameters.into())
+        }
+        ProposalType::MergeStake {
+            destination,
+            source,
+        } if destination == source => Err(WalletError::InvalidStakeAccount.into()),
+        // a proposal has to be able to outlive its challenge period, urgent ones included
+        ProposalType::SetOptimisticDelay { delay }
+            if *delay < 0
+                || *delay >= wallet_details.proposal_lifetime
+                || *delay >= wallet_details.emergency_lifetime =>
+        {
+            Err(WalletError::InvalidWalletParameters.into())
+        }
+        ProposalType::SetRoles { roles, .. } if roles & !(ROLE_ALL | ROLE_OPTIMISTIC) != 0 => {
+            Err(WalletError::InvalidWalletParameters.into())
+        }
+        // with no room for proposals the cap could never be raised again
+        ProposalType::SetMaxOpenProposals { max } if *max == 0 => {
+            Err(WalletError::InvalidWalletParameters.into())
+        }
+        ProposalType::SetRecovery {
+            inactivity_period, ..
+        } if *inactivity_period < 0 => Err(WalletError::InvalidRecoveryPeriod.into()),
+        ProposalType::CreateTemplate { template, .. } => match **template {
+            ProposalType::CreateTemplate { .. } => Err(WalletError::NestedTemplate.into()),
+            _ => validate_proposal(template, wallet_details),
+        },
+        ProposalType::CreateVault { index, m, n } if *index == 0 || *m == 0 || m > n => {
+            Err(WalletError::InvalidWalletParameters.into())
+        }
+        ProposalType::CreateStream { rate, duration, .. } if *rate == 0 || *duration <= 0 => {
+            Err(WalletError::InvalidStreamParameters.into())
+        }
+        ProposalType::CreateVesting {
+            amount,
+            start_time,
+            cliff_time,
+            end_time,
+            ..
+        } if *amount == 0
+            || start_time >= end_time
+            || cliff_time < start_time
+            || cliff_time > end_time =>
+        {
+            Err(WalletError::InvalidVestingSchedule.into())
+        }
+        ProposalType::RemoveInactiveOwners {
+            inactive_for,
+            owners,
+        } if *inactive_for <= 0
+            || owners.is_empty()
+            || owners.len() >= wallet_details.owners as usize =>
+        {
+            Err(WalletError::InvalidOwnerRemoval.into())
+        }
+        ProposalType::ClaimableTransfer { claim_period, .. } if *claim_period <= 0 => {
+            Err(WalletError::InvalidClaimPeriod.into())
+        }
+        ProposalType::ExtendLookupTable { addresses, .. } if addresses.is_empty() => {
+            Err(WalletError::InvalidLookupTable.into())
+        }
+        ProposalType::CreateRecurringSwap {
+            input_mint,
+            output_mint,
+            amount,
+            interval,
+            swaps,
+            ..
+        } if *amount == 0 || *interval <= 0 || *swaps == 0 || input_mint == output_mint => {
+            Err(WalletError::InvalidSwapParameters.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn create_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_proposal: ProposalType,
+    urgent: bool,
+    treasury_funded: bool,
+    draft: bool,
+) -> ProgramResult {
+    let CreateProposalAccounts {
+        user,
+        payer,
+        wallet_config,
+        wallet_auth,
+        proposal,
+        vote_count,
+        wallet_stats,
+        vault,
+        mut wallet_details,
+        mut user_details,
+        sequence,
+        proposal_bump,
+        vote_count_bump: bump,
+    } = CreateProposalAccounts::load_and_validate(
+        program_id,
+        accounts,
+        &new_proposal,
+        treasury_funded,
+    )?;
+    let authority_bump = wallet_details.authority_bump;
+    if wallet_details.open_proposals >= wallet_details.max_open_proposals {
+        return Err(WalletError::TooManyOpenProposals.into());
+    }
+    validate_proposal(&new_proposal, &wallet_details)?;
+
+    // the proposer's vote is only counted if they can vote, for a draft once it is activated, with gated votes
+    // the proposer votes like everyone else
+    let mut vote_record = [0u8; 32];
+    let mut votes = 0;
+    if !draft
+        && user_details.roles & ROLE_VOTE == ROLE_VOTE
+        && wallet_details.gate_mint == Pubkey::default()
+    {
+        set_bit(&mut vote_record, user_details.id);
+        votes = 1;
+    }
+    // create proposal account, sized to its content since a template proposal nests another one
+    let proposal_details = Proposal {
+        discriminator: AccountType::Proposal,
+        wallet: *wallet_config.key,
+        proposer: seat_user(&user_details, user.key),
+        sequence,
+        bump: proposal_bump,
+        executed: false,
+        proposal: new_proposal,
+        urgent,
+        deposit: wallet_details.proposal_deposit,
+        initial_votes: votes,
+        keeper_tip: wallet_details.keeper_tip,
+        treasury_funded,
+        draft,
+        revision: 0,
+        content_hash: [0; 32],
+        price_condition: PriceCondition::default(),
+        // a draft's challenge period starts when it is activated
+        challenge_ends_at: if draft {
+            0
+        } else {
+            challenge_end(
+                &wallet_details,
+                user_details.roles,
+                Clock::get()?.unix_timestamp,
+            )
+        },
+        is_initialized: true,
+    };
+    let proposal_len = DISCRIMINATOR_LEN + proposal_details.try_to_vec()?.len();
+    let mut account_size: u64 = proposal_len.try_into().unwrap();
+    let rent = Rent::get()?;
+    let mut rent_amount = rent.minimum_balance(proposal_len);
+    // the deposit and keeper tip are set by the wallet, a large pair fails here rather than wrapping
+    let proposal_lamports = rent_amount
+        .checked_add(proposal_details.deposit)
+        .and_then(|lamports| lamports.checked_add(proposal_details.keeper_tip))
+        .ok_or(WalletError::Overflow)?;
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            proposal.key,
+            proposal_lamports,
+            account_size,
+            program_id,
+        ),
+        &[payer.clone(), proposal.clone()],
+        &[
+            &[
+                PROPOSAL.as_bytes(),
+                wallet_config.key.as_ref(),
+                &sequence.to_le_bytes(),
+                &[proposal_bump],
+            ],
+            &[
+                AUTHORITY.as_bytes(),
+                wallet_config.key.as_ref(),
+                &[authority_bump],
+            ],
+        ],
+    )?;
+    check_rent_exempt(proposal, &rent)?;
+    // initialize proposal account
+    store(&proposal_details, proposal)?;
+    // create vote count account
+    account_size = VoteCount::LEN.try_into().unwrap();
+    rent_amount = rent.minimum_balance(VoteCount::LEN);
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            vote_count.key,
+            rent_amount,
+            account_size,
+            program_id,
+        ),
+        &[payer.clone(), vote_count.clone()],
+        &[
+            &[
+                VOTES.as_bytes(),
+                wallet_config.key.as_ref(),
+                proposal.key.as_ref(),
+                &[bump],
+            ],
+            &[
+                AUTHORITY.as_bytes(),
+                wallet_config.key.as_ref(),
+                &[authority_bump],
+            ],
+        ],
+    )?;
+    check_rent_exempt(vote_count, &rent)?;
+    if treasury_funded && payer.lamports() < wallet_details.rent_floor {
+        return Err(WalletError::BelowRentFloor.into());
+    }
+    // initialize vote count account, the sum saturates so offline signers can use lifetimes up to i64::MAX
+    let current_time = Clock::get()?.unix_timestamp;
+    let (threshold, lifetime) = proposal_policy(&wallet_details, urgent);
+    let threshold = proposal_threshold(
+        program_id,
+        wallet_config.key,
+        &wallet_details,
+        &proposal_details.proposal,
+        threshold,
+        vault,
+    )?;
+    let voting_details = VoteCount {
+        discriminator: AccountType::VoteCount,
+        proposed_time: current_time,
+        expires_at: current_time.saturating_add(lifetime),
+        threshold,
+        mandatory_approvers: wallet_details.mandatory_approvers,
+        quorum: wallet_details.quorum_votes(),
+        votes,
+        vote_record,
+        epoch: wallet_details.epoch,
+        bump,
+        extended: false,
+        is_initialized: true,
+    };
+    store(&voting_details, vote_count)?;
+    // record activity of the user and the wallet
+    user_details.last_active_at = current_time;
+    user_details.proposals_created += 1;
+    if votes == 1 {
+        user_details.last_vote_at = current_time;
+        user_details.votes_cast += 1;
+    }
+    store(&user_details, wallet_auth)?;
+    wallet_details.last_activity = current_time;
+    wallet_details.proposal_count += 1;
+    wallet_details.open_proposals += 1;
+    WalletEvent::ProposalCreated {
+        wallet: *wallet_config.key,
+        proposal: *proposal.key,
+        proposer: seat_user(&user_details, user.key),
+        sequence,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)?;
+    record_stats(
+        program_id,
+        wallet_config.key,
+        wallet_stats,
+        |stats_details| stats_details.proposals_created += 1,
+    )?;
+
+    Ok(())
+}
+
+pub fn create_proposal_from_template(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let (template, accounts) = accounts
+        .split_first()
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    // the wallet config is the third of the CreateProposal accounts
+    let wallet_config = accounts.get(2).ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let template_details = load::<Template>(template, program_id)?;
+    let template_key = template_address(
+        program_id,
+        wallet_config.key,
+        template_details.id,
+        template_details.bump,
+    )?;
+    if *template.key != template_key {
+        return Err(WalletError::InvalidTemplate.into());
+    }
+    create_proposal(
+        program_id,
+        accounts,
+        template_details.proposal,
+        false,
+        false,
+        false,
+    )
+}
+
+// loads a proposal of the wallet made by proposer
+fn load_own_proposal(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    proposer: &AccountInfo,
+    proposal: &AccountInfo,
+) -> Result<Proposal, ProgramError> {
+    if !proposer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let proposal_details = load::<Proposal>(proposal, program_id)?;
+    if proposal_details.wallet != *wallet_config {
+        return Err(WalletError::ProposalWalletMismatch.into());
+    }
+    let proposal_key = proposal_address(
+        program_id,
+        wallet_config,
+        proposal_details.sequence,
+        proposal_details.bump,
+    )?;
+    if *proposal.key != proposal_key {
+        return Err(WalletError::InvalidProposal.into());
+    }
+    if proposal_details.proposer != *proposer.key {
+        return Err(WalletError::NotProposer.into());
+    }
+    Ok(proposal_details)
+}
+
+// swaps in new_proposal as the next revision, the account is resized to the new content and only growing it
+// costs the payer
+fn replace_proposal<'a>(
+    proposal: &AccountInfo<'a>,
+    proposal_details: &mut Proposal,
+    new_proposal: ProposalType,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        return Err(WalletError::IncorrectSystemProgram.into());
+    }
+    proposal_details.proposal = new_proposal;
+    proposal_details.revision = proposal_details
+        .revision
+        .checked_add(1)
+        .ok_or(ProgramError::InvalidArgument)?;
+    let proposal_len = DISCRIMINATOR_LEN + proposal_details.try_to_vec()?.len();
+    let required = Rent::get()?
+        .minimum_balance(proposal_len)
+        .checked_add(proposal_details.deposit)
+        .and_then(|lamports| lamports.checked_add(proposal_details.keeper_tip))
+        .ok_or(WalletError::Overflow)?;
+    if required > proposal.lamports() {
+        if !payer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        invoke(
+            &system_instruction::transfer(payer.key, proposal.key, required - proposal.lamports()),
+            &[payer.clone(), proposal.clone(), system_program.clone()],
+        )?;
+    }
+    proposal.realloc(proposal_len, false)?;
+    store(&*proposal_details, proposal)
+}
+
+pub fn edit_draft(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_proposal: ProposalType,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let proposer = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let proposal = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    let mut proposal_details =
+        load_own_proposal(program_id, wallet_config.key, proposer, proposal)?;
+    if !proposal_details.draft {
+        return Err(WalletError::ProposalNotDraft.into());
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    validate_proposal(&new_proposal, &wallet_details)?;
+
+    replace_proposal(
+        proposal,
+        &mut proposal_details,
+        new_proposal,
+        payer,
+        system_program,
+    )?;
+    WalletEvent::DraftEdited {
+        wallet: *wallet_config.key,
+        proposal: *proposal.key,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)
+}
+
+pub fn activate_proposal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let proposer = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let proposal = next_account_info(accounts_iter)?;
+    let vote_count = next_account_info(accounts_iter)?;
+
+    let mut proposal_details =
+        load_own_proposal(program_id, wallet_config.key, proposer, proposal)?;
+    if !proposal_details.draft {
+        return Err(WalletError::ProposalNotDraft.into());
+    }
+    let mut voting_details = load::<VoteCount>(vote_count, program_id)?;
+    let vote_count_key = vote_count_address(
+        program_id,
+        wallet_config.key,
+        proposal.key,
+        voting_details.bump,
+    )?;
+    if *vote_count.key != vote_count_key {
+        return Err(WalletError::InvalidVoteCount.into());
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let vault = next_vault_account(&proposal_details.proposal, accounts_iter)?;
+    let mut user_details = load_signer(
+        program_id,
+        wallet_config.key,
+        proposer.key,
+        wallet_auth,
+        next_account_info(accounts_iter).ok(),
+        ROLE_PROPOSE,
+    )?;
+    // an expired draft is left for anyone to close
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time > voting_details.expires_at {
+        return Err(WalletError::ProposalExpired.into());
+    }
+    // the wallet may have changed since the draft was created
+    validate_proposal(&proposal_details.proposal, &wallet_details)?;
+
+    let (threshold, lifetime) = proposal_policy(&wallet_details, proposal_details.urgent);
+    voting_details.proposed_time = current_time;
+    voting_details.expires_at = current_time.saturating_add(lifetime);
+    voting_details.threshold = proposal_threshold(
+        program_id,
+        wallet_config.key,
+        &wallet_details,
+        &proposal_details.proposal,
+        threshold,
+        vault,
+    )?;
+    voting_details.mandatory_approvers = wallet_details.mandatory_approvers;
+    voting_details.quorum = wallet_details.quorum_votes();
+    voting_details.epoch = wallet_details.epoch;
+    if user_details.roles & ROLE_VOTE == ROLE_VOTE && wallet_details.gate_mint == Pubkey::default()
+    {
+        set_bit(&mut voting_details.vote_record, user_details.id);
+        voting_details.votes = 1;
+    }
+    store(&voting_details, vote_count)?;
+    proposal_details.draft = false;
+    proposal_details.initial_votes = voting_details.votes;
+    proposal_details.challenge_ends_at =
+        challenge_end(&wallet_details, user_details.roles, current_time);
+    store(&proposal_details, proposal)?;
+
+    user_details.last_active_at = current_time;
+    if voting_details.votes == 1 {
+        user_details.last_vote_at = current_time;
+        user_details.votes_cast += 1;
+    }
+    store(&user_details, wallet_auth)?;
+    wallet_details.last_activity = current_time;
+    WalletEvent::ProposalActivated {
+        wallet: *wallet_config.key,
+        proposal: *proposal.key,
+        sequence: proposal_details.sequence,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)
+}
+
+pub fn amend_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_proposal: ProposalType,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let proposer = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let proposal = next_account_info(accounts_iter)?;
+    let vote_count = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let vault = next_vault_account(&new_proposal, accounts_iter)?;
+
+    let mut proposal_details =
+        load_own_proposal(program_id, wallet_config.key, proposer, proposal)?;
+    if proposal_details.draft {
+        return Err(WalletError::ProposalIsDraft.into());
+    }
+    let mut voting_details = load::<VoteCount>(vote_count, program_id)?;
+    let vote_count_key = vote_count_address(
+        program_id,
+        wallet_config.key,
+        proposal.key,
+        voting_details.bump,
+    )?;
+    if *vote_count.key != vote_count_key {
+        return Err(WalletError::InvalidVoteCount.into());
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time > voting_details.expires_at {
+        return Err(WalletError::ProposalExpired.into());
+    }
+    if voting_details.epoch != wallet_details.epoch {
+        return Err(WalletError::EpochMismatch.into());
+    }
+    if voting_details.votes > proposal_details.initial_votes {
+        return Err(WalletError::ProposalAlreadyVoted.into());
+    }
+    validate_proposal(&new_proposal, &wallet_details)?;
+    // moving the transfer into or out of a vault moves it to that threshold, as it is now
+    if new_proposal.vault() != proposal_details.proposal.vault() {
+        let (threshold, _) = proposal_policy(&wallet_details, proposal_details.urgent);
+        voting_details.threshold = proposal_threshold(
+            program_id,
+            wallet_config.key,
+            &wallet_details,
+            &new_proposal,
+            threshold,
+            vault,
+        )?;
+    }
+
+    replace_proposal(
+        proposal,
+        &mut proposal_details,
+        new_proposal,
+        payer,
+        system_program,
+    )?;
+    let lifetime = voting_details.expires_at - voting_details.proposed_time;
+    voting_details.proposed_time = current_time;
+    voting_details.expires_at = current_time.saturating_add(lifetime);
+    store(&voting_details, vote_count)?;
+    restart_challenge(&mut proposal_details, &wallet_details, current_time);
+    store(&proposal_details, proposal)?;
+    wallet_details.last_activity = current_time;
+    WalletEvent::ProposalAmended {
+        wallet: *wallet_config.key,
+        proposal: *proposal.key,
+        revision: proposal_details.revision,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)
+}
+
+pub fn set_content_hash(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    content_hash: [u8; 32],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let proposer = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let proposal = next_account_info(accounts_iter)?;
+    let vote_count = next_account_info(accounts_iter)?;
+
+    let mut proposal_details =
+        load_own_proposal(program_id, wallet_config.key, proposer, proposal)?;
+    let voting_details = load::<VoteCount>(vote_count, program_id)?;
+    let vote_count_key = vote_count_address(
+        program_id,
+        wallet_config.key,
+        proposal.key,
+        voting_details.bump,
+    )?;
+    if *vote_count.key != vote_count_key {
+        return Err(WalletError::InvalidVoteCount.into());
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    // an active proposal follows the rules of AmendProposal
+    if !proposal_details.draft {
+        if Clock::get()?.unix_timestamp > voting_details.expires_at {
+            return Err(WalletError::ProposalExpired.into());
+        }
+        if voting_details.epoch != wallet_details.epoch {
+            return Err(WalletError::EpochMismatch.into());
+        }
+        if voting_details.votes > proposal_details.initial_votes {
+            return Err(WalletError::ProposalAlreadyVoted.into());
+        }
+    }
+
+    proposal_details.content_hash = content_hash;
+    proposal_details.revision = proposal_details
+        .revision
+        .checked_add(1)
+        .ok_or(ProgramError::InvalidArgument)?;
+    restart_challenge(
+        &mut proposal_details,
+        &wallet_details,
+        Clock::get()?.unix_timestamp,
+    );
+    store(&proposal_details, proposal)?;
+    WalletEvent::ContentHashSet {
+        wallet: *wallet_config.key,
+        proposal: *proposal.key,
+        content_hash,
+        revision: proposal_details.revision,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)
+}
+
+pub fn set_price_condition(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    condition: PriceCondition,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let proposer = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let proposal = next_account_info(accounts_iter)?;
+    let vote_count = next_account_info(accounts_iter)?;
+
+    let mut proposal_details =
+        load_own_proposal(program_id, wallet_config.key, proposer, proposal)?;
+    let voting_details = load::<VoteCount>(vote_count, program_id)?;
+    let vote_count_key = vote_count_address(
+        program_id,
+        wallet_config.key,
+        proposal.key,
+        voting_details.bump,
+    )?;
+    if *vote_count.key != vote_count_key {
+        return Err(WalletError::InvalidVoteCount.into());
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    // an active proposal follows the rules of AmendProposal
+    if !proposal_details.draft {
+        if Clock::get()?.unix_timestamp > voting_details.expires_at {
+            return Err(WalletError::ProposalExpired.into());
+        }
+        if voting_details.epoch != wallet_details.epoch {
+            return Err(WalletError::EpochMismatch.into());
+        }
+        if voting_details.votes > proposal_details.initial_votes {
+            return Err(WalletError::ProposalAlreadyVoted.into());
+        }
+    }
+
+    // a removed condition is stored as the default so the vote digest does not change with the other fields
+    proposal_details.price_condition = if condition.feed == Pubkey::default() {
+        PriceCondition::default()
+    } else {
+        condition
+    };
+    proposal_details.revision = proposal_details
+        .revision
+        .checked_add(1)
+        .ok_or(ProgramError::InvalidArgument)?;
+    restart_challenge(
+        &mut proposal_details,
+        &wallet_details,
+        Clock::get()?.unix_timestamp,
+    );
+    store(&proposal_details, proposal)?;
+    WalletEvent::PriceConditionSet {
+        wallet: *wallet_config.key,
+        proposal: *proposal.key,
+        condition: proposal_details.price_condition,
+        revision: proposal_details.revision,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)
+}
+
+pub fn object_proposal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let proposal = next_account_info(accounts_iter)?;
+    let vote_count = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    load_owner(program_id, wallet_config.key, owner.key, wallet_auth)?;
+    let ProposalAccounts {
+        mut proposal_details,
+        voting_details,
+        ..
+    } = ProposalAccounts::load_and_validate(program_id, wallet_config.key, proposal, vote_count)?;
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time > voting_details.expires_at {
+        return Err(WalletError::ProposalExpired.into());
+    }
+    if voting_details.epoch != wallet_details.epoch {
+        return Err(WalletError::EpochMismatch.into());
+    }
+    // once the window has closed the proposal may already have executed unchallenged
+    if proposal_details.challenge_ends_at == 0 || current_time >= proposal_details.challenge_ends_at
+    {
+        return Err(WalletError::NotOptimistic.into());
+    }
+
+    proposal_details.challenge_ends_at = 0;
+    store(&proposal_details, proposal)?;
+    WalletEvent::ProposalObjected {
+        wallet: *wallet_config.key,
+        proposal: *proposal.key,
+        owner: *owner.key,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)
+}
+
+pub fn get_proposal_status(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ProposalStatusAccounts {
+        wallet_details,
+        proposal:
+            ProposalAccounts {
+                proposal_details,
+                voting_details,
+                ..
+            },
+    } = ProposalStatusAccounts::load_and_validate(program_id, accounts)?;
+    // same rules close_proposal applies, a transfer out of a vault is held to its snapshotted threshold even when
+    // unchallenged
+    let threshold = voting_details.threshold;
+    let expires_at = voting_details.expires_at;
+    let current_time = Clock::get()?.unix_timestamp;
+    let expired = current_time > expires_at;
+    let stale = voting_details.epoch != wallet_details.epoch;
+    let mandatory_approved = mandatory_approved(&voting_details);
+    let quorum_reached = quorum_reached(&voting_details);
+    let unchallenged = proposal_details.challenge_ends_at != 0
+        && current_time >= proposal_details.challenge_ends_at;
+    let votes_reached = voting_details.votes >= threshold;
+    let approved = if proposal_details.proposal.vault() != 0 {
+        votes_reached && (unchallenged || mandatory_approved && quorum_reached)
+    } else {
+        unchallenged || votes_reached && mandatory_approved && quorum_reached
+    };
+    let votes_needed = threshold.saturating_sub(voting_details.votes);
+    let time_remaining = expires_at.saturating_sub(current_time).max(0);
+    let conditional = proposal_details.price_condition.feed != Pubkey::default();
+    // only an Unfreeze proposal executes while the wallet is frozen
+    let frozen =
+        wallet_details.frozen && !matches!(proposal_details.proposal, ProposalType::Unfreeze);
+    let proposal_status = ProposalStatus {
+        sequence: proposal_details.sequence,
+        proposer: proposal_details.proposer,
+        proposal_type: proposal_details.proposal.kind(),
+        proposed_time: voting_details.proposed_time,
+        expires_at,
+        votes: voting_details.votes,
+        threshold,
+        vote_record: voting_details.vote_record,
+        expired,
+        stale,
+        executable: !expired
+            && !stale
+            && !proposal_details.draft
+            && !frozen
+            && !conditional
+            && approved,
+        urgent: proposal_details.urgent,
+        mandatory_approved,
+        approved,
+        votes_needed,
+        time_remaining,
+        draft: proposal_details.draft,
+        quorum: voting_details.quorum,
+        quorum_reached,
+        challenge_ends_at: proposal_details.challenge_ends_at,
+        conditional,
+    };
+    msg!(
+        "proposal {}: {}, {} of {} votes, {} more needed, {}s remaining",
+        proposal_details.sequence,
+        if stale {
+            "stale"
+        } else if proposal_details.draft {
+            "draft"
+        } else if expired {
+            "expired"
+        } else if approved {
+            "approved"
+        } else {
+            "active"
+        },
+        voting_details.votes,
+        threshold,
+        votes_needed,
+        time_remaining
+    );
+    set_return_data(&proposal_status.try_to_vec()?);
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+// the spl stake pool instructions the wallet authority signs, only the SOL deposit and withdrawal are used so they
+// are encoded here rather than pulling in the whole stake pool crate
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+    stake, system_program, sysvar,
+};
+
+pub const STAKE_POOL_PROGRAM_ID: Pubkey = pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+
+// positions of the instructions in the stake pool's instruction enum
+const DEPOSIT_SOL: u8 = 14;
+const WITHDRAW_SOL: u8 = 16;
+
+// the accounts of a pool's state that deposits and withdrawals need, the rest of the state is skipped
+pub struct StakePool {
+    pub reserve_stake: Pubkey,
+    pub pool_mint: Pubkey,
+    pub manager_fee_account: Pubkey,
+    pub token_program_id: Pubkey,
+}
+
+impl StakePool {
+    // account type, manager, staker, deposit authority, withdraw bump seed and validator list precede the reserve
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let key_at = |offset: usize| {
+            data.get(offset..offset + 32)
+                .and_then(|key| key.try_into().ok())
+                .map(Pubkey::new_from_array)
+                .ok_or(ProgramError::InvalidAccountData)
+        };
+        Ok(StakePool {
+            reserve_stake: key_at(130)?,
+            pool_mint: key_at(162)?,
+            manager_fee_account: key_at(194)?,
+            token_program_id: key_at(226)?,
+        })
+    }
+}
+
+pub fn find_withdraw_authority_address(stake_pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[stake_pool.as_ref(), b"withdraw"],
+        &STAKE_POOL_PROGRAM_ID,
+    )
+}
+
+fn amount_data(instruction: u8, amount: u64) -> Vec<u8> {
+    let mut data = vec![instruction];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn deposit_sol(
+    stake_pool: &Pubkey,
+    withdraw_authority: &Pubkey,
+    reserve_stake: &Pubkey,
+    lamports_from: &Pubkey,
+    pool_tokens_to: &Pubkey,
+    manager_fee_account: &Pubkey,
+    referrer_pool_tokens: &Pubkey,
+    pool_mint: &Pubkey,
+    token_program: &Pubkey,
+    lamports: u64,
+) -> Instruction {
+    Instruction {
+        program_id: STAKE_POOL_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*stake_pool, false),
+            AccountMeta::new_readonly(*withdraw_authority, false),
+            AccountMeta::new(*reserve_stake, false),
+            AccountMeta::new(*lamports_from, true),
+            AccountMeta::new(*pool_tokens_to, false),
+            AccountMeta::new(*manager_fee_account, false),
+            AccountMeta::new(*referrer_pool_tokens, false),
+            AccountMeta::new(*pool_mint, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: amount_data(DEPOSIT_SOL, lamports),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn withdraw_sol(
+    stake_pool: &Pubkey,
+    withdraw_authority: &Pubkey,
+    user_transfer_authority: &Pubkey,
+    pool_tokens_from: &Pubkey,
+    reserve_stake: &Pubkey,
+    lamports_to: &Pubkey,
+    manager_fee_account: &Pubkey,
+    pool_mint: &Pubkey,
+    token_program: &Pubkey,
+    pool_tokens: u64,
+) -> Instruction {
+    Instruction {
+        program_id: STAKE_POOL_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*stake_pool, false),
+            AccountMeta::new_readonly(*withdraw_authority, false),
+            AccountMeta::new_readonly(*user_transfer_authority, true),
+            AccountMeta::new(*pool_tokens_from, false),
+            AccountMeta::new(*reserve_stake, false),
+            AccountMeta::new(*lamports_to, false),
+            AccountMeta::new(*manager_fee_account, false),
+            AccountMeta::new(*pool_mint, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(sysvar::stake_history::ID, false),
+            AccountMeta::new_readonly(stake::program::ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: amount_data(WITHDRAW_SOL, pool_tokens),
+    }
+}
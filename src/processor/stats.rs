@@ -0,0 +1,84 @@
+use super::accounts::{check_rent_exempt, load, store, CreateWalletStatsAccounts};
+use super::pda::WALLET_STATS;
+use crate::error::WalletError;
+use crate::state::{AccountType, MintTotal, WalletStats, MAX_TRACKED_MINTS};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+// applies update to the wallet's stats, a no-op until CreateWalletStats creates them
+pub(super) fn record_stats(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    wallet_stats: &AccountInfo,
+    update: impl FnOnce(&mut WalletStats),
+) -> ProgramResult {
+    if wallet_stats.owner != program_id {
+        let (wallet_stats_key, _) = Pubkey::find_program_address(
+            &[WALLET_STATS.as_bytes(), wallet_config.as_ref()],
+            program_id,
+        );
+        if *wallet_stats.key != wallet_stats_key {
+            return Err(WalletError::InvalidWalletStats.into());
+        }
+        return Ok(());
+    }
+    let mut stats_details = load::<WalletStats>(wallet_stats, program_id)?;
+    let wallet_stats_key = Pubkey::create_program_address(
+        &[
+            WALLET_STATS.as_bytes(),
+            wallet_config.as_ref(),
+            &[stats_details.bump],
+        ],
+        program_id,
+    )?;
+    if stats_details.wallet != *wallet_config || *wallet_stats.key != wallet_stats_key {
+        return Err(WalletError::InvalidWalletStats.into());
+    }
+    update(&mut stats_details);
+    store(&stats_details, wallet_stats)
+}
+
+// anyone can pay for the stats, they count from their creation on
+pub fn create_wallet_stats(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let CreateWalletStatsAccounts {
+        payer,
+        wallet_config,
+        wallet_stats,
+        bump,
+    } = CreateWalletStatsAccounts::load_and_validate(program_id, accounts)?;
+
+    let account_size: u64 = WalletStats::LEN.try_into().unwrap();
+    let rent = Rent::get()?;
+    let rent_amount = rent.minimum_balance(WalletStats::LEN);
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            wallet_stats.key,
+            rent_amount,
+            account_size,
+            program_id,
+        ),
+        &[payer.clone(), wallet_stats.clone()],
+        &[&[WALLET_STATS.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+    )?;
+    check_rent_exempt(wallet_stats, &rent)?;
+    let stats_details = WalletStats {
+        discriminator: AccountType::WalletStats,
+        wallet: *wallet_config.key,
+        proposals_created: 0,
+        proposals_executed: 0,
+        proposals_expired: 0,
+        last_execution_slot: 0,
+        mint_totals: [MintTotal::default(); MAX_TRACKED_MINTS],
+        bump,
+        is_initialized: true,
+    };
+    store(&stats_details, wallet_stats)
+}
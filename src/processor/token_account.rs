@@ -0,0 +1,342 @@
+use super::accounts::{load, load_mint_decimals, load_owner, load_vault};
+use super::pda::{authority_address, vault_authority_address, AUTHORITY, AUX_TOKEN};
+use crate::error::WalletError;
+use crate::state::WalletConfig;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::{rent::Rent, Sysvar},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+    ID as ASSOCIATED_TOKEN_PROGRAM_ID,
+};
+use spl_token::{
+    instruction as token_instruction,
+    state::{Account, Mint},
+    ID as TOKEN_PROGRAM_ID,
+};
+
+pub fn create_token_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    vault: u8,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let authority_bump = if vault == 0 {
+        wallet_details.authority_bump
+    } else {
+        let vault_account = next_account_info(accounts_iter)?;
+        load_vault(program_id, wallet_config.key, vault, vault_account)?.authority_bump
+    };
+    let wallet_authority_key =
+        vault_authority_address(program_id, wallet_config.key, vault, authority_bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    if *mint.owner != TOKEN_PROGRAM_ID {
+        return Err(WalletError::InvalidMint.into());
+    }
+    if Mint::unpack(&mint.data.borrow()).is_err() {
+        return Err(WalletError::InvalidMint.into());
+    }
+    let ata_key = get_associated_token_address(wallet_authority.key, mint.key);
+    if *token_account.key != ata_key {
+        return Err(WalletError::IncorrectAssociatedTokenAccount.into());
+    }
+    if *system_program.key != SYSTEM_PROGRAM_ID
+        || *token_program.key != TOKEN_PROGRAM_ID
+        || *associated_token_program.key != ASSOCIATED_TOKEN_PROGRAM_ID
+    {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    invoke(
+        &create_associated_token_account(
+            payer.key,
+            wallet_authority.key,
+            mint.key,
+            token_program.key,
+        ),
+        &[
+            payer.clone(),
+            token_account.clone(),
+            wallet_authority.clone(),
+            mint.clone(),
+            system_program.clone(),
+            token_program.clone(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub fn create_aux_token_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    vault: u8,
+    seed: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let authority_bump = if vault == 0 {
+        wallet_details.authority_bump
+    } else {
+        let vault_account = next_account_info(accounts_iter)?;
+        load_vault(program_id, wallet_config.key, vault, vault_account)?.authority_bump
+    };
+    let wallet_authority_key =
+        vault_authority_address(program_id, wallet_config.key, vault, authority_bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    if *mint.owner != TOKEN_PROGRAM_ID {
+        return Err(WalletError::InvalidMint.into());
+    }
+    if Mint::unpack(&mint.data.borrow()).is_err() {
+        return Err(WalletError::InvalidMint.into());
+    }
+    let (token_account_key, bump) = Pubkey::find_program_address(
+        &[
+            AUX_TOKEN.as_bytes(),
+            wallet_config.key.as_ref(),
+            wallet_authority.key.as_ref(),
+            mint.key.as_ref(),
+            &seed.to_le_bytes(),
+        ],
+        program_id,
+    );
+    if *token_account.key != token_account_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if *system_program.key != SYSTEM_PROGRAM_ID || *token_program.key != TOKEN_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let account_size: u64 = Account::LEN.try_into().unwrap();
+    let rent_amount = Rent::get()?.minimum_balance(Account::LEN);
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            token_account.key,
+            rent_amount,
+            account_size,
+            token_program.key,
+        ),
+        &[payer.clone(), token_account.clone()],
+        &[&[
+            AUX_TOKEN.as_bytes(),
+            wallet_config.key.as_ref(),
+            wallet_authority.key.as_ref(),
+            mint.key.as_ref(),
+            &seed.to_le_bytes(),
+            &[bump],
+        ]],
+    )?;
+    invoke(
+        &token_instruction::initialize_account3(
+            token_program.key,
+            token_account.key,
+            mint.key,
+            wallet_authority.key,
+        )?,
+        &[token_account.clone(), mint.clone()],
+    )?;
+
+    Ok(())
+}
+
+// tokens never leave the wallet here, so any owner can move them from stray accounts into the canonical
+// associated token accounts, emptied accounts are closed with their rent going to the wallet authority
+pub fn sweep_token_accounts(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    load_owner(program_id, wallet_config.key, owner.key, wallet_auth)?;
+    let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let bump = wallet_details.authority_bump;
+    let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    if *token_program.key != TOKEN_PROGRAM_ID {
+        return Err(WalletError::IncorrectTokenProgram.into());
+    }
+    let signer_seeds: &[&[u8]] = &[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]];
+    while let Ok(source_account) = next_account_info(accounts_iter) {
+        let destination_account = next_account_info(accounts_iter)?;
+        let mint = next_account_info(accounts_iter)?;
+
+        if *source_account.owner != TOKEN_PROGRAM_ID {
+            return Err(WalletError::IncorrectTokenProgram.into());
+        }
+        let source_account_details = Account::unpack(&source_account.data.borrow())?;
+        if source_account_details.owner != *wallet_authority.key {
+            return Err(WalletError::IncorrectSendAccount.into());
+        }
+        let associated_token_account =
+            get_associated_token_address(wallet_authority.key, &source_account_details.mint);
+        if *destination_account.key != associated_token_account
+            || *source_account.key == associated_token_account
+        {
+            return Err(WalletError::IncorrectReceiveAccount.into());
+        }
+        if source_account_details.amount > 0 {
+            let decimals = load_mint_decimals(mint, &source_account_details.mint)?;
+            invoke_signed(
+                &token_instruction::transfer_checked(
+                    token_program.key,
+                    source_account.key,
+                    mint.key,
+                    destination_account.key,
+                    wallet_authority.key,
+                    &[],
+                    source_account_details.amount,
+                    decimals,
+                )?,
+                &[
+                    source_account.clone(),
+                    mint.clone(),
+                    destination_account.clone(),
+                    wallet_authority.clone(),
+                ],
+                &[signer_seeds],
+            )?;
+        }
+        // an account someone else can close is only emptied
+        if source_account_details.close_authority.is_none()
+            || source_account_details
+                .close_authority
+                .contains(wallet_authority.key)
+        {
+            invoke_signed(
+                &token_instruction::close_account(
+                    token_program.key,
+                    source_account.key,
+                    wallet_authority.key,
+                    wallet_authority.key,
+                    &[],
+                )?,
+                &[source_account.clone(), wallet_authority.clone()],
+                &[signer_seeds],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+// only associated token accounts without a balance are closed, so no tokens can be lost and any owner may
+// do it, the rent of vault accounts also goes to the wallet authority
+pub fn close_empty_token_accounts(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    vault: u8,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    load_owner(program_id, wallet_config.key, owner.key, wallet_auth)?;
+    let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let wallet_authority_key =
+        authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    if *token_program.key != TOKEN_PROGRAM_ID {
+        return Err(WalletError::IncorrectTokenProgram.into());
+    }
+    let (vault_authority, bump) = if vault == 0 {
+        (wallet_authority, wallet_details.authority_bump)
+    } else {
+        let vault_account = next_account_info(accounts_iter)?;
+        let vault_authority = next_account_info(accounts_iter)?;
+        let bump = load_vault(program_id, wallet_config.key, vault, vault_account)?.authority_bump;
+        if *vault_authority.key
+            != vault_authority_address(program_id, wallet_config.key, vault, bump)?
+        {
+            return Err(WalletError::InvalidWalletAuthority.into());
+        }
+        (vault_authority, bump)
+    };
+    let vault_seed = [vault];
+    let bump_seed = [bump];
+    let mut signer_seeds: Vec<&[u8]> = vec![AUTHORITY.as_bytes(), wallet_config.key.as_ref()];
+    if vault != 0 {
+        signer_seeds.push(&vault_seed);
+    }
+    signer_seeds.push(&bump_seed);
+    for token_account in accounts_iter {
+        if *token_account.owner != TOKEN_PROGRAM_ID {
+            return Err(WalletError::IncorrectTokenProgram.into());
+        }
+        let token_account_details = Account::unpack(&token_account.data.borrow())?;
+        if *token_account.key
+            != get_associated_token_address(vault_authority.key, &token_account_details.mint)
+        {
+            return Err(WalletError::IncorrectAssociatedTokenAccount.into());
+        }
+        if token_account_details.amount > 0 {
+            return Err(WalletError::TokenAccountNotEmpty.into());
+        }
+        invoke_signed(
+            &token_instruction::close_account(
+                token_program.key,
+                token_account.key,
+                wallet_authority.key,
+                vault_authority.key,
+                &[],
+            )?,
+            &[
+                token_account.clone(),
+                wallet_authority.clone(),
+                vault_authority.clone(),
+            ],
+            &[&signer_seeds],
+        )?;
+    }
+
+    Ok(())
+}
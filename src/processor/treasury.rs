@@ -0,0 +1,640 @@
+use super::accounts::{
+    load, load_claim, load_escrow, load_mint_decimals, load_owner, load_recurring_swap,
+    load_registry, load_stream, load_token_account, load_vesting, store,
+};
+use super::cpi::{close_claim, close_escrow};
+use super::pda::{allowance_address, authority_address, treasury_address, AUTHORITY, VESTING};
+use crate::error::WalletError;
+use crate::event::WalletEvent;
+use crate::state::{Allowance, SolBalance, WalletConfig};
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::{invoke, invoke_signed, set_return_data},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{instruction as token_instruction, state::Account, ID as TOKEN_PROGRAM_ID};
+
+pub fn deposit_sol(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let depositor = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !depositor.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let wallet_authority_key =
+        authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        return Err(WalletError::IncorrectSystemProgram.into());
+    }
+    // a deposit into an empty authority has to cover its rent floor
+    if wallet_authority.lamports() + amount < wallet_details.rent_floor {
+        return Err(WalletError::BelowRentFloor.into());
+    }
+    invoke(
+        &system_instruction::transfer(depositor.key, wallet_authority.key, amount),
+        &[
+            depositor.clone(),
+            wallet_authority.clone(),
+            system_program.clone(),
+        ],
+    )?;
+    wallet_details.total_deposited = wallet_details
+        .total_deposited
+        .checked_add(amount)
+        .ok_or(WalletError::Overflow)?;
+    WalletEvent::SolDeposited {
+        wallet: *wallet_config.key,
+        depositor: *depositor.key,
+        amount,
+        total_deposited: wallet_details.total_deposited,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)?;
+
+    Ok(())
+}
+
+// the treasury holds no data, a deposit into an empty one has to make it rent exempt
+pub fn deposit_treasury(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let depositor = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !depositor.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let treasury_key =
+        treasury_address(program_id, wallet_config.key, wallet_details.treasury_bump)?;
+    if *treasury.key != treasury_key {
+        return Err(WalletError::InvalidTreasury.into());
+    }
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        return Err(WalletError::IncorrectSystemProgram.into());
+    }
+    if treasury.lamports() + amount < wallet_details.rent_floor {
+        return Err(WalletError::BelowRentFloor.into());
+    }
+    invoke(
+        &system_instruction::transfer(depositor.key, treasury.key, amount),
+        &[depositor.clone(), treasury.clone(), system_program.clone()],
+    )?;
+    WalletEvent::TreasuryDeposited {
+        wallet: *wallet_config.key,
+        depositor: *depositor.key,
+        amount,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)?;
+
+    Ok(())
+}
+
+pub fn spend_allowance(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let allowance = next_account_info(accounts_iter)?;
+    let source_account = next_account_info(accounts_iter)?;
+    let destination_account = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    if wallet_details.frozen {
+        return Err(WalletError::WalletFrozen.into());
+    }
+    // only a current owner can spend, an allowance of a removed owner is dead
+    let mut user_details = load_owner(program_id, wallet_config.key, owner.key, wallet_auth)?;
+    let mut allowance_details = load::<Allowance>(allowance, program_id)?;
+    let allowance_key = allowance_address(
+        program_id,
+        wallet_config.key,
+        owner.key,
+        &allowance_details.mint,
+        allowance_details.bump,
+    )?;
+    if *allowance.key != allowance_key {
+        return Err(WalletError::InvalidAllowance.into());
+    }
+    let spent = allowance_details
+        .spent
+        .checked_add(amount)
+        .ok_or(WalletError::AllowanceExceeded)?;
+    if spent > allowance_details.limit {
+        return Err(WalletError::AllowanceExceeded.into());
+    }
+    load_token_account(
+        source_account,
+        &allowance_details.mint,
+        wallet_authority.key,
+    )?;
+    let bump = wallet_details.authority_bump;
+    let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    if *token_program.key != TOKEN_PROGRAM_ID {
+        return Err(WalletError::IncorrectTokenProgram.into());
+    }
+    let decimals = load_mint_decimals(mint, &allowance_details.mint)?;
+    allowance_details.spent = spent;
+    store(&allowance_details, allowance)?;
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            token_program.key,
+            source_account.key,
+            mint.key,
+            destination_account.key,
+            wallet_authority.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            source_account.clone(),
+            mint.clone(),
+            destination_account.clone(),
+            wallet_authority.clone(),
+        ],
+        &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+    )?;
+    // record activity of the user and the wallet
+    let current_time = Clock::get()?.unix_timestamp;
+    user_details.last_active_at = current_time;
+    store(&user_details, wallet_auth)?;
+    wallet_details.last_activity = current_time;
+    store(&wallet_details, wallet_config)?;
+
+    Ok(())
+}
+
+pub fn release_escrow(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let escrow = next_account_info(accounts_iter)?;
+    let escrow_token_account = next_account_info(accounts_iter)?;
+    let receive_account = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let wallet_authority_key =
+        authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    let escrow_details = load_escrow(program_id, wallet_config.key, escrow)?;
+    if Clock::get()?.unix_timestamp < escrow_details.unlock_time {
+        return Err(WalletError::EscrowLocked.into());
+    }
+    if *receive_account.key != escrow_details.receive_account {
+        return Err(WalletError::IncorrectReceiveAccount.into());
+    }
+    wallet_details.open_accounts = wallet_details.open_accounts.saturating_sub(1);
+    store(&wallet_details, wallet_config)?;
+    close_escrow(
+        wallet_config.key,
+        escrow,
+        escrow_details,
+        escrow_token_account,
+        receive_account,
+        wallet_authority,
+        token_program,
+        mint,
+    )
+}
+
+pub fn withdraw_stream(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let stream = next_account_info(accounts_iter)?;
+    let source_account = next_account_info(accounts_iter)?;
+    let destination_account = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let bump = wallet_details.authority_bump;
+    let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    let mut stream_details = load_stream(program_id, wallet_config.key, stream)?;
+    if *destination_account.key != stream_details.receive_account {
+        return Err(WalletError::IncorrectReceiveAccount.into());
+    }
+    load_token_account(source_account, &stream_details.mint, wallet_authority.key)?;
+    if *token_program.key != TOKEN_PROGRAM_ID {
+        return Err(WalletError::IncorrectTokenProgram.into());
+    }
+    let decimals = load_mint_decimals(mint, &stream_details.mint)?;
+    // everything vested so far minus what was already withdrawn
+    let current_time = Clock::get()?.unix_timestamp;
+    let elapsed = current_time.min(stream_details.end_time) - stream_details.start_time;
+    let vested = stream_details
+        .rate
+        .checked_mul(elapsed.try_into().unwrap())
+        .ok_or(ProgramError::InvalidArgument)?;
+    let amount = vested - stream_details.withdrawn;
+    if amount == 0 {
+        return Err(WalletError::NothingVested.into());
+    }
+    // record the withdrawal before paying out, a fully paid out stream is closed with its rent going to the wallet authority
+    stream_details.withdrawn = vested;
+    if current_time >= stream_details.end_time {
+        stream_details.is_initialized = false;
+        wallet_details.open_accounts = wallet_details.open_accounts.saturating_sub(1);
+        store(&wallet_details, wallet_config)?;
+    }
+    store(&stream_details, stream)?;
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            token_program.key,
+            source_account.key,
+            mint.key,
+            destination_account.key,
+            wallet_authority.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            source_account.clone(),
+            mint.clone(),
+            destination_account.clone(),
+            wallet_authority.clone(),
+        ],
+        &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+    )?;
+    if !stream_details.is_initialized {
+        let balance = stream.lamports();
+        **stream.try_borrow_mut_lamports()? -= balance;
+        **wallet_authority.try_borrow_mut_lamports()? += balance;
+    }
+
+    Ok(())
+}
+
+// permissionless, the tokens can only go to the vesting's receive account
+pub fn release_vesting(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let vesting = next_account_info(accounts_iter)?;
+    let vesting_token_account = next_account_info(accounts_iter)?;
+    let receive_account = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let wallet_authority_key =
+        authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    let mut vesting_details = load_vesting(program_id, wallet_config.key, vesting)?;
+    if *receive_account.key != vesting_details.receive_account {
+        return Err(WalletError::IncorrectReceiveAccount.into());
+    }
+    if *vesting_token_account.key
+        != get_associated_token_address(vesting.key, &vesting_details.mint)
+    {
+        return Err(WalletError::IncorrectSendAccount.into());
+    }
+    if *token_program.key != TOKEN_PROGRAM_ID {
+        return Err(WalletError::IncorrectTokenProgram.into());
+    }
+    let decimals = load_mint_decimals(mint, &vesting_details.mint)?;
+    let vested = vesting_details.vested(Clock::get()?.unix_timestamp);
+    let amount = vested - vesting_details.released;
+    if amount == 0 {
+        return Err(WalletError::NothingVested.into());
+    }
+    // record the release before paying out, a fully released vesting is closed with its rent going to the wallet
+    // authority
+    vesting_details.released = vested;
+    let finished = vested == vesting_details.amount;
+    if finished {
+        vesting_details.is_initialized = false;
+        wallet_details.open_accounts = wallet_details.open_accounts.saturating_sub(1);
+        store(&wallet_details, wallet_config)?;
+    }
+    store(&vesting_details, vesting)?;
+    let vesting_seeds: &[&[u8]] = &[
+        VESTING.as_bytes(),
+        wallet_config.key.as_ref(),
+        &vesting_details.sequence.to_le_bytes(),
+        &[vesting_details.bump],
+    ];
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            token_program.key,
+            vesting_token_account.key,
+            mint.key,
+            receive_account.key,
+            vesting.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            vesting_token_account.clone(),
+            mint.clone(),
+            receive_account.clone(),
+            vesting.clone(),
+        ],
+        &[vesting_seeds],
+    )?;
+    if finished {
+        invoke_signed(
+            &token_instruction::close_account(
+                token_program.key,
+                vesting_token_account.key,
+                wallet_authority.key,
+                vesting.key,
+                &[],
+            )?,
+            &[
+                vesting_token_account.clone(),
+                wallet_authority.clone(),
+                vesting.clone(),
+            ],
+            &[vesting_seeds],
+        )?;
+        let balance = vesting.lamports();
+        **vesting.try_borrow_mut_lamports()? -= balance;
+        **wallet_authority.try_borrow_mut_lamports()? += balance;
+    }
+
+    Ok(())
+}
+
+pub fn withdraw_claim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let recipient = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let claim = next_account_info(accounts_iter)?;
+    let claim_token_account = next_account_info(accounts_iter)?;
+    let receive_account = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+
+    if !recipient.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let wallet_authority_key =
+        authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    let claim_details = load_claim(program_id, wallet_config.key, claim)?;
+    if *recipient.key != claim_details.recipient {
+        return Err(WalletError::IncorrectRecipient.into());
+    }
+    if Clock::get()?.unix_timestamp > claim_details.expires_at {
+        return Err(WalletError::ClaimExpired.into());
+    }
+    wallet_details.open_accounts = wallet_details.open_accounts.saturating_sub(1);
+    store(&wallet_details, wallet_config)?;
+    close_claim(
+        wallet_config.key,
+        claim,
+        claim_details,
+        claim_token_account,
+        receive_account,
+        wallet_authority,
+        token_program,
+        mint,
+    )
+}
+
+pub fn reclaim_expired_claim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let claim = next_account_info(accounts_iter)?;
+    let claim_token_account = next_account_info(accounts_iter)?;
+    let receive_account = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let wallet_authority_key =
+        authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    let claim_details = load_claim(program_id, wallet_config.key, claim)?;
+    if Clock::get()?.unix_timestamp <= claim_details.expires_at {
+        return Err(WalletError::ClaimNotExpired.into());
+    }
+    // unclaimed tokens go back to the wallet
+    let receive_account_details = Account::unpack(&receive_account.data.borrow())?;
+    if receive_account_details.owner != *wallet_authority.key {
+        return Err(WalletError::IncorrectReceiveAccount.into());
+    }
+    wallet_details.open_accounts = wallet_details.open_accounts.saturating_sub(1);
+    store(&wallet_details, wallet_config)?;
+    close_claim(
+        wallet_config.key,
+        claim,
+        claim_details,
+        claim_token_account,
+        receive_account,
+        wallet_authority,
+        token_program,
+        mint,
+    )
+}
+
+// permissionless crank, whoever calls it picks the route but the wallet only ever gives up amount input tokens
+pub fn execute_recurring_swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+    let swap = next_account_info(accounts_iter)?;
+    let registry = next_account_info(accounts_iter)?;
+    let input_account = next_account_info(accounts_iter)?;
+    let output_account = next_account_info(accounts_iter)?;
+    let swap_program = next_account_info(accounts_iter)?;
+
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    if wallet_details.frozen {
+        return Err(WalletError::WalletFrozen.into());
+    }
+    let bump = wallet_details.authority_bump;
+    let wallet_authority_key = authority_address(program_id, wallet_config.key, bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    let mut swap_details = load_recurring_swap(program_id, wallet_config.key, swap)?;
+    // the swap program has to still be registered, unregistering it pauses every swap through it
+    let registry_details = load_registry(program_id, wallet_config.key, registry)?;
+    if !registry_details
+        .programs
+        .contains(&swap_details.swap_program)
+    {
+        return Err(WalletError::ProgramNotRegistered.into());
+    }
+    if *swap_program.key != swap_details.swap_program {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let current_time = Clock::get()?.unix_timestamp;
+    if swap_details.remaining == 0 || current_time < swap_details.next_swap_at {
+        return Err(WalletError::SwapNotDue.into());
+    }
+    let input_before = Account::unpack(&input_account.data.borrow())?;
+    if *input_account.owner != TOKEN_PROGRAM_ID
+        || input_before.mint != swap_details.input_mint
+        || input_before.owner != *wallet_authority.key
+    {
+        return Err(WalletError::IncorrectSendAccount.into());
+    }
+    let output_before = Account::unpack(&output_account.data.borrow())?;
+    if *output_account.owner != TOKEN_PROGRAM_ID
+        || output_before.mint != swap_details.output_mint
+        || output_before.owner != *wallet_authority.key
+    {
+        return Err(WalletError::IncorrectReceiveAccount.into());
+    }
+    // no other token account of the wallet may be handed to the swap program along with the authority signature
+    let mut account_metas = Vec::new();
+    let mut account_infos = vec![swap_program.clone()];
+    for account_info in accounts_iter {
+        if *account_info.owner == TOKEN_PROGRAM_ID
+            && account_info.key != input_account.key
+            && account_info.key != output_account.key
+        {
+            if let Ok(token_account) = Account::unpack(&account_info.data.borrow()) {
+                if token_account.owner == *wallet_authority.key {
+                    return Err(WalletError::UnauthorizedSwapAccount.into());
+                }
+            }
+        }
+        let is_signer = account_info.is_signer || account_info.key == wallet_authority.key;
+        account_metas.push(if account_info.is_writable {
+            AccountMeta::new(*account_info.key, is_signer)
+        } else {
+            AccountMeta::new_readonly(*account_info.key, is_signer)
+        });
+        account_infos.push(account_info.clone());
+    }
+    let authority_lamports = wallet_authority.lamports();
+    // record the swap before the CPI, a finished schedule is closed with its rent going to the wallet authority
+    swap_details.next_swap_at = current_time.saturating_add(swap_details.interval);
+    swap_details.remaining -= 1;
+    if swap_details.remaining == 0 {
+        swap_details.is_initialized = false;
+        wallet_details.open_accounts = wallet_details.open_accounts.saturating_sub(1);
+    }
+    store(&swap_details, swap)?;
+    invoke_signed(
+        &Instruction {
+            program_id: swap_details.swap_program,
+            accounts: account_metas,
+            data,
+        },
+        &account_infos,
+        &[&[AUTHORITY.as_bytes(), wallet_config.key.as_ref(), &[bump]]],
+    )?;
+    // the swap may only move the input and output balances, not authorities, delegates or SOL
+    let input_after = Account::unpack(&input_account.data.borrow())?;
+    let output_after = Account::unpack(&output_account.data.borrow())?;
+    if *wallet_authority.owner != SYSTEM_PROGRAM_ID
+        || wallet_authority.lamports() < authority_lamports
+        || input_after.owner != input_before.owner
+        || input_after.delegate != input_before.delegate
+        || input_after.close_authority != input_before.close_authority
+        || output_after.owner != output_before.owner
+        || output_after.delegate != output_before.delegate
+        || output_after.close_authority != output_before.close_authority
+    {
+        return Err(WalletError::UnauthorizedSwapAccount.into());
+    }
+    let input_amount = input_before.amount.saturating_sub(input_after.amount);
+    if input_amount > swap_details.amount {
+        return Err(WalletError::SwapAmountExceeded.into());
+    }
+    let output_amount = output_after.amount.saturating_sub(output_before.amount);
+    if output_amount < swap_details.min_output {
+        return Err(WalletError::SwapOutputTooLow.into());
+    }
+    if !swap_details.is_initialized {
+        let balance = swap.lamports();
+        **swap.try_borrow_mut_lamports()? -= balance;
+        **wallet_authority.try_borrow_mut_lamports()? += balance;
+    }
+    WalletEvent::SwapExecuted {
+        wallet: *wallet_config.key,
+        swap: *swap.key,
+        input_amount,
+        output_amount,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)
+}
+
+pub fn get_sol_balance(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_authority = next_account_info(accounts_iter)?;
+
+    let wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let wallet_authority_key =
+        authority_address(program_id, wallet_config.key, wallet_details.authority_bump)?;
+    if *wallet_authority.key != wallet_authority_key {
+        return Err(WalletError::InvalidWalletAuthority.into());
+    }
+    let balance = wallet_authority.lamports();
+    let sol_balance = SolBalance {
+        balance,
+        rent_floor: wallet_details.rent_floor,
+        available: balance.saturating_sub(wallet_details.rent_floor),
+        total_deposited: wallet_details.total_deposited,
+        total_withdrawn: wallet_details.total_withdrawn,
+    };
+    set_return_data(&sol_balance.try_to_vec()?);
+
+    Ok(())
+}
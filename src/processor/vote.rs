@@ -0,0 +1,491 @@
+use super::accounts::{
+    check_rent_exempt, check_role, check_vote_gate, load, load_owner, store, ProposalAccounts,
+    VoteAccounts, VoteManyAccounts,
+};
+use super::bitmap::{is_bit_set, set_bit};
+use super::pda::{COMMENT, VOTE_DIGEST, VOTE_PERMIT};
+use crate::error::WalletError;
+use crate::event::WalletEvent;
+use crate::state::{
+    AccountType, PriceCondition, ProposalType, VoteComment, VotePermit, WalletAuth, WalletConfig,
+    MAX_COMMENT_LEN, ROLE_VOTE,
+};
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    ed25519_program::ID as ED25519_PROGRAM_ID,
+    entrypoint::ProgramResult,
+    hash::hashv,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::{
+        instructions::{load_current_index_checked, load_instruction_at_checked},
+        rent::Rent,
+        Sysvar,
+    },
+};
+
+pub fn vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let VoteAccounts {
+        mut voter,
+        mut proposal,
+    } = VoteAccounts::load_and_validate(program_id, accounts)?;
+
+    let current_time = Clock::get()?.unix_timestamp;
+    cast_vote(
+        voter.wallet_config.key,
+        &mut voter.wallet_details,
+        &voter.user_details,
+        &mut proposal,
+        current_time,
+    )?;
+    // record activity of the user and the wallet
+    voter.user_details.last_active_at = current_time;
+    voter.user_details.last_vote_at = current_time;
+    voter.user_details.votes_cast += 1;
+    store(&voter.user_details, voter.wallet_auth)?;
+    voter.wallet_details.last_activity = current_time;
+    store(&voter.wallet_details, voter.wallet_config)?;
+
+    Ok(())
+}
+
+// proposal and vote count pairs follow the wallet auth, all votes are cast or none are
+pub fn vote_many(program_id: &Pubkey, accounts: &[AccountInfo], proposals: u8) -> ProgramResult {
+    if proposals == 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let VoteManyAccounts {
+        mut voter,
+        proposals,
+    } = VoteManyAccounts::load_and_validate(program_id, accounts, proposals)?;
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let votes = proposals.len() as u64;
+    for mut proposal in proposals {
+        cast_vote(
+            voter.wallet_config.key,
+            &mut voter.wallet_details,
+            &voter.user_details,
+            &mut proposal,
+            current_time,
+        )?;
+    }
+    // record activity of the user and the wallet
+    voter.user_details.last_active_at = current_time;
+    voter.user_details.last_vote_at = current_time;
+    voter.user_details.votes_cast += votes;
+    store(&voter.user_details, voter.wallet_auth)?;
+    voter.wallet_details.last_activity = current_time;
+    store(&voter.wallet_details, voter.wallet_config)?;
+
+    Ok(())
+}
+
+// owners sign the digest off-chain, anyone can submit their signatures together with the ed25519 instructions
+pub fn vote_with_signatures(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let wallet_config = next_account_info(accounts_iter)?;
+    let proposal = next_account_info(accounts_iter)?;
+    let vote_count = next_account_info(accounts_iter)?;
+    let instructions = next_account_info(accounts_iter)?;
+
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let mut proposal =
+        ProposalAccounts::load_and_validate(program_id, wallet_config.key, proposal, vote_count)?;
+    let digest = vote_digest(
+        program_id,
+        wallet_config.key,
+        proposal.proposal.key,
+        &proposal.proposal_details.proposal,
+        &proposal.proposal_details.content_hash,
+        &proposal.proposal_details.price_condition,
+        proposal.proposal_details.revision,
+    )?;
+    let signers = signed_messages(instructions, &digest)?;
+    let current_time = Clock::get()?.unix_timestamp;
+    while let Some(wallet_auth) = accounts_iter.next() {
+        let user_details = load::<WalletAuth>(wallet_auth, program_id)?;
+        let mut user_details = load_owner(
+            program_id,
+            wallet_config.key,
+            &user_details.owner,
+            wallet_auth,
+        )?;
+        if user_details.nft_bound {
+            return Err(WalletError::NftBoundSeat.into());
+        }
+        if !signers.contains(&user_details.owner) {
+            return Err(WalletError::MissingOwnerSignature.into());
+        }
+        check_role(&user_details, ROLE_VOTE)?;
+        let gate_account = if wallet_details.gate_mint != Pubkey::default() {
+            Some(next_account_info(accounts_iter)?)
+        } else {
+            None
+        };
+        check_vote_gate(&wallet_details, &user_details.owner, gate_account)?;
+        cast_vote(
+            wallet_config.key,
+            &mut wallet_details,
+            &user_details,
+            &mut proposal,
+            current_time,
+        )?;
+        user_details.last_active_at = current_time;
+        user_details.last_vote_at = current_time;
+        user_details.votes_cast += 1;
+        store(&user_details, wallet_auth)?;
+    }
+    wallet_details.last_activity = current_time;
+    store(&wallet_details, wallet_config)?;
+
+    Ok(())
+}
+
+pub fn submit_vote_permit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    expirations: Vec<i64>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let proposer = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let proposal = next_account_info(accounts_iter)?;
+    let vote_count = next_account_info(accounts_iter)?;
+    let instructions = next_account_info(accounts_iter)?;
+
+    if !proposer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let mut proposal =
+        ProposalAccounts::load_and_validate(program_id, wallet_config.key, proposal, vote_count)?;
+    if proposal.proposal_details.proposer != *proposer.key {
+        return Err(WalletError::NotProposer.into());
+    }
+    let current_time = Clock::get()?.unix_timestamp;
+    for expires_at in expirations {
+        let wallet_auth = next_account_info(accounts_iter)?;
+        let user_details = load::<WalletAuth>(wallet_auth, program_id)?;
+        let mut user_details = load_owner(
+            program_id,
+            wallet_config.key,
+            &user_details.owner,
+            wallet_auth,
+        )?;
+        if current_time > expires_at {
+            return Err(WalletError::VotePermitExpired.into());
+        }
+        let permit = VotePermit {
+            wallet: *wallet_config.key,
+            proposal: *proposal.proposal.key,
+            approve: true,
+            expires_at,
+        };
+        let message = vote_permit_message(program_id, &permit, proposal.proposal_details.revision)?;
+        if user_details.nft_bound {
+            return Err(WalletError::NftBoundSeat.into());
+        }
+        if !signed_messages(instructions, &message)?.contains(&user_details.owner) {
+            return Err(WalletError::MissingOwnerSignature.into());
+        }
+        check_role(&user_details, ROLE_VOTE)?;
+        let gate_account = if wallet_details.gate_mint != Pubkey::default() {
+            Some(next_account_info(accounts_iter)?)
+        } else {
+            None
+        };
+        check_vote_gate(&wallet_details, &user_details.owner, gate_account)?;
+        cast_vote(
+            wallet_config.key,
+            &mut wallet_details,
+            &user_details,
+            &mut proposal,
+            current_time,
+        )?;
+        user_details.last_active_at = current_time;
+        user_details.last_vote_at = current_time;
+        user_details.votes_cast += 1;
+        store(&user_details, wallet_auth)?;
+    }
+    wallet_details.last_activity = current_time;
+    store(&wallet_details, wallet_config)?;
+
+    Ok(())
+}
+
+pub fn extend_proposal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let proposal = next_account_info(accounts_iter)?;
+    let vote_count = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let user_details = load_owner(program_id, wallet_config.key, owner.key, wallet_auth)?;
+    let ProposalAccounts {
+        proposal_details,
+        mut voting_details,
+        ..
+    } = ProposalAccounts::load_and_validate(program_id, wallet_config.key, proposal, vote_count)?;
+    if proposal_details.draft {
+        return Err(WalletError::ProposalIsDraft.into());
+    }
+    if Clock::get()?.unix_timestamp > voting_details.expires_at {
+        return Err(WalletError::ProposalExpired.into());
+    }
+    if voting_details.epoch != wallet_details.epoch {
+        return Err(WalletError::EpochMismatch.into());
+    }
+    if voting_details.extended {
+        return Err(WalletError::ProposalAlreadyExtended.into());
+    }
+    // half of the threshold rounded up, the caller among them
+    if !is_bit_set(&voting_details.vote_record, user_details.id)
+        || voting_details.votes < voting_details.threshold / 2 + voting_details.threshold % 2
+    {
+        return Err(WalletError::ExtensionQuorumNotMet.into());
+    }
+
+    let lifetime = voting_details.expires_at - voting_details.proposed_time;
+    voting_details.expires_at = voting_details.expires_at.saturating_add(lifetime);
+    voting_details.extended = true;
+    store(&voting_details, vote_count)?;
+    WalletEvent::ProposalExtended {
+        wallet: *wallet_config.key,
+        proposal: *proposal.key,
+        owner: *owner.key,
+        expires_at: voting_details.expires_at,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)
+}
+
+pub fn comment_vote(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    comment: Vec<u8>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let wallet_config = next_account_info(accounts_iter)?;
+    let wallet_auth = next_account_info(accounts_iter)?;
+    let proposal = next_account_info(accounts_iter)?;
+    let vote_count = next_account_info(accounts_iter)?;
+    let vote_comment = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut wallet_details = load::<WalletConfig>(wallet_config, program_id)?;
+    let user_details = load_owner(program_id, wallet_config.key, owner.key, wallet_auth)?;
+    let ProposalAccounts { voting_details, .. } =
+        ProposalAccounts::load_and_validate(program_id, wallet_config.key, proposal, vote_count)?;
+    if comment.is_empty()
+        || comment.len() > MAX_COMMENT_LEN
+        || !is_bit_set(&voting_details.vote_record, user_details.id)
+    {
+        return Err(WalletError::InvalidVoteComment.into());
+    }
+    let (vote_comment_key, bump) = Pubkey::find_program_address(
+        &[
+            COMMENT.as_bytes(),
+            proposal.key.as_ref(),
+            owner.key.as_ref(),
+        ],
+        program_id,
+    );
+    if *vote_comment.key != vote_comment_key {
+        return Err(WalletError::InvalidVoteComment.into());
+    }
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        return Err(WalletError::IncorrectSystemProgram.into());
+    }
+
+    // create the comment, a second comment of the owner fails here
+    let account_size: u64 = VoteComment::LEN.try_into().unwrap();
+    let rent = Rent::get()?;
+    let rent_amount = rent.minimum_balance(VoteComment::LEN);
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            vote_comment.key,
+            rent_amount,
+            account_size,
+            program_id,
+        ),
+        &[owner.clone(), vote_comment.clone()],
+        &[&[
+            COMMENT.as_bytes(),
+            proposal.key.as_ref(),
+            owner.key.as_ref(),
+            &[bump],
+        ]],
+    )?;
+    check_rent_exempt(vote_comment, &rent)?;
+    let mut comment_bytes = [0u8; MAX_COMMENT_LEN];
+    comment_bytes[..comment.len()].copy_from_slice(&comment);
+    let comment_details = VoteComment {
+        discriminator: AccountType::VoteComment,
+        wallet: *wallet_config.key,
+        proposal: *proposal.key,
+        owner: *owner.key,
+        posted_at: Clock::get()?.unix_timestamp,
+        len: comment.len() as u8,
+        comment: comment_bytes,
+        bump,
+        is_initialized: true,
+    };
+    store(&comment_details, vote_comment)?;
+
+    WalletEvent::VoteCommented {
+        wallet: *wallet_config.key,
+        proposal: *proposal.key,
+        owner: *owner.key,
+    }
+    .emit(&mut wallet_details);
+    store(&wallet_details, wallet_config)
+}
+
+// the signed bytes of a vote permit, only approving permits are accepted since votes can not be against a proposal
+// the revision of an edited or amended proposal is appended, so permits signed for an earlier one are refused
+pub(crate) fn vote_permit_message(
+    program_id: &Pubkey,
+    permit: &VotePermit,
+    revision: u16,
+) -> Result<Vec<u8>, ProgramError> {
+    let mut message = VOTE_PERMIT.as_bytes().to_vec();
+    message.extend_from_slice(program_id.as_ref());
+    message.extend_from_slice(&permit.try_to_vec()?);
+    if revision != 0 {
+        message.extend_from_slice(&revision.to_le_bytes());
+    }
+    Ok(message)
+}
+
+// what an owner signs to vote for a proposal without sending a transaction, an amended or edited proposal can go
+// back to an earlier payload under the same address, so its revision is appended like in vote_permit_message
+// a content hash or price condition is only part of it once set, so digests of proposals without them stay the same
+pub(crate) fn vote_digest(
+    program_id: &Pubkey,
+    wallet_config: &Pubkey,
+    proposal: &Pubkey,
+    proposal_type: &ProposalType,
+    content_hash: &[u8; 32],
+    price_condition: &PriceCondition,
+    revision: u16,
+) -> Result<[u8; 32], ProgramError> {
+    let proposal_bytes = proposal_type.try_to_vec()?;
+    let condition_bytes = price_condition.try_to_vec()?;
+    let revision_bytes = revision.to_le_bytes();
+    let mut parts = vec![
+        VOTE_DIGEST.as_bytes(),
+        program_id.as_ref(),
+        wallet_config.as_ref(),
+        proposal.as_ref(),
+        &proposal_bytes,
+    ];
+    if *content_hash != [0; 32] {
+        parts.push(content_hash);
+    }
+    if price_condition.feed != Pubkey::default() {
+        parts.push(&condition_bytes);
+    }
+    if revision != 0 {
+        parts.push(&revision_bytes);
+    }
+    Ok(hashv(&parts).to_bytes())
+}
+
+// public keys of the signatures over message verified by ed25519 instructions before the current one, only
+// signatures whose key and message live in their own instruction are counted
+fn signed_messages(
+    instructions: &AccountInfo,
+    message: &[u8],
+) -> Result<Vec<Pubkey>, ProgramError> {
+    let mut signers = Vec::new();
+    let current_index = load_current_index_checked(instructions)?;
+    for index in 0..current_index {
+        let instruction = load_instruction_at_checked(index as usize, instructions)?;
+        if instruction.program_id != ED25519_PROGRAM_ID {
+            continue;
+        }
+        let data = instruction.data;
+        let count = data.first().copied().unwrap_or(0) as usize;
+        for i in 0..count {
+            // signature, public key and message offsets with their instruction indexes, 7 u16s per signature
+            let offsets: Vec<u16> = (0..7)
+                .map(|j| {
+                    data.get(2 + i * 14 + j * 2..4 + i * 14 + j * 2)
+                        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+                })
+                .collect::<Option<_>>()
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            if offsets[1] != u16::MAX || offsets[3] != u16::MAX || offsets[6] != u16::MAX {
+                continue;
+            }
+            let public_key = offsets[2] as usize;
+            let message_start = offsets[4] as usize;
+            let message_end = message_start + offsets[5] as usize;
+            match (
+                data.get(public_key..public_key + 32),
+                data.get(message_start..message_end),
+            ) {
+                (Some(public_key), Some(signed)) if signed == message => {
+                    signers.push(Pubkey::new_from_array(public_key.try_into().unwrap()))
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(signers)
+}
+
+// checks that the proposal is active and records the vote of user_details on it
+fn cast_vote(
+    wallet_config: &Pubkey,
+    wallet_details: &mut WalletConfig,
+    user_details: &WalletAuth,
+    proposal: &mut ProposalAccounts,
+    current_time: i64,
+) -> ProgramResult {
+    if proposal.proposal_details.draft {
+        return Err(WalletError::ProposalIsDraft.into());
+    }
+    let voting_details = &mut proposal.voting_details;
+    // check that proposal is active
+    if current_time > voting_details.expires_at {
+        return Err(WalletError::ProposalExpired.into());
+    }
+    if voting_details.epoch != wallet_details.epoch {
+        return Err(WalletError::EpochMismatch.into());
+    }
+    // check that user has not voted yet
+    if is_bit_set(&voting_details.vote_record, user_details.id) {
+        return Err(WalletError::AlreadyVoted.into());
+    }
+    set_bit(&mut voting_details.vote_record, user_details.id);
+    voting_details.votes += 1;
+    store(voting_details, proposal.vote_count)?;
+
+    WalletEvent::Voted {
+        wallet: *wallet_config,
+        proposal: *proposal.proposal.key,
+        owner: user_details.owner,
+        votes: voting_details.votes,
+    }
+    .emit(wallet_details);
+
+    Ok(())
+}
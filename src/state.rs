@@ -5,20 +5,137 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
-#[derive(BorshSerialize, BorshDeserialize)]
+// the bit maps are handled by the processor, they stay reachable from here for existing users
+pub use crate::processor::bitmap::{clear_bit, count_bits, first_clear_bit, is_bit_set, set_bit};
+
+pub const MAX_EXECUTORS: usize = 3;
+pub const MAX_REGISTERED_PROGRAMS: usize = 16;
+pub const MAX_BLACKLISTED: usize = 32;
+// bytes of a vote comment, enough for a short reason or the hash of a longer one kept elsewhere
+pub const MAX_COMMENT_LEN: usize = 128;
+// records kept by an audit log before the oldest is overwritten
+pub const AUDIT_LOG_LEN: usize = 32;
+// mints whose transferred totals wallet stats keep, later mints are not tracked
+pub const MAX_TRACKED_MINTS: usize = 16;
+// delay between approval and effect of threshold, lifetime and timelock changes for new wallets
+pub const DEFAULT_TIMELOCK: i64 = 24 * 60 * 60;
+// proposal lifetime bounds of wallets created without their own
+pub const DEFAULT_MIN_PROPOSAL_LIFETIME: i64 = 600;
+pub const DEFAULT_MAX_PROPOSAL_LIFETIME: i64 = i64::MAX;
+// window other owners get to veto a self-service key rotation in new wallets
+pub const DEFAULT_ROTATION_DELAY: i64 = 3 * 24 * 60 * 60;
+// proposals a new wallet can have open at once
+pub const DEFAULT_MAX_OPEN_PROPOSALS: u16 = 32;
+// windows of the daily and weekly spend caps, each restarts with the first outflow after it ends
+pub const SPEND_WINDOW_DAY: i64 = 24 * 60 * 60;
+pub const SPEND_WINDOW_WEEK: i64 = 7 * SPEND_WINDOW_DAY;
+// amount of a Transfer or TransferToWallet proposal that moves the whole balance of the send account at execution
+pub const TRANSFER_ALL: u64 = u64::MAX;
+// basis points of a TransferPercent proposal that move the whole balance
+pub const BASIS_POINTS: u16 = 10_000;
+// recipients of a single SplitPayment proposal
+pub const MAX_PAYMENTS: usize = 16;
+
+// with the anchor feature every account starts with sha256("account:<Name>")[..8], as anchor expects
+#[cfg(feature = "anchor")]
+pub const DISCRIMINATOR_LEN: usize = 8;
+#[cfg(not(feature = "anchor"))]
+pub const DISCRIMINATOR_LEN: usize = 0;
+
+pub const ROLE_PROPOSE: u8 = 1;
+pub const ROLE_VOTE: u8 = 1 << 1;
+pub const ROLE_EXECUTE: u8 = 1 << 2;
+pub const ROLE_ALL: u8 = ROLE_PROPOSE | ROLE_VOTE | ROLE_EXECUTE;
+// proposals of an owner with this role execute without votes once the wallet's optimistic delay passes without an
+// objection, not part of ROLE_ALL so it is only ever granted by SetRoles
+pub const ROLE_OPTIMISTIC: u8 = 1 << 3;
+// roles a session key can be scoped to, execution always needs the owner or an executor
+pub const SESSION_SCOPES: u8 = ROLE_PROPOSE | ROLE_VOTE;
+
+// votes a proposal needs, owners * m is computed wide since it does not fit in a u8 for larger wallets
+pub fn approval_threshold(owners: u8, m: u8, n: u8) -> u8 {
+    (owners as u16 * m as u16 / n as u16) as u8
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq)]
 pub enum AccountType {
     WalletConfig,
     WalletAuth,
     Proposal,
     VoteCount,
+    Allowance,
+    SessionKey,
+    Template,
+    Vault,
+    Escrow,
+    Stream,
+    Claim,
+    ProgramRegistry,
+    RecurringSwap,
+    KeyRotation,
+    TreasuryStats,
+    Blacklist,
+    AuditLog,
+    WalletStats,
+    Vesting,
+    VoteComment,
+}
+
+// an approved configuration change waiting out the wallet's timelock
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq)]
+pub enum PendingChange {
+    None,
+    Threshold { m: u8, n: u8 },
+    ProposalLifetime { duration: i64 },
+    Timelock { delay: i64 },
+    LifetimeBounds { min: i64, max: i64 },
+    RotationDelay { delay: i64 },
+    EmergencyPolicy { m: u8, lifetime: i64 },
+    ThresholdMode { fixed: bool },
+}
+
+impl PendingChange {
+    // the borsh discriminant, recorded in the audit log when the change is applied
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Threshold { .. } => 1,
+            Self::ProposalLifetime { .. } => 2,
+            Self::Timelock { .. } => 3,
+            Self::LifetimeBounds { .. } => 4,
+            Self::RotationDelay { .. } => 5,
+            Self::EmergencyPolicy { .. } => 6,
+            Self::ThresholdMode { .. } => 7,
+        }
+    }
+}
+
+// an account of an ExecuteInstruction proposal, the wallet authority is the only signer the program adds
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ProposalAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+// an entry of a SplitPayment proposal, amount is in basis points of the balance if the payment is by share
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Payment {
+    pub receive_account: Pubkey,
+    pub amount: u64,
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
 pub enum ProposalType {
+    // with unwrap a native mint transfer pays out lamports to receive_account instead of wrapped SOL, with source
+    // the tokens have to come from the authority's auxiliary token account of that seed
     Transfer {
         token_mint: Pubkey,
         receive_account: Pubkey,
         amount: u64,
+        vault: u8,
+        unwrap: bool,
+        source: Option<u64>,
     },
     AddOwner {
         user: Pubkey,
@@ -26,6 +143,432 @@ pub enum ProposalType {
     ChangeProposalLifetime {
         duration: i64,
     },
+    TransferSol {
+        receive_account: Pubkey,
+        amount: u64,
+    },
+    CloseWallet {
+        destination: Pubkey,
+    },
+    SetRecovery {
+        beneficiary: Pubkey,
+        inactivity_period: i64,
+    },
+    SetExecutors {
+        executors: [Pubkey; MAX_EXECUTORS],
+    },
+    SetRoles {
+        user: Pubkey,
+        roles: u8,
+    },
+    // sets the cumulative amount of token_mint user can spend, amounts already spent still count
+    SetAllowance {
+        user: Pubkey,
+        token_mint: Pubkey,
+        amount: u64,
+    },
+    CreateTemplate {
+        id: u64,
+        template: TemplateProposal,
+    },
+    DeleteTemplate {
+        id: u64,
+    },
+    CreateVault {
+        index: u8,
+        m: u8,
+        n: u8,
+    },
+    EscrowTransfer {
+        token_mint: Pubkey,
+        receive_account: Pubkey,
+        amount: u64,
+        unlock_time: i64,
+    },
+    CancelEscrow {
+        sequence: u64,
+    },
+    CreateStream {
+        token_mint: Pubkey,
+        receive_account: Pubkey,
+        rate: u64,
+        duration: i64,
+    },
+    CancelStream {
+        sequence: u64,
+    },
+    ClaimableTransfer {
+        token_mint: Pubkey,
+        recipient: Pubkey,
+        amount: u64,
+        claim_period: i64,
+    },
+    // lookup tables owned by the wallet authority, so execute transactions with many accounts fit in a v0 message
+    CreateLookupTable {
+        recent_slot: u64,
+    },
+    ExtendLookupTable {
+        table: Pubkey,
+        addresses: Vec<Pubkey>,
+    },
+    FreezeLookupTable {
+        table: Pubkey,
+    },
+    // idle treasury SOL goes into an spl stake pool, the pool tokens stay with the wallet authority
+    StakePoolDeposit {
+        stake_pool: Pubkey,
+        amount: u64,
+    },
+    StakePoolWithdraw {
+        stake_pool: Pubkey,
+        pool_tokens: u64,
+    },
+    // arbitrary CPIs are only allowed into programs the wallet has registered
+    RegisterProgram {
+        program: Pubkey,
+    },
+    UnregisterProgram {
+        program: Pubkey,
+    },
+    ExecuteInstruction {
+        program: Pubkey,
+        accounts: Vec<ProposalAccount>,
+        data: Vec<u8>,
+    },
+    // a recurring swap of amount input tokens every interval, executed by anyone through a registered swap program
+    CreateRecurringSwap {
+        swap_program: Pubkey,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        amount: u64,
+        min_output: u64,
+        interval: i64,
+        swaps: u64,
+    },
+    CancelRecurringSwap {
+        sequence: u64,
+    },
+    ChangeThreshold {
+        m: u8,
+        n: u8,
+    },
+    ChangeTimelock {
+        delay: i64,
+    },
+    CancelPendingChange,
+    SetLifetimeBounds {
+        min: i64,
+        max: i64,
+    },
+    // for mints whose freeze authority is the wallet authority
+    FreezeAccount {
+        mint: Pubkey,
+        account: Pubkey,
+    },
+    ThawAccount {
+        mint: Pubkey,
+        account: Pubkey,
+    },
+    // spl token multisig accounts with the wallet authority as one of their signers
+    CreateTokenMultisig {
+        m: u8,
+        signers: Vec<Pubkey>,
+    },
+    MultisigTransfer {
+        multisig: Pubkey,
+        token_mint: Pubkey,
+        receive_account: Pubkey,
+        amount: u64,
+    },
+    // rotates an owner's key, keeping their id, roles and activity
+    ChangeOwnerKey {
+        old_owner: Pubkey,
+        new_owner: Pubkey,
+    },
+    ChangeRotationDelay {
+        delay: i64,
+    },
+    // threshold (over the wallet's n) and lifetime of urgent proposals
+    SetEmergencyPolicy {
+        m: u8,
+        lifetime: i64,
+    },
+    // caps of 0 are unlimited, the default pubkey as mint limits TransferSol
+    SetSpendLimit {
+        mint: Pubkey,
+        daily_cap: u64,
+        weekly_cap: u64,
+    },
+    // destinations Transfer and TransferSol refuse to pay out to, whatever the votes
+    BlacklistAddress {
+        address: Pubkey,
+    },
+    UnblacklistAddress {
+        address: Pubkey,
+    },
+    // owners whose vote every execution needs on top of the threshold, an empty list clears them
+    SetMandatoryApprovers {
+        approvers: Vec<Pubkey>,
+    },
+    SetMaxOpenProposals {
+        max: u16,
+    },
+    // lamports locked by every new proposal on top of its rent, 0 disables the deposit
+    SetProposalDeposit {
+        amount: u64,
+    },
+    // lamports every new proposal carries for the executor that closes it, 0 disables the tip
+    SetKeeperTip {
+        amount: u64,
+    },
+    // a transfer to the associated token account of recipient, created at execution if missing, by the
+    // closer or by the wallet authority if treasury_funded
+    TransferToWallet {
+        token_mint: Pubkey,
+        recipient: Pubkey,
+        amount: u64,
+        vault: u8,
+        treasury_funded: bool,
+    },
+    // SOL out of the treasury, which is left either empty or rent exempt
+    TreasuryWithdraw {
+        receive_account: Pubkey,
+        amount: u64,
+    },
+    // a Transfer of basis_points of the send account's balance at execution, never unwrapped
+    TransferPercent {
+        token_mint: Pubkey,
+        receive_account: Pubkey,
+        basis_points: u16,
+        vault: u8,
+    },
+    // one transfer of token_mint to each of payments out of the same send account
+    SplitPayment {
+        token_mint: Pubkey,
+        vault: u8,
+        by_share: bool,
+        payments: Vec<Payment>,
+    },
+    // locks amount for receive_account, nothing is released before cliff_time and the rest linearly from start_time
+    // to end_time
+    CreateVesting {
+        token_mint: Pubkey,
+        receive_account: Pubkey,
+        amount: u64,
+        start_time: i64,
+        cliff_time: i64,
+        end_time: i64,
+    },
+    // clears the emergency freeze set by EmergencyFreeze
+    Unfreeze,
+    // removes owners that have not voted for longer than inactive_for, checked again at execution so an owner
+    // voting in the meantime stays
+    RemoveInactiveOwners {
+        inactive_for: i64,
+        owners: Vec<Pubkey>,
+    },
+    // with fixed set m and the vault and emergency m are plain approval counts instead of fractions of the owners
+    SetThresholdMode {
+        fixed: bool,
+    },
+    // share of the owners that has to have voted for an execution on top of the threshold, 0 disables it, only
+    // applies to proposals created afterwards
+    SetQuorum {
+        basis_points: u16,
+    },
+    // only owners holding amount of mint in their associated token account can vote, the default pubkey as mint
+    // removes the gate
+    SetVoteGate {
+        mint: Pubkey,
+        amount: u64,
+    },
+    // a seat used by whoever holds the NFT of mint, it changes hands with the NFT, the mint must have a supply of one
+    // and no decimals
+    AddNftOwner {
+        mint: Pubkey,
+    },
+    // the program is invoked after every executed proposal, the default pubkey removes the callback
+    SetExecutionCallback {
+        program: Pubkey,
+    },
+    // token-2022 mints whose close or extension authority is the wallet authority, the rent of a closed mint goes
+    // to the wallet authority
+    CloseMint {
+        mint: Pubkey,
+    },
+    SetTransferFee {
+        mint: Pubkey,
+        basis_points: u16,
+        maximum_fee: u64,
+    },
+    // basis points a year, negative rates are allowed
+    SetInterestRate {
+        mint: Pubkey,
+        rate: i16,
+    },
+    // new accounts of the mint start frozen or initialized
+    SetDefaultAccountState {
+        mint: Pubkey,
+        frozen: bool,
+    },
+    // token metadata whose update authority is the wallet authority, creators, collection and uses are kept
+    UpdateMetadata {
+        metadata: Pubkey,
+        name: String,
+        symbol: String,
+        uri: String,
+        seller_fee_basis_points: u16,
+    },
+    // the wallet authority has to be the update authority of the collection too, sized for collections created
+    // with a size
+    SetAndVerifyCollection {
+        metadata: Pubkey,
+        collection_mint: Pubkey,
+        sized: bool,
+    },
+    TransferUpdateAuthority {
+        metadata: Pubkey,
+        new_authority: Pubkey,
+    },
+    // name service accounts owned by the wallet authority, a .sol domain or one of its record accounts
+    TransferDomain {
+        name_account: Pubkey,
+        new_owner: Pubkey,
+    },
+    // writes data at offset into the data of the name account, past its header
+    UpdateDomainRecord {
+        name_account: Pubkey,
+        offset: u32,
+        data: Vec<u8>,
+    },
+    // seconds proposals from owners with ROLE_OPTIMISTIC wait for objections before executing without votes, 0
+    // disables it, only applies to proposals created afterwards
+    SetOptimisticDelay {
+        delay: i64,
+    },
+    // native stake accounts whose staker is the wallet authority, the split off lamports go to a new stake account
+    // at ["split-stake", wallet_config, sequence] that keeps the authorities and delegation
+    SplitStake {
+        stake_account: Pubkey,
+        lamports: u64,
+    },
+    // source is drained into destination and closed, the stake program decides if the two can be merged
+    MergeStake {
+        destination: Pubkey,
+        source: Pubkey,
+    },
+}
+
+// the proposal a CreateTemplate stores, borsh's derive bounds every field type so a bare Box<ProposalType> field
+// would make the impls of ProposalType require themselves
+pub struct TemplateProposal(pub Box<ProposalType>);
+
+impl BorshSerialize for TemplateProposal {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for TemplateProposal {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(TemplateProposal(Box::new(ProposalType::deserialize(buf)?)))
+    }
+}
+
+impl std::ops::Deref for TemplateProposal {
+    type Target = ProposalType;
+
+    fn deref(&self) -> &ProposalType {
+        &self.0
+    }
+}
+
+impl ProposalType {
+    // the vault a transfer pays out of, 0 is the wallet authority itself
+    pub fn vault(&self) -> u8 {
+        match self {
+            Self::Transfer { vault, .. }
+            | Self::TransferToWallet { vault, .. }
+            | Self::TransferPercent { vault, .. }
+            | Self::SplitPayment { vault, .. } => *vault,
+            _ => 0,
+        }
+    }
+
+    // the tag the proposal is created with, also its borsh discriminant
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::Transfer { .. } => 0,
+            Self::AddOwner { .. } => 1,
+            Self::ChangeProposalLifetime { .. } => 2,
+            Self::TransferSol { .. } => 3,
+            Self::CloseWallet { .. } => 4,
+            Self::SetRecovery { .. } => 5,
+            Self::SetExecutors { .. } => 6,
+            Self::SetRoles { .. } => 7,
+            Self::SetAllowance { .. } => 8,
+            Self::CreateTemplate { .. } => 9,
+            Self::DeleteTemplate { .. } => 10,
+            Self::CreateVault { .. } => 11,
+            Self::EscrowTransfer { .. } => 12,
+            Self::CancelEscrow { .. } => 13,
+            Self::CreateStream { .. } => 14,
+            Self::CancelStream { .. } => 15,
+            Self::ClaimableTransfer { .. } => 16,
+            Self::CreateLookupTable { .. } => 17,
+            Self::ExtendLookupTable { .. } => 18,
+            Self::FreezeLookupTable { .. } => 19,
+            Self::StakePoolDeposit { .. } => 20,
+            Self::StakePoolWithdraw { .. } => 21,
+            Self::RegisterProgram { .. } => 22,
+            Self::UnregisterProgram { .. } => 23,
+            Self::ExecuteInstruction { .. } => 24,
+            Self::CreateRecurringSwap { .. } => 25,
+            Self::CancelRecurringSwap { .. } => 26,
+            Self::ChangeThreshold { .. } => 27,
+            Self::ChangeTimelock { .. } => 28,
+            Self::CancelPendingChange => 29,
+            Self::SetLifetimeBounds { .. } => 30,
+            Self::FreezeAccount { .. } => 31,
+            Self::ThawAccount { .. } => 32,
+            Self::CreateTokenMultisig { .. } => 33,
+            Self::MultisigTransfer { .. } => 34,
+            Self::ChangeOwnerKey { .. } => 35,
+            Self::ChangeRotationDelay { .. } => 36,
+            Self::SetEmergencyPolicy { .. } => 37,
+            Self::SetSpendLimit { .. } => 38,
+            Self::BlacklistAddress { .. } => 39,
+            Self::UnblacklistAddress { .. } => 40,
+            Self::SetMandatoryApprovers { .. } => 41,
+            Self::SetMaxOpenProposals { .. } => 42,
+            Self::SetProposalDeposit { .. } => 43,
+            Self::SetKeeperTip { .. } => 44,
+            Self::TransferToWallet { .. } => 45,
+            Self::TreasuryWithdraw { .. } => 46,
+            Self::TransferPercent { .. } => 47,
+            Self::SplitPayment { .. } => 48,
+            Self::CreateVesting { .. } => 49,
+            Self::Unfreeze => 50,
+            Self::RemoveInactiveOwners { .. } => 51,
+            Self::SetThresholdMode { .. } => 52,
+            Self::SetQuorum { .. } => 53,
+            Self::SetVoteGate { .. } => 54,
+            Self::AddNftOwner { .. } => 55,
+            Self::SetExecutionCallback { .. } => 56,
+            Self::CloseMint { .. } => 57,
+            Self::SetTransferFee { .. } => 58,
+            Self::SetInterestRate { .. } => 59,
+            Self::SetDefaultAccountState { .. } => 60,
+            Self::UpdateMetadata { .. } => 61,
+            Self::SetAndVerifyCollection { .. } => 62,
+            Self::TransferUpdateAuthority { .. } => 63,
+            Self::TransferDomain { .. } => 64,
+            Self::UpdateDomainRecord { .. } => 65,
+            Self::SplitStake { .. } => 66,
+            Self::MergeStake { .. } => 67,
+            Self::SetOptimisticDelay { .. } => 68,
+        }
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
@@ -36,9 +579,71 @@ pub struct WalletConfig {
     pub owners: u8,
     pub owner_identities: [u8; 32],
     pub proposal_lifetime: i64,
+    pub rent_floor: u64,
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    pub last_activity: i64,
+    pub beneficiary: Pubkey,
+    pub inactivity_period: i64,
+    pub authority_bump: u8,
+    pub proposal_count: u64,
+    pub executors: [Pubkey; MAX_EXECUTORS],
+    pub epoch: u64,
+    pub timelock: i64,
+    pub pending_change: PendingChange,
+    pub pending_change_at: i64,
+    pub min_proposal_lifetime: i64,
+    pub max_proposal_lifetime: i64,
+    pub rotation_delay: i64,
+    pub emergency_m: u8,
+    pub emergency_lifetime: i64,
+    // owner ids, like owner_identities
+    pub mandatory_approvers: [u8; 32],
+    // number of events the wallet has emitted, each event is logged with its value
+    pub event_count: u64,
+    // created and not yet closed, expired proposals count until someone closes them
+    pub open_proposals: u16,
+    pub max_open_proposals: u16,
+    pub proposal_deposit: u64,
+    pub keeper_tip: u64,
+    pub treasury_bump: u8,
+    // set by any single owner through EmergencyFreeze, no proposal but Unfreeze executes while it is set
+    pub frozen: bool,
+    // m of n of the current owners if not set, m approvals if set
+    pub fixed_threshold: bool,
+    // in basis points of the owners, 0 if no quorum is required
+    pub quorum: u16,
+    // the default pubkey if votes are not gated
+    pub gate_mint: Pubkey,
+    pub gate_amount: u64,
+    // the default pubkey if no program is called back after executions
+    pub callback_program: Pubkey,
+    // challenge period of proposals from owners with ROLE_OPTIMISTIC, 0 if every proposal is voted on
+    pub optimistic_delay: i64,
+    // vaults, templates, treasury stats, allowances, escrows, streams, claims, vestings and recurring swaps not yet
+    // closed, CloseWallet can not leave any of them behind
+    pub open_accounts: u16,
     pub is_initialized: bool,
 }
 
+impl WalletConfig {
+    // votes a proposal held to m of n needs under the wallet's threshold mode, a fixed count is capped at the
+    // owners so owners leaving can not lock the wallet
+    pub fn threshold(&self, m: u8, n: u8) -> u8 {
+        if self.fixed_threshold {
+            m.min(self.owners)
+        } else {
+            approval_threshold(self.owners, m, n)
+        }
+    }
+
+    // votes that have to be cast for a new proposal to be executed, rounded up
+    pub fn quorum_votes(&self) -> u8 {
+        ((self.owners as u32 * self.quorum as u32 + BASIS_POINTS as u32 - 1) / BASIS_POINTS as u32)
+            as u8
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct WalletAuth {
     pub discriminator: AccountType,
@@ -46,25 +651,541 @@ pub struct WalletAuth {
     pub wallet: Pubkey,
     pub added_time: i64,
     pub id: u8,
+    pub last_active_at: i64,
+    // when the owner last voted, starting at added_time, RemoveInactiveOwners goes by it
+    pub last_vote_at: i64,
+    // activity counters for indexers and governance health checks, carried over by key changes
+    pub proposals_created: u64,
+    pub votes_cast: u64,
+    // owner is then the mint of an NFT, the seat is used by the holder of its token account
+    pub nft_bound: bool,
+    pub roles: u8,
+    pub bump: u8,
     pub is_initialized: bool,
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
-pub struct Proposal {
-    pub discriminator: AccountType,
-    pub wallet: Pubkey,
+pub struct Proposal {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    // the owner, or the holder who signed for an NFT-bound seat, refunds go to it
+    pub proposer: Pubkey,
+    pub sequence: u64,
+    pub bump: u8,
+    pub executed: bool,
+    pub proposal: ProposalType,
+    pub urgent: bool,
+    // held in the account on top of its rent, forfeited to the treasury if no vote beyond the initial ones came in
+    // before expiry
+    pub deposit: u64,
+    pub initial_votes: u8,
+    // also held on top of the rent, paid to an executor that closes the proposal and refunded otherwise
+    pub keeper_tip: u64,
+    // paid for by the wallet authority, whose lamports it returns to when closed
+    pub treasury_funded: bool,
+    // editable by the proposer and not votable until ActivateProposal restarts its lifetime
+    pub draft: bool,
+    // bumped by every EditDraft, AmendProposal and SetContentHash, vote permits name the proposal address so they
+    // also carry it
+    pub revision: u16,
+    // digest of an off-chain document behind the proposal, e.g. of an IPFS CID, all zeroes if there is none
+    pub content_hash: [u8; 32],
+    // checked when the proposal is executed, an approved proposal waits for the price instead of being voted again
+    pub price_condition: PriceCondition,
+    // executable without votes from then on unless an owner objected, 0 if the proposal is voted on, restarted by
+    // every revision
+    pub challenge_ends_at: i64,
+    pub is_initialized: bool,
+}
+
+// the pyth price the proposal can be executed at, the default feed means there is no condition
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Default)]
+pub struct PriceCondition {
+    pub feed: Pubkey,
+    // executable while the price is above threshold, or below it if not set
+    pub above: bool,
+    // in the exponent of the feed, e.g. 200 * 10^8 for $200 on a feed with an exponent of -8
+    pub threshold: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct VoteCount {
+    pub discriminator: AccountType,
+    pub proposed_time: i64,
+    // the policy the proposal was created under, later lifetime, emergency policy or mandatory approver changes do
+    // not apply to open proposals, a transfer out of a vault takes the vault's threshold instead
+    pub expires_at: i64,
+    pub threshold: u8,
+    pub mandatory_approvers: [u8; 32],
+    // every vote is an approval, so the participation is the vote count
+    pub quorum: u8,
+    pub votes: u8,
+    pub vote_record: [u8; 32],
+    pub epoch: u64,
+    pub bump: u8,
+    // set by ExtendProposal, a proposal is only extended once
+    pub extended: bool,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Allowance {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub limit: u64,
+    pub spent: u64,
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SessionKey {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub owner: Pubkey,
+    pub key: Pubkey,
+    pub expires_at: i64,
+    pub scope: u8,
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Template {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub id: u64,
+    pub bump: u8,
+    pub proposal: ProposalType,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Vault {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub index: u8,
+    pub m: u8,
+    pub n: u8,
+    pub authority_bump: u8,
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Escrow {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub sequence: u64,
+    pub mint: Pubkey,
+    pub receive_account: Pubkey,
+    pub amount: u64,
+    pub unlock_time: i64,
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Stream {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub sequence: u64,
+    pub mint: Pubkey,
+    pub receive_account: Pubkey,
+    pub rate: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub withdrawn: u64,
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Claim {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub sequence: u64,
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ProgramRegistry {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    // unused slots hold the default pubkey
+    pub programs: [Pubkey; MAX_REGISTERED_PROGRAMS],
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct RecurringSwap {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub sequence: u64,
+    pub swap_program: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount: u64,
+    pub min_output: u64,
+    pub interval: i64,
+    pub next_swap_at: i64,
+    pub remaining: u64,
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+// an owner's request to move their wallet auth to a new key, completable once effective_at passes
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct KeyRotation {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub payer: Pubkey,
+    pub effective_at: i64,
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Blacklist {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    // unused slots hold the default pubkey
+    pub addresses: [Pubkey; MAX_BLACKLISTED],
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Default)]
+pub enum AuditAction {
+    // an unused slot
+    #[default]
+    None,
+    ProposalExecuted,
+    OwnerLeft,
+    RecoveryClaimed,
+    ChangeApplied,
+    KeyRotated,
+    WalletFrozen,
+}
+
+// kind is the proposal type of an execution or the PendingChange variant of an applied change, subject is the
+// executed proposal, the owner who left, the beneficiary or the new owner key
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Default)]
+pub struct AuditRecord {
+    pub action: AuditAction,
+    pub kind: u8,
+    pub sequence: u64,
+    pub subject: Pubkey,
+    pub timestamp: i64,
+}
+
+// ring buffer of the wallet's most recent membership, execution and config changes, record i is at
+// records[i % AUDIT_LOG_LEN] and next_index counts every record ever appended
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct AuditLog {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub next_index: u64,
+    pub records: [AuditRecord; AUDIT_LOG_LEN],
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Default)]
+pub struct MintTotal {
+    pub mint: Pubkey,
+    pub transferred: u64,
+}
+
+// running totals for treasury dashboards, counted from the stats' creation on, TransferSol is counted under the
+// native mint and unused mint_totals entries have the default mint
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct WalletStats {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub proposals_created: u64,
+    pub proposals_executed: u64,
+    pub proposals_expired: u64,
+    pub last_execution_slot: u64,
+    pub mint_totals: [MintTotal; MAX_TRACKED_MINTS],
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+impl WalletStats {
+    // adds to the mint's total, taking the first free entry for a new mint
+    pub fn add_transferred(&mut self, mint: &Pubkey, amount: u64) {
+        let entry = self
+            .mint_totals
+            .iter_mut()
+            .find(|entry| entry.mint == *mint || entry.mint == Pubkey::default());
+        if let Some(entry) = entry {
+            entry.mint = *mint;
+            entry.transferred = entry.transferred.saturating_add(amount);
+        }
+    }
+}
+
+// tokens of a CreateVesting proposal, held by the vesting's associated token account until released
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Vesting {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub sequence: u64,
+    pub mint: Pubkey,
+    pub receive_account: Pubkey,
+    pub amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+    pub released: u64,
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+// an owner's reason for their vote on a proposal, one per owner and proposal
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct VoteComment {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub proposal: Pubkey,
+    pub owner: Pubkey,
+    pub posted_at: i64,
+    // the used bytes of comment
+    pub len: u8,
+    pub comment: [u8; MAX_COMMENT_LEN],
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+impl Vesting {
+    // the part of amount vested at current_time, released or not
+    pub fn vested(&self, current_time: i64) -> u64 {
+        if current_time < self.cliff_time {
+            return 0;
+        }
+        if current_time >= self.end_time {
+            return self.amount;
+        }
+        let elapsed = (current_time - self.start_time) as u128;
+        let duration = (self.end_time - self.start_time) as u128;
+        (self.amount as u128 * elapsed / duration) as u64
+    }
+}
+
+// outflow of a mint through Transfer and TransferSol within the current windows, looser caps wait in pending
+// until pending_at
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct TreasuryStats {
+    pub discriminator: AccountType,
+    pub wallet: Pubkey,
+    pub mint: Pubkey,
+    pub daily_cap: u64,
+    pub weekly_cap: u64,
+    pub pending_daily_cap: u64,
+    pub pending_weekly_cap: u64,
+    pub pending_at: i64,
+    pub day_start: i64,
+    pub day_spent: u64,
+    pub week_start: i64,
+    pub week_spent: u64,
+    pub bump: u8,
+    pub is_initialized: bool,
+}
+
+// what an owner signs off-chain for SubmitVotePermit, kept readable for air-gapped signers to check before signing
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct VotePermit {
+    pub wallet: Pubkey,
+    pub proposal: Pubkey,
+    pub approve: bool,
+    pub expires_at: i64,
+}
+
+// the instruction data the callback program receives after an execution, with the wallet config and the proposal as
+// its readonly accounts
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ExecutionSummary {
+    pub wallet: Pubkey,
+    pub proposal: Pubkey,
+    pub sequence: u64,
+    pub proposal_type: u8,
+    pub proposer: Pubkey,
+    pub executed_at: i64,
+}
+
+// returned by GetSolBalance, available is what can leave the authority without dropping below its rent floor
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SolBalance {
+    pub balance: u64,
+    pub rent_floor: u64,
+    pub available: u64,
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+}
+
+// returned by GetWalletInfo, threshold is the number of votes a proposal needs with the current owners
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct WalletInfo {
+    pub m: u8,
+    pub n: u8,
+    pub owners: u8,
+    pub threshold: u8,
+    pub proposal_lifetime: i64,
+    pub proposal_count: u64,
+    pub epoch: u64,
+    pub authority: Pubkey,
+    pub executors: [Pubkey; MAX_EXECUTORS],
+    pub beneficiary: Pubkey,
+    pub inactivity_period: i64,
+    pub last_activity: i64,
+    pub emergency_threshold: u8,
+    pub emergency_lifetime: i64,
+    pub event_count: u64,
+    pub open_proposals: u16,
+    pub max_open_proposals: u16,
+    pub proposal_deposit: u64,
+    pub keeper_tip: u64,
+    pub treasury: Pubkey,
+    pub frozen: bool,
+    pub fixed_threshold: bool,
+    pub quorum: u16,
+    pub gate_mint: Pubkey,
+    pub gate_amount: u64,
+    pub callback_program: Pubkey,
+    pub optimistic_delay: i64,
+}
+
+// returned by GetProposalStatus, executable means close_proposal would execute rather than only close it,
+// approved only looks at the votes and an optimistic proposal's unchallenged period, votes_needed and
+// time_remaining saturate at 0, conditional is set for a proposal with a price condition, which is only checked
+// against the feed at execution and keeps it from being reported executable
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ProposalStatus {
+    pub sequence: u64,
     pub proposer: Pubkey,
-    pub proposal: ProposalType,
-    pub is_initialized: bool,
-}
-
-#[derive(BorshSerialize, BorshDeserialize)]
-pub struct VoteCount {
-    pub discriminator: AccountType,
+    pub proposal_type: u8,
     pub proposed_time: i64,
+    pub expires_at: i64,
     pub votes: u8,
+    pub threshold: u8,
     pub vote_record: [u8; 32],
-    pub is_initialized: bool,
+    pub expired: bool,
+    pub stale: bool,
+    pub executable: bool,
+    pub urgent: bool,
+    pub mandatory_approved: bool,
+    pub approved: bool,
+    pub votes_needed: u8,
+    pub time_remaining: i64,
+    pub draft: bool,
+    pub quorum: u8,
+    pub quorum_reached: bool,
+    pub challenge_ends_at: i64,
+    pub conditional: bool,
+}
+
+// returned by ValidateProposal, error is the code of the ProgramError execution would fail with, 0 if none
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ProposalValidation {
+    pub executable: bool,
+    pub error: u64,
+}
+
+pub trait WalletAccount: BorshSerialize + BorshDeserialize + IsInitialized {
+    const ACCOUNT_TYPE: AccountType;
+    const ANCHOR_DISCRIMINATOR: [u8; 8];
+}
+
+impl WalletAccount for WalletConfig {
+    const ACCOUNT_TYPE: AccountType = AccountType::WalletConfig;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [248, 6, 16, 222, 235, 5, 195, 69];
+}
+impl WalletAccount for WalletAuth {
+    const ACCOUNT_TYPE: AccountType = AccountType::WalletAuth;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [131, 111, 99, 219, 98, 243, 255, 179];
+}
+impl WalletAccount for Proposal {
+    const ACCOUNT_TYPE: AccountType = AccountType::Proposal;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [26, 94, 189, 187, 116, 136, 53, 33];
+}
+impl WalletAccount for VoteCount {
+    const ACCOUNT_TYPE: AccountType = AccountType::VoteCount;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [47, 3, 119, 30, 160, 221, 9, 92];
+}
+impl WalletAccount for Allowance {
+    const ACCOUNT_TYPE: AccountType = AccountType::Allowance;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [144, 8, 184, 213, 49, 248, 73, 131];
+}
+impl WalletAccount for SessionKey {
+    const ACCOUNT_TYPE: AccountType = AccountType::SessionKey;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [93, 186, 163, 139, 160, 255, 81, 112];
+}
+impl WalletAccount for Template {
+    const ACCOUNT_TYPE: AccountType = AccountType::Template;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [43, 26, 88, 69, 69, 96, 9, 79];
+}
+impl WalletAccount for Vault {
+    const ACCOUNT_TYPE: AccountType = AccountType::Vault;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [211, 8, 232, 43, 2, 152, 117, 119];
+}
+impl WalletAccount for Escrow {
+    const ACCOUNT_TYPE: AccountType = AccountType::Escrow;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [31, 213, 123, 187, 186, 22, 218, 155];
+}
+impl WalletAccount for Stream {
+    const ACCOUNT_TYPE: AccountType = AccountType::Stream;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [166, 224, 59, 4, 202, 10, 186, 83];
+}
+impl WalletAccount for Claim {
+    const ACCOUNT_TYPE: AccountType = AccountType::Claim;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [155, 70, 22, 176, 123, 215, 246, 102];
+}
+impl WalletAccount for ProgramRegistry {
+    const ACCOUNT_TYPE: AccountType = AccountType::ProgramRegistry;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [104, 149, 117, 95, 105, 140, 29, 64];
+}
+impl WalletAccount for RecurringSwap {
+    const ACCOUNT_TYPE: AccountType = AccountType::RecurringSwap;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [87, 197, 87, 180, 206, 0, 198, 252];
+}
+impl WalletAccount for KeyRotation {
+    const ACCOUNT_TYPE: AccountType = AccountType::KeyRotation;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [98, 157, 255, 187, 227, 47, 81, 140];
+}
+impl WalletAccount for TreasuryStats {
+    const ACCOUNT_TYPE: AccountType = AccountType::TreasuryStats;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [50, 8, 105, 205, 63, 75, 79, 29];
+}
+impl WalletAccount for Blacklist {
+    const ACCOUNT_TYPE: AccountType = AccountType::Blacklist;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [131, 9, 212, 250, 58, 186, 247, 3];
+}
+impl WalletAccount for AuditLog {
+    const ACCOUNT_TYPE: AccountType = AccountType::AuditLog;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [230, 207, 176, 233, 170, 130, 101, 244];
+}
+impl WalletAccount for WalletStats {
+    const ACCOUNT_TYPE: AccountType = AccountType::WalletStats;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [96, 53, 96, 132, 136, 197, 236, 144];
+}
+impl WalletAccount for Vesting {
+    const ACCOUNT_TYPE: AccountType = AccountType::Vesting;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [100, 149, 66, 138, 95, 200, 128, 241];
+}
+impl WalletAccount for VoteComment {
+    const ACCOUNT_TYPE: AccountType = AccountType::VoteComment;
+    const ANCHOR_DISCRIMINATOR: [u8; 8] = [91, 122, 189, 90, 160, 174, 120, 143];
 }
 
 impl IsInitialized for WalletConfig {
@@ -87,15 +1208,96 @@ impl IsInitialized for VoteCount {
         self.is_initialized
     }
 }
+impl IsInitialized for Allowance {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for SessionKey {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for Template {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for Vault {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for Escrow {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for Stream {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for Claim {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for ProgramRegistry {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for RecurringSwap {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for KeyRotation {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for TreasuryStats {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for Blacklist {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for AuditLog {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for WalletStats {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for Vesting {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for VoteComment {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
 
 impl Sealed for WalletConfig {}
 impl Pack for WalletConfig {
-    const LEN: usize = std::mem::size_of::<Self>();
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        self.serialize(&mut &mut dst[..]).unwrap()
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
     }
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        if let Ok(result) = Self::deserialize(&mut &src[..]) {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
             Ok(result)
         } else {
             Err(ProgramError::InvalidAccountData)
@@ -104,12 +1306,13 @@ impl Pack for WalletConfig {
 }
 impl Sealed for WalletAuth {}
 impl Pack for WalletAuth {
-    const LEN: usize = std::mem::size_of::<Self>();
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        self.serialize(&mut &mut dst[..]).unwrap()
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
     }
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        if let Ok(result) = Self::deserialize(&mut &src[..]) {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
             Ok(result)
         } else {
             Err(ProgramError::InvalidAccountData)
@@ -118,12 +1321,13 @@ impl Pack for WalletAuth {
 }
 impl Sealed for Proposal {}
 impl Pack for Proposal {
-    const LEN: usize = std::mem::size_of::<Self>();
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        self.serialize(&mut &mut dst[..]).unwrap()
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
     }
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        if let Ok(result) = Self::deserialize(&mut &src[..]) {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
             Ok(result)
         } else {
             Err(ProgramError::InvalidAccountData)
@@ -132,15 +1336,312 @@ impl Pack for Proposal {
 }
 impl Sealed for VoteCount {}
 impl Pack for VoteCount {
-    const LEN: usize = std::mem::size_of::<Self>();
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for Allowance {}
+impl Pack for Allowance {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for SessionKey {}
+impl Pack for SessionKey {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for Template {}
+impl Pack for Template {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for Vault {}
+impl Pack for Vault {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for Escrow {}
+impl Pack for Escrow {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for Stream {}
+impl Pack for Stream {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for Claim {}
+impl Pack for Claim {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for ProgramRegistry {}
+impl Pack for ProgramRegistry {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for RecurringSwap {}
+impl Pack for RecurringSwap {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for KeyRotation {}
+impl Pack for KeyRotation {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        self.serialize(&mut &mut dst[..]).unwrap()
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
     }
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        if let Ok(result) = Self::deserialize(&mut &src[..]) {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
             Ok(result)
         } else {
             Err(ProgramError::InvalidAccountData)
         }
     }
 }
+impl Sealed for TreasuryStats {}
+impl Pack for TreasuryStats {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for Blacklist {}
+impl Pack for Blacklist {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for AuditLog {}
+impl Pack for AuditLog {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for WalletStats {}
+impl Pack for WalletStats {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for Vesting {}
+impl Pack for Vesting {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+impl Sealed for VoteComment {}
+impl Pack for VoteComment {
+    const LEN: usize = DISCRIMINATOR_LEN + std::mem::size_of::<Self>();
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..DISCRIMINATOR_LEN].copy_from_slice(&Self::ANCHOR_DISCRIMINATOR[..DISCRIMINATOR_LEN]);
+        self.serialize(&mut &mut dst[DISCRIMINATOR_LEN..]).unwrap()
+    }
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(result) = Self::deserialize(&mut &src[DISCRIMINATOR_LEN..]) {
+            Ok(result)
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+
+#[cfg(feature = "anchor")]
+macro_rules! anchor_account {
+    ($($account:ty),*) => {$(
+        impl anchor_lang::Discriminator for $account {
+            const DISCRIMINATOR: [u8; 8] = <$account as WalletAccount>::ANCHOR_DISCRIMINATOR;
+        }
+        impl anchor_lang::AccountSerialize for $account {
+            fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> anchor_lang::Result<()> {
+                writer.write_all(&<$account as WalletAccount>::ANCHOR_DISCRIMINATOR)?;
+                self.serialize(writer)?;
+                Ok(())
+            }
+        }
+        impl anchor_lang::AccountDeserialize for $account {
+            fn try_deserialize(buf: &mut &[u8]) -> anchor_lang::Result<Self> {
+                if buf.len() < DISCRIMINATOR_LEN {
+                    return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorNotFound.into());
+                }
+                if buf[..DISCRIMINATOR_LEN] != <$account as WalletAccount>::ANCHOR_DISCRIMINATOR {
+                    return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+                }
+                Self::try_deserialize_unchecked(buf)
+            }
+            fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_lang::Result<Self> {
+                let mut data = &buf[DISCRIMINATOR_LEN..];
+                Self::deserialize(&mut data)
+                    .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "anchor")]
+anchor_account!(
+    WalletConfig,
+    WalletAuth,
+    Proposal,
+    VoteCount,
+    Allowance,
+    SessionKey,
+    Template,
+    Vault,
+    Escrow,
+    Stream,
+    Claim,
+    ProgramRegistry,
+    RecurringSwap,
+    KeyRotation,
+    TreasuryStats,
+    Blacklist,
+    AuditLog,
+    WalletStats,
+    Vesting,
+    VoteComment
+);
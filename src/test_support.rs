@@ -0,0 +1,381 @@
+// ProgramTest fixtures, a wallet with funded owners and token balances is set up in genesis so tests only
+// script the steps they are about
+//
+// let mut scenario = WalletFixture::new(1, 2).with_owners(3).start().await;
+// let proposal = scenario.propose(0, ProposalType::ChangeProposalLifetime { duration: 600 }).await?;
+// scenario.vote(1, &proposal).await?;
+
+use crate::client::{self, decode_account, find_proposal_address, find_vault_authority_address};
+use crate::processor::process_instruction;
+use crate::state::{ProposalType, VoteCount, WalletAccount, WalletConfig};
+use solana_program_test::{
+    processor, BanksClientError, EbpfVm, InvokeContext, ProgramTest, ProgramTestContext,
+};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    sysvar::clock::Clock,
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{
+    state::{Account as TokenAccount, AccountState, Mint},
+    ID as TOKEN_PROGRAM_ID,
+};
+
+pub const DEFAULT_PROPOSAL_LIFETIME: i64 = 60 * 60;
+pub const DEFAULT_MIN_PROPOSAL_LIFETIME: i64 = 60;
+pub const DEFAULT_MAX_PROPOSAL_LIFETIME: i64 = 30 * 24 * 60 * 60;
+// every owner starts with this much so it can pay for proposals, deposits and tips
+pub const OWNER_LAMPORTS: u64 = 100 * LAMPORTS_PER_SOL;
+
+// what processor! expands to, the runtime crate that names it is not a dependency of the wallet
+pub type BuiltinFunctionWithContext =
+    fn(*mut EbpfVm<InvokeContext<'static>>, u64, u64, u64, u64, u64);
+
+// a token program account holding the packed state
+fn packed_account<T: Pack>(details: &T) -> Account {
+    let mut data = vec![0; T::LEN];
+    details.pack_into_slice(&mut data);
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: TOKEN_PROGRAM_ID,
+        ..Account::default()
+    }
+}
+
+pub struct WalletFixture {
+    m: u8,
+    n: u8,
+    owners: usize,
+    tokens: Vec<(Pubkey, u64)>,
+    programs: Vec<(&'static str, Pubkey, Option<BuiltinFunctionWithContext>)>,
+    accounts: Vec<(Pubkey, Account)>,
+    wallet_config: Option<Keypair>,
+    proposal_lifetime: i64,
+    min_proposal_lifetime: i64,
+    max_proposal_lifetime: i64,
+    program_id: Pubkey,
+}
+
+impl WalletFixture {
+    // a single owner wallet, m and n are the approval fraction passed to CreateWallet
+    pub fn new(m: u8, n: u8) -> Self {
+        WalletFixture {
+            m,
+            n,
+            owners: 1,
+            tokens: Vec::new(),
+            programs: Vec::new(),
+            accounts: Vec::new(),
+            wallet_config: None,
+            proposal_lifetime: DEFAULT_PROPOSAL_LIFETIME,
+            min_proposal_lifetime: DEFAULT_MIN_PROPOSAL_LIFETIME,
+            max_proposal_lifetime: DEFAULT_MAX_PROPOSAL_LIFETIME,
+            program_id: Pubkey::new_unique(),
+        }
+    }
+
+    // owners counts the creator, who is always owners[0]
+    pub fn with_owners(mut self, owners: usize) -> Self {
+        self.owners = owners;
+        self
+    }
+
+    // the mint is created in genesis and amount is held by the wallet's vault 0 token account
+    pub fn with_token(mut self, mint: Pubkey, amount: u64) -> Self {
+        self.tokens.push((mint, amount));
+        self
+    }
+
+    // another program loaded next to the wallet, e.g. an execution callback, processor comes from processor!
+    pub fn with_program(
+        mut self,
+        name: &'static str,
+        program_id: Pubkey,
+        processor: Option<BuiltinFunctionWithContext>,
+    ) -> Self {
+        self.programs.push((name, program_id, processor));
+        self
+    }
+
+    // any other account the test needs in genesis
+    pub fn with_account(mut self, address: Pubkey, account: Account) -> Self {
+        self.accounts.push((address, account));
+        self
+    }
+
+    // the wallet is created at this address instead of a fresh one, so genesis accounts can refer to its PDAs
+    pub fn with_wallet_config(mut self, wallet_config: Keypair) -> Self {
+        self.wallet_config = Some(wallet_config);
+        self
+    }
+
+    pub fn with_lifetime(mut self, proposal_lifetime: i64, min: i64, max: i64) -> Self {
+        self.proposal_lifetime = proposal_lifetime;
+        self.min_proposal_lifetime = min;
+        self.max_proposal_lifetime = max;
+        self
+    }
+
+    pub fn with_program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = program_id;
+        self
+    }
+
+    // the program test, with the accounts of the fixture added, for tests that need more genesis state
+    pub fn program_test(&self, owners: &[Keypair], wallet_config: &Pubkey) -> ProgramTest {
+        let mut program_test = ProgramTest::new(
+            "multisig_wallet",
+            self.program_id,
+            processor!(process_instruction),
+        );
+        for owner in owners.iter() {
+            program_test.add_account(
+                owner.pubkey(),
+                Account {
+                    lamports: OWNER_LAMPORTS,
+                    ..Account::default()
+                },
+            );
+        }
+        let authority = find_vault_authority_address(&self.program_id, wallet_config, 0).0;
+        for (mint, amount) in self.tokens.iter() {
+            let mint_details = Mint {
+                mint_authority: COption::None,
+                supply: *amount,
+                decimals: 0,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            };
+            program_test.add_account(*mint, packed_account(&mint_details));
+            let token_details = TokenAccount {
+                mint: *mint,
+                owner: authority,
+                amount: *amount,
+                delegate: COption::None,
+                state: AccountState::Initialized,
+                is_native: COption::None,
+                delegated_amount: 0,
+                close_authority: COption::None,
+            };
+            program_test.add_account(
+                get_associated_token_address(&authority, mint),
+                packed_account(&token_details),
+            );
+        }
+        for (name, program_id, processor) in self.programs.iter() {
+            program_test.add_program(name, *program_id, *processor);
+        }
+        for (address, account) in self.accounts.iter() {
+            program_test.add_account(*address, account.clone());
+        }
+        program_test
+    }
+
+    // starts the bank and creates the wallet, panics if the fixture is not a valid wallet
+    pub async fn start(self) -> WalletScenario {
+        let owners: Vec<Keypair> = (0..self.owners).map(|_| Keypair::new()).collect();
+        let wallet_config = self
+            .wallet_config
+            .as_ref()
+            .map_or_else(Keypair::new, |wallet_config| wallet_config.insecure_clone());
+        let context = self
+            .program_test(&owners, &wallet_config.pubkey())
+            .start_with_context()
+            .await;
+        let mut scenario = WalletScenario {
+            context,
+            program_id: self.program_id,
+            wallet_config,
+            owners,
+        };
+        let instruction = client::create_wallet(
+            &scenario.program_id,
+            &scenario.owners[0].pubkey(),
+            &scenario.wallet_config.pubkey(),
+            self.m,
+            self.n,
+            scenario.owners[1..]
+                .iter()
+                .map(|owner| owner.pubkey())
+                .collect(),
+            self.proposal_lifetime,
+            self.min_proposal_lifetime,
+            self.max_proposal_lifetime,
+        );
+        let creator = scenario.owners[0].insecure_clone();
+        let wallet_config = scenario.wallet_config.insecure_clone();
+        scenario
+            .process(&[instruction], &[&creator, &wallet_config])
+            .await
+            .expect("fixture wallet could not be created");
+        scenario
+    }
+}
+
+// a started fixture, owners are referred to by their index in owners
+pub struct WalletScenario {
+    pub context: ProgramTestContext,
+    pub program_id: Pubkey,
+    pub wallet_config: Keypair,
+    pub owners: Vec<Keypair>,
+}
+
+impl WalletScenario {
+    // the context payer pays the fee, signers only need to cover what the instructions take from them
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await?;
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    // units the instructions consume when simulated against the current bank, nothing is committed
+    pub async fn compute_units(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<u64, BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await?;
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let simulation = self
+            .context
+            .banks_client
+            .simulate_transaction(transaction)
+            .await?;
+        if let Some(Err(error)) = simulation.result {
+            return Err(error.into());
+        }
+        Ok(simulation
+            .simulation_details
+            .map(|details| details.units_consumed)
+            .unwrap_or(0))
+    }
+
+    pub async fn account<T: WalletAccount>(&mut self, address: &Pubkey) -> Option<T> {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .expect("banks client failed")?;
+        decode_account::<T>(&account.data).ok()
+    }
+
+    pub async fn wallet(&mut self) -> WalletConfig {
+        let wallet_config = self.wallet_config.pubkey();
+        self.account::<WalletConfig>(&wallet_config)
+            .await
+            .expect("fixture wallet is closed")
+    }
+
+    pub async fn votes(&mut self, proposal: &Pubkey) -> Option<VoteCount> {
+        let vote_count = client::find_vote_count_address(
+            &self.program_id,
+            &self.wallet_config.pubkey(),
+            proposal,
+        )
+        .0;
+        self.account::<VoteCount>(&vote_count).await
+    }
+
+    // owner pays for the proposal, its address is returned
+    pub async fn propose(
+        &mut self,
+        owner: usize,
+        proposal: ProposalType,
+    ) -> Result<Pubkey, BanksClientError> {
+        let sequence = self.wallet().await.proposal_count;
+        let wallet_config = self.wallet_config.pubkey();
+        let proposer = self.owners[owner].insecure_clone();
+        let instruction = client::create_proposal(
+            &self.program_id,
+            &proposer.pubkey(),
+            &proposer.pubkey(),
+            &proposer.pubkey(),
+            &wallet_config,
+            sequence,
+            proposal,
+        );
+        self.process(&[instruction], &[&proposer]).await?;
+        Ok(find_proposal_address(&self.program_id, &wallet_config, sequence).0)
+    }
+
+    pub async fn vote(&mut self, owner: usize, proposal: &Pubkey) -> Result<(), BanksClientError> {
+        let voter = self.owners[owner].insecure_clone();
+        let instruction = client::vote(
+            &self.program_id,
+            &voter.pubkey(),
+            &voter.pubkey(),
+            &self.wallet_config.pubkey(),
+            proposal,
+        );
+        self.process(&[instruction], &[&voter]).await
+    }
+
+    // votes with each of owners in turn, stopping at the first failure
+    pub async fn vote_with(
+        &mut self,
+        owners: &[usize],
+        proposal: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        for owner in owners.iter() {
+            self.vote(*owner, proposal).await?;
+        }
+        Ok(())
+    }
+
+    // owner closes the proposal and is refunded, execution_accounts follow the CloseProposal account list
+    pub async fn close(
+        &mut self,
+        owner: usize,
+        proposal: &Pubkey,
+        execution_accounts: Vec<AccountMeta>,
+    ) -> Result<(), BanksClientError> {
+        let closer = self.owners[owner].insecure_clone();
+        let instruction = client::close_proposal(
+            &self.program_id,
+            &closer.pubkey(),
+            &closer.pubkey(),
+            &self.wallet_config.pubkey(),
+            proposal,
+            execution_accounts,
+        );
+        self.process(&[instruction], &[&closer]).await
+    }
+
+    // moves the bank clock forward, e.g. past the expiry of a proposal
+    pub async fn advance_clock(&mut self, seconds: i64) -> Result<(), BanksClientError> {
+        let mut clock = self.context.banks_client.get_sysvar::<Clock>().await?;
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+        Ok(())
+    }
+}
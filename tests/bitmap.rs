@@ -0,0 +1,102 @@
+use multisig_wallet::state::{
+    approval_threshold, clear_bit, count_bits, first_clear_bit, is_bit_set, set_bit,
+};
+use proptest::collection::{btree_set, vec};
+use proptest::prelude::*;
+
+fn map_of(ids: &[u8]) -> [u8; 32] {
+    let mut map = [0u8; 32];
+    for id in ids.iter() {
+        set_bit(&mut map, *id);
+    }
+    map
+}
+
+#[test]
+fn id_zero_is_the_high_bit_of_the_first_byte() {
+    let map = map_of(&[0, 9, 255]);
+    assert_eq!(map[0], 0b1000_0000);
+    assert_eq!(map[1], 0b0100_0000);
+    assert_eq!(map[31], 0b0000_0001);
+}
+
+#[test]
+fn full_map_has_no_free_id() {
+    assert_eq!(first_clear_bit(&[255u8; 32]), None);
+}
+
+proptest! {
+    #[test]
+    fn set_and_clear_round_trip(ids in vec(any::<u8>(), 0..64), id in any::<u8>()) {
+        let before = map_of(&ids);
+        let was_set = is_bit_set(&before, id);
+        let mut map = before;
+        set_bit(&mut map, id);
+        prop_assert!(is_bit_set(&map, id));
+        prop_assert_eq!(count_bits(&map), count_bits(&before) + !was_set as u32);
+        clear_bit(&mut map, id);
+        prop_assert!(!is_bit_set(&map, id));
+        prop_assert_eq!(count_bits(&map), count_bits(&before) - was_set as u32);
+        if !was_set {
+            prop_assert_eq!(map, before);
+        }
+    }
+
+    #[test]
+    fn ids_do_not_collide(a in any::<u8>(), b in any::<u8>()) {
+        prop_assume!(a != b);
+        let mut map = map_of(&[a]);
+        prop_assert!(!is_bit_set(&map, b));
+        set_bit(&mut map, b);
+        clear_bit(&mut map, b);
+        prop_assert!(is_bit_set(&map, a));
+        prop_assert_eq!(count_bits(&map), 1);
+    }
+
+    #[test]
+    fn count_matches_distinct_ids(ids in btree_set(any::<u8>(), 0..256)) {
+        let ids: Vec<u8> = ids.into_iter().collect();
+        prop_assert_eq!(count_bits(&map_of(&ids)) as usize, ids.len());
+    }
+
+    // a new owner gets the lowest id no current owner holds
+    #[test]
+    fn free_id_is_lowest_unused(ids in btree_set(any::<u8>(), 0..256)) {
+        let map = map_of(&ids.iter().copied().collect::<Vec<u8>>());
+        let free = first_clear_bit(&map).unwrap();
+        prop_assert!(!ids.contains(&free));
+        prop_assert!((0..free).all(|id| ids.contains(&id)));
+    }
+
+    // removing an owner and adding another reuses the freed id without touching anyone else
+    #[test]
+    fn removed_id_is_reused(owners in 1usize..=255, removed in any::<u8>()) {
+        prop_assume!((removed as usize) < owners);
+        let ids: Vec<u8> = (0..owners).map(|id| id as u8).collect();
+        let mut map = map_of(&ids);
+        clear_bit(&mut map, removed);
+        let id = first_clear_bit(&map).unwrap();
+        prop_assert_eq!(id, removed);
+        set_bit(&mut map, id);
+        prop_assert_eq!(map, map_of(&ids));
+    }
+
+    #[test]
+    fn threshold_never_exceeds_owners(owners in 1u8..=255, m in 1u8..=255, n in 1u8..=255) {
+        prop_assume!(m <= n);
+        let threshold = approval_threshold(owners, m, n);
+        prop_assert!(threshold <= owners);
+        prop_assert_eq!(threshold as u32, owners as u32 * m as u32 / n as u32);
+        if m == n {
+            prop_assert_eq!(threshold, owners);
+        }
+    }
+
+    #[test]
+    fn threshold_grows_with_owners_and_m(owners in 1u8..255, m in 1u8..255, n in 1u8..=255) {
+        prop_assume!(m < n);
+        let threshold = approval_threshold(owners, m, n);
+        prop_assert!(approval_threshold(owners + 1, m, n) >= threshold);
+        prop_assert!(approval_threshold(owners, m + 1, n) >= threshold);
+    }
+}
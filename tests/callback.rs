@@ -0,0 +1,187 @@
+#![cfg(feature = "test-support")]
+
+use multisig_wallet::client::{
+    close_wallet_accounts, execution_callback_account, find_authority_address,
+    multisig_transfer_accounts,
+};
+use multisig_wallet::state::ProposalType;
+use multisig_wallet::test_support::{WalletFixture, WalletScenario};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError, program_option::COption, program_pack::Pack, pubkey::Pubkey,
+};
+use solana_program_test::{processor, BanksClientError};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+};
+use spl_token::state::{Account as TokenAccount, AccountState, Multisig};
+
+fn accept(_program_id: &Pubkey, _accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+fn reject(_program_id: &Pubkey, _accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    Err(ProgramError::Custom(0))
+}
+
+fn packed<T: Pack>(state: T) -> Account {
+    let mut data = vec![0; T::LEN];
+    T::pack(state, &mut data).unwrap();
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: spl_token::ID,
+        ..Account::default()
+    }
+}
+
+fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    packed(TokenAccount {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    })
+}
+
+async fn set_callback(scenario: &mut WalletScenario, callback: Pubkey) {
+    let proposal = scenario
+        .propose(0, ProposalType::SetExecutionCallback { program: callback })
+        .await
+        .unwrap();
+    scenario.close(0, &proposal, vec![]).await.unwrap();
+    assert_eq!(scenario.wallet().await.callback_program, callback);
+}
+
+// closes the wallet with no token accounts to sweep
+async fn close_wallet(
+    scenario: &mut WalletScenario,
+    callback: Pubkey,
+) -> Result<(), BanksClientError> {
+    let program_id = scenario.program_id;
+    let wallet_config = scenario.wallet_config.pubkey();
+    let destination = Pubkey::new_unique();
+    let proposal = scenario
+        .propose(0, ProposalType::CloseWallet { destination })
+        .await?;
+    let owners: Vec<Pubkey> = scenario.owners.iter().map(|owner| owner.pubkey()).collect();
+    let mut accounts = vec![execution_callback_account(&callback)];
+    accounts.extend(close_wallet_accounts(
+        &program_id,
+        &wallet_config,
+        &destination,
+        &owners,
+        &[],
+        &[],
+    ));
+    scenario.close(0, &proposal, accounts).await
+}
+
+#[tokio::test]
+async fn callback_runs_after_close_wallet() {
+    let callback = Pubkey::new_unique();
+    let mut scenario = WalletFixture::new(1, 2)
+        .with_owners(2)
+        .with_program("accept", callback, processor!(accept))
+        .start()
+        .await;
+    set_callback(&mut scenario, callback).await;
+    close_wallet(&mut scenario, callback).await.unwrap();
+    let wallet_config = scenario.wallet_config.pubkey();
+    assert!(scenario
+        .context
+        .banks_client
+        .get_account(wallet_config)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn failing_callback_keeps_wallet_open() {
+    let callback = Pubkey::new_unique();
+    let mut scenario = WalletFixture::new(1, 2)
+        .with_owners(2)
+        .with_program("reject", callback, processor!(reject))
+        .start()
+        .await;
+    set_callback(&mut scenario, callback).await;
+    assert!(close_wallet(&mut scenario, callback).await.is_err());
+    assert_eq!(scenario.wallet().await.owners, 2);
+}
+
+#[tokio::test]
+async fn callback_runs_after_multisig_transfer() {
+    let program_id = Pubkey::new_unique();
+    let wallet_config = Keypair::new();
+    let authority = find_authority_address(&program_id, &wallet_config.pubkey()).0;
+    let callback = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let multisig = Pubkey::new_unique();
+    let source = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+    let mut signers = [Pubkey::default(); 11];
+    signers[0] = authority;
+    let mut scenario = WalletFixture::new(1, 2)
+        .with_owners(2)
+        .with_program_id(program_id)
+        .with_wallet_config(wallet_config.insecure_clone())
+        .with_token(mint, 100)
+        .with_account(
+            multisig,
+            packed(Multisig {
+                m: 1,
+                n: 1,
+                is_initialized: true,
+                signers,
+            }),
+        )
+        .with_account(source, token_account(mint, multisig, 100))
+        .with_account(destination, token_account(mint, Pubkey::new_unique(), 0))
+        .with_program("accept", callback, processor!(accept))
+        .start()
+        .await;
+    set_callback(&mut scenario, callback).await;
+
+    let proposal = scenario
+        .propose(
+            0,
+            ProposalType::MultisigTransfer {
+                multisig,
+                token_mint: mint,
+                receive_account: destination,
+                amount: 40,
+            },
+        )
+        .await
+        .unwrap();
+    let mut accounts = vec![execution_callback_account(&callback)];
+    accounts.extend(multisig_transfer_accounts(
+        &program_id,
+        &wallet_config.pubkey(),
+        &multisig,
+        &mint,
+        &source,
+        &destination,
+        &[],
+    ));
+    scenario.close(0, &proposal, accounts).await.unwrap();
+    let destination_account = scenario
+        .context
+        .banks_client
+        .get_account(destination)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        TokenAccount::unpack(&destination_account.data)
+            .unwrap()
+            .amount,
+        40
+    );
+}
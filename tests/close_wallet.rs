@@ -0,0 +1,113 @@
+#![cfg(feature = "test-support")]
+
+use multisig_wallet::client::{close_wallet_accounts, find_template_address};
+use multisig_wallet::state::{ProposalType, TemplateProposal};
+use multisig_wallet::test_support::{WalletFixture, WalletScenario};
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey, system_program};
+use solana_sdk::signature::Signer;
+
+async fn propose_close(scenario: &mut WalletScenario, destination: Pubkey) -> Pubkey {
+    scenario
+        .propose(0, ProposalType::CloseWallet { destination })
+        .await
+        .unwrap()
+}
+
+fn close_accounts(
+    scenario: &WalletScenario,
+    destination: &Pubkey,
+    open_accounts: &[Pubkey],
+) -> Vec<AccountMeta> {
+    let owners: Vec<Pubkey> = scenario.owners.iter().map(|owner| owner.pubkey()).collect();
+    close_wallet_accounts(
+        &scenario.program_id,
+        &scenario.wallet_config.pubkey(),
+        destination,
+        &owners,
+        open_accounts,
+        &[],
+    )
+}
+
+async fn wallet_exists(scenario: &mut WalletScenario) -> bool {
+    let wallet_config = scenario.wallet_config.pubkey();
+    scenario
+        .context
+        .banks_client
+        .get_account(wallet_config)
+        .await
+        .unwrap()
+        .is_some()
+}
+
+#[tokio::test]
+async fn refuses_to_close_with_another_proposal_open() {
+    let mut scenario = WalletFixture::new(1, 2).with_owners(2).start().await;
+    let destination = Pubkey::new_unique();
+    let other = scenario
+        .propose(0, ProposalType::ChangeProposalLifetime { duration: 600 })
+        .await
+        .unwrap();
+    let proposal = propose_close(&mut scenario, destination).await;
+    let accounts = close_accounts(&scenario, &destination, &[]);
+    assert!(scenario
+        .close(0, &proposal, accounts.clone())
+        .await
+        .is_err());
+    assert!(wallet_exists(&mut scenario).await);
+
+    scenario.close(0, &other, vec![]).await.unwrap();
+    scenario.close(0, &proposal, accounts).await.unwrap();
+    assert!(!wallet_exists(&mut scenario).await);
+}
+
+#[tokio::test]
+async fn closes_open_accounts_of_the_wallet() {
+    let mut scenario = WalletFixture::new(1, 2).with_owners(2).start().await;
+    let wallet_config = scenario.wallet_config.pubkey();
+    let template = find_template_address(&scenario.program_id, &wallet_config, 7).0;
+    let create = scenario
+        .propose(
+            0,
+            ProposalType::CreateTemplate {
+                id: 7,
+                template: TemplateProposal(Box::new(ProposalType::ChangeProposalLifetime {
+                    duration: 600,
+                })),
+            },
+        )
+        .await
+        .unwrap();
+    let payer = scenario.owners[0].pubkey();
+    scenario
+        .close(
+            0,
+            &create,
+            vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new(template, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(scenario.wallet().await.open_accounts, 1);
+
+    let destination = Pubkey::new_unique();
+    let proposal = propose_close(&mut scenario, destination).await;
+    // the template has to be passed to be closed along with the wallet
+    let accounts = close_accounts(&scenario, &destination, &[]);
+    assert!(scenario.close(0, &proposal, accounts).await.is_err());
+    assert!(wallet_exists(&mut scenario).await);
+
+    let accounts = close_accounts(&scenario, &destination, &[template]);
+    scenario.close(0, &proposal, accounts).await.unwrap();
+    assert!(!wallet_exists(&mut scenario).await);
+    assert!(scenario
+        .context
+        .banks_client
+        .get_account(template)
+        .await
+        .unwrap()
+        .is_none());
+}
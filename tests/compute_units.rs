@@ -0,0 +1,161 @@
+#![cfg(feature = "test-support")]
+
+// compute unit budgets, each instruction is simulated at every owner count and fails the run once it
+// needs more than its budget, lower a budget after an optimization so the gain can not silently regress
+//
+// cargo test --features test-support --test compute_units -- --nocapture prints the measured units
+
+use multisig_wallet::client;
+use multisig_wallet::state::ProposalType;
+use multisig_wallet::test_support::{WalletFixture, WalletScenario};
+use solana_sdk::signature::{Keypair, Signer};
+
+// a create wallet transaction carries every owner twice, larger wallets no longer fit in one
+const OWNER_COUNTS: [usize; 3] = [1, 4, 8];
+
+// budgets are base + per_owner * owners
+const CREATE_WALLET_BUDGET: (u64, u64) = (20_000, 12_000);
+const CREATE_PROPOSAL_BUDGET: (u64, u64) = (40_000, 0);
+const VOTE_BUDGET: (u64, u64) = (15_000, 0);
+const CLOSE_PROPOSAL_BUDGET: (u64, u64) = (35_000, 0);
+const DEPOSIT_SOL_BUDGET: (u64, u64) = (10_000, 0);
+
+fn check_budget(instruction: &str, owners: usize, units: u64, budget: (u64, u64)) {
+    let budget = budget.0 + budget.1 * owners as u64;
+    println!("{instruction:<16} owners {owners:>3}: {units:>7} units, budget {budget:>7}");
+    assert!(
+        units <= budget,
+        "{instruction} with {owners} owners used {units} compute units, over its budget of {budget}"
+    );
+}
+
+// every owner has to vote for a wallet with m == n
+async fn wallet(owners: usize) -> WalletScenario {
+    WalletFixture::new(1, 1).with_owners(owners).start().await
+}
+
+#[tokio::test]
+async fn create_wallet_within_budget() {
+    for owners in OWNER_COUNTS {
+        let mut scenario = wallet(owners).await;
+        let creator = scenario.owners[0].insecure_clone();
+        let wallet_config = Keypair::new();
+        let instruction = client::create_wallet(
+            &scenario.program_id,
+            &creator.pubkey(),
+            &wallet_config.pubkey(),
+            1,
+            1,
+            scenario.owners[1..]
+                .iter()
+                .map(|owner| owner.pubkey())
+                .collect(),
+            3600,
+            60,
+            86400,
+        );
+        let units = scenario
+            .compute_units(&[instruction], &[&creator, &wallet_config])
+            .await
+            .unwrap();
+        check_budget("CreateWallet", owners, units, CREATE_WALLET_BUDGET);
+    }
+}
+
+#[tokio::test]
+async fn create_proposal_within_budget() {
+    for owners in OWNER_COUNTS {
+        let mut scenario = wallet(owners).await;
+        let proposer = scenario.owners[0].insecure_clone();
+        let wallet_config = scenario.wallet_config.pubkey();
+        let sequence = scenario.wallet().await.proposal_count;
+        let instruction = client::create_proposal(
+            &scenario.program_id,
+            &proposer.pubkey(),
+            &proposer.pubkey(),
+            &proposer.pubkey(),
+            &wallet_config,
+            sequence,
+            ProposalType::ChangeProposalLifetime { duration: 600 },
+        );
+        let units = scenario
+            .compute_units(&[instruction], &[&proposer])
+            .await
+            .unwrap();
+        check_budget("CreateProposal", owners, units, CREATE_PROPOSAL_BUDGET);
+    }
+}
+
+#[tokio::test]
+async fn vote_within_budget() {
+    // a single owner votes by proposing, there is no vote left to measure
+    for owners in OWNER_COUNTS.into_iter().filter(|owners| *owners > 1) {
+        let mut scenario = wallet(owners).await;
+        let proposal = scenario
+            .propose(0, ProposalType::ChangeProposalLifetime { duration: 600 })
+            .await
+            .unwrap();
+        // the last vote is measured, with every other owner already recorded, the proposer's when proposing
+        let voters: Vec<usize> = (1..owners - 1).collect();
+        scenario.vote_with(&voters, &proposal).await.unwrap();
+        let voter = scenario.owners[owners - 1].insecure_clone();
+        let instruction = client::vote(
+            &scenario.program_id,
+            &voter.pubkey(),
+            &voter.pubkey(),
+            &scenario.wallet_config.pubkey(),
+            &proposal,
+        );
+        let units = scenario
+            .compute_units(&[instruction], &[&voter])
+            .await
+            .unwrap();
+        check_budget("Vote", owners, units, VOTE_BUDGET);
+    }
+}
+
+#[tokio::test]
+async fn close_proposal_within_budget() {
+    for owners in OWNER_COUNTS {
+        let mut scenario = wallet(owners).await;
+        let proposal = scenario
+            .propose(0, ProposalType::ChangeProposalLifetime { duration: 600 })
+            .await
+            .unwrap();
+        let voters: Vec<usize> = (1..owners).collect();
+        scenario.vote_with(&voters, &proposal).await.unwrap();
+        let closer = scenario.owners[0].insecure_clone();
+        let instruction = client::close_proposal(
+            &scenario.program_id,
+            &closer.pubkey(),
+            &closer.pubkey(),
+            &scenario.wallet_config.pubkey(),
+            &proposal,
+            Vec::new(),
+        );
+        let units = scenario
+            .compute_units(&[instruction], &[&closer])
+            .await
+            .unwrap();
+        check_budget("CloseProposal", owners, units, CLOSE_PROPOSAL_BUDGET);
+    }
+}
+
+#[tokio::test]
+async fn deposit_sol_within_budget() {
+    for owners in OWNER_COUNTS {
+        let mut scenario = wallet(owners).await;
+        let depositor = scenario.owners[0].insecure_clone();
+        let instruction = client::deposit_sol(
+            &scenario.program_id,
+            &depositor.pubkey(),
+            &scenario.wallet_config.pubkey(),
+            1_000_000,
+        );
+        let units = scenario
+            .compute_units(&[instruction], &[&depositor])
+            .await
+            .unwrap();
+        check_budget("DepositSol", owners, units, DEPOSIT_SOL_BUDGET);
+    }
+}
@@ -0,0 +1,313 @@
+#![cfg(feature = "test-support")]
+
+use multisig_wallet::client::{self, find_wallet_auth_address, seat_holder_account};
+use multisig_wallet::state::{Proposal, ProposalType, WalletAuth};
+use multisig_wallet::test_support::{WalletFixture, WalletScenario, OWNER_LAMPORTS};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+};
+use solana_program_test::BanksClientError;
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+fn packed<T: Pack>(state: T) -> Account {
+    let mut data = vec![0; T::LEN];
+    T::pack(state, &mut data).unwrap();
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: spl_token::ID,
+        ..Account::default()
+    }
+}
+
+// a fixture whose holders each hold one token of mint in their associated token account, mint_authority is what
+// could mint more after the seat is bound
+fn held_mint(mint: Pubkey, mint_authority: COption<Pubkey>, holders: &[&Keypair]) -> WalletFixture {
+    let mut fixture = WalletFixture::new(1, 2).with_owners(2).with_account(
+        mint,
+        packed(Mint {
+            mint_authority,
+            supply: 1,
+            decimals: 0,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }),
+    );
+    for holder in holders.iter() {
+        fixture = fixture
+            .with_account(
+                holder.pubkey(),
+                Account {
+                    lamports: OWNER_LAMPORTS,
+                    ..Account::default()
+                },
+            )
+            .with_account(
+                get_associated_token_address(&holder.pubkey(), &mint),
+                packed(TokenAccount {
+                    mint,
+                    owner: holder.pubkey(),
+                    amount: 1,
+                    delegate: COption::None,
+                    state: AccountState::Initialized,
+                    is_native: COption::None,
+                    delegated_amount: 0,
+                    close_authority: COption::None,
+                }),
+            );
+    }
+    fixture
+}
+
+// the session key account client helpers append for a user signing for another owner is the holder's token account
+// for an NFT-bound seat
+fn as_seat_holder(mut instruction: Instruction, holder: &Keypair, mint: &Pubkey) -> Instruction {
+    *instruction.accounts.last_mut().unwrap() = seat_holder_account(&holder.pubkey(), mint);
+    instruction
+}
+
+async fn propose_as_holder(
+    scenario: &mut WalletScenario,
+    holder: &Keypair,
+    mint: &Pubkey,
+    proposal: ProposalType,
+    draft: bool,
+) -> Result<Pubkey, BanksClientError> {
+    let sequence = scenario.wallet().await.proposal_count;
+    let wallet_config = scenario.wallet_config.pubkey();
+    let instruction = if draft {
+        client::create_draft_proposal(
+            &scenario.program_id,
+            &holder.pubkey(),
+            mint,
+            &holder.pubkey(),
+            &wallet_config,
+            sequence,
+            proposal,
+            false,
+        )
+    } else {
+        client::create_proposal(
+            &scenario.program_id,
+            &holder.pubkey(),
+            mint,
+            &holder.pubkey(),
+            &wallet_config,
+            sequence,
+            proposal,
+        )
+    };
+    let instruction = as_seat_holder(instruction, holder, mint);
+    scenario.process(&[instruction], &[holder]).await?;
+    Ok(client::find_proposal_address(&scenario.program_id, &wallet_config, sequence).0)
+}
+
+// owners[0] proposes, which approves it, and pays for the seat of mint
+async fn add_nft_owner(
+    scenario: &mut WalletScenario,
+    mint: Pubkey,
+) -> Result<Pubkey, BanksClientError> {
+    let wallet_auth = find_wallet_auth_address(
+        &scenario.program_id,
+        &scenario.wallet_config.pubkey(),
+        &mint,
+    )
+    .0;
+    let proposal = scenario
+        .propose(0, ProposalType::AddNftOwner { mint })
+        .await?;
+    scenario
+        .close(
+            0,
+            &proposal,
+            vec![
+                AccountMeta::new(scenario.owners[0].pubkey(), true),
+                AccountMeta::new(wallet_auth, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(mint, false),
+            ],
+        )
+        .await?;
+    Ok(wallet_auth)
+}
+
+#[tokio::test]
+async fn binds_seat_to_nft_mint() {
+    let mint = Pubkey::new_unique();
+    let mut scenario = WalletFixture::new(1, 2)
+        .with_owners(2)
+        .with_token(mint, 1)
+        .start()
+        .await;
+    let wallet_auth = add_nft_owner(&mut scenario, mint).await.unwrap();
+    let seat = scenario.account::<WalletAuth>(&wallet_auth).await.unwrap();
+    assert!(seat.nft_bound);
+    assert_eq!(scenario.wallet().await.owners, 3);
+}
+
+#[tokio::test]
+async fn rejects_seat_for_fungible_mint() {
+    let mint = Pubkey::new_unique();
+    let mut scenario = WalletFixture::new(1, 2)
+        .with_owners(2)
+        .with_token(mint, 1_000)
+        .start()
+        .await;
+    assert!(add_nft_owner(&mut scenario, mint).await.is_err());
+    assert_eq!(scenario.wallet().await.owners, 2);
+}
+
+#[tokio::test]
+async fn rejects_seat_for_mint_with_live_authority() {
+    let mint = Pubkey::new_unique();
+    let holder = Keypair::new();
+    // a second token the mint authority could mint once the seat is bound
+    let second_holder = Keypair::new();
+    let mut scenario = held_mint(
+        mint,
+        COption::Some(Pubkey::new_unique()),
+        &[&holder, &second_holder],
+    )
+    .start()
+    .await;
+    assert!(add_nft_owner(&mut scenario, mint).await.is_err());
+
+    let proposal = scenario
+        .propose(0, ProposalType::ChangeProposalLifetime { duration: 600 })
+        .await
+        .unwrap();
+    let instruction = as_seat_holder(
+        client::vote(
+            &scenario.program_id,
+            &second_holder.pubkey(),
+            &mint,
+            &scenario.wallet_config.pubkey(),
+            &proposal,
+        ),
+        &second_holder,
+        &mint,
+    );
+    assert!(scenario
+        .process(&[instruction], &[&second_holder])
+        .await
+        .is_err());
+    assert!(propose_as_holder(
+        &mut scenario,
+        &second_holder,
+        &mint,
+        ProposalType::ChangeProposalLifetime { duration: 900 },
+        false,
+    )
+    .await
+    .is_err());
+}
+
+#[tokio::test]
+async fn holder_proposes_and_edits_own_draft() {
+    let mint = Pubkey::new_unique();
+    let holder = Keypair::new();
+    let mut scenario = held_mint(mint, COption::None, &[&holder]).start().await;
+    add_nft_owner(&mut scenario, mint).await.unwrap();
+
+    let proposal = propose_as_holder(
+        &mut scenario,
+        &holder,
+        &mint,
+        ProposalType::ChangeProposalLifetime { duration: 600 },
+        true,
+    )
+    .await
+    .unwrap();
+    let proposal_details = scenario.account::<Proposal>(&proposal).await.unwrap();
+    assert_eq!(proposal_details.proposer, holder.pubkey());
+
+    let instruction = client::edit_draft(
+        &scenario.program_id,
+        &holder.pubkey(),
+        &holder.pubkey(),
+        &scenario.wallet_config.pubkey(),
+        proposal_details.sequence,
+        ProposalType::ChangeProposalLifetime { duration: 900 },
+    );
+    scenario.process(&[instruction], &[&holder]).await.unwrap();
+    let proposal_details = scenario.account::<Proposal>(&proposal).await.unwrap();
+    assert_eq!(proposal_details.revision, 1);
+}
+
+#[tokio::test]
+async fn holder_votes_with_own_gate_tokens() {
+    let mint = Pubkey::new_unique();
+    let gate_mint = Pubkey::new_unique();
+    let holder = Keypair::new();
+    let mut scenario = held_mint(mint, COption::None, &[&holder])
+        .with_account(
+            gate_mint,
+            packed(Mint {
+                mint_authority: COption::None,
+                supply: 10,
+                decimals: 0,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            }),
+        )
+        .with_account(
+            get_associated_token_address(&holder.pubkey(), &gate_mint),
+            packed(TokenAccount {
+                mint: gate_mint,
+                owner: holder.pubkey(),
+                amount: 10,
+                delegate: COption::None,
+                state: AccountState::Initialized,
+                is_native: COption::None,
+                delegated_amount: 0,
+                close_authority: COption::None,
+            }),
+        )
+        .start()
+        .await;
+    add_nft_owner(&mut scenario, mint).await.unwrap();
+    let gate = scenario
+        .propose(
+            0,
+            ProposalType::SetVoteGate {
+                mint: gate_mint,
+                amount: 10,
+            },
+        )
+        .await
+        .unwrap();
+    scenario.vote(1, &gate).await.unwrap();
+    scenario.close(0, &gate, vec![]).await.unwrap();
+
+    let proposal = scenario
+        .propose(0, ProposalType::ChangeProposalLifetime { duration: 600 })
+        .await
+        .unwrap();
+    // the gate tokens are looked up for the holder, the seat's mint owns none
+    let mut instruction = as_seat_holder(
+        client::vote(
+            &scenario.program_id,
+            &holder.pubkey(),
+            &mint,
+            &scenario.wallet_config.pubkey(),
+            &proposal,
+        ),
+        &holder,
+        &mint,
+    );
+    instruction
+        .accounts
+        .push(client::vote_gate_account(&holder.pubkey(), &gate_mint));
+    scenario.process(&[instruction], &[&holder]).await.unwrap();
+    assert_eq!(scenario.votes(&proposal).await.unwrap().votes, 1);
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "test-support")]
+
+use multisig_wallet::client::{self, find_wallet_auth_address};
+use multisig_wallet::state::{Proposal, ProposalType, ROLE_ALL, ROLE_OPTIMISTIC};
+use multisig_wallet::test_support::{
+    WalletFixture, WalletScenario, DEFAULT_MIN_PROPOSAL_LIFETIME, DEFAULT_PROPOSAL_LIFETIME,
+};
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::Signer;
+
+// below the fixture's emergency lifetime, which defaults to the minimum proposal lifetime
+const OPTIMISTIC_DELAY: i64 = 30;
+
+// owners[0] holds the optimistic role and has just proposed, the sequence of the proposal is returned with it
+async fn optimistic_proposal() -> (WalletScenario, Pubkey, u64) {
+    let mut scenario = WalletFixture::new(1, 3).with_owners(3).start().await;
+    let delay = scenario
+        .propose(
+            0,
+            ProposalType::SetOptimisticDelay {
+                delay: OPTIMISTIC_DELAY,
+            },
+        )
+        .await
+        .unwrap();
+    scenario.close(0, &delay, vec![]).await.unwrap();
+
+    let user = scenario.owners[0].pubkey();
+    let wallet_auth = find_wallet_auth_address(
+        &scenario.program_id,
+        &scenario.wallet_config.pubkey(),
+        &user,
+    )
+    .0;
+    let roles = scenario
+        .propose(
+            0,
+            ProposalType::SetRoles {
+                user,
+                roles: ROLE_ALL | ROLE_OPTIMISTIC,
+            },
+        )
+        .await
+        .unwrap();
+    scenario
+        .close(0, &roles, vec![AccountMeta::new(wallet_auth, false)])
+        .await
+        .unwrap();
+
+    let sequence = scenario.wallet().await.proposal_count;
+    let proposal = scenario
+        .propose(0, ProposalType::ChangeProposalLifetime { duration: 600 })
+        .await
+        .unwrap();
+    (scenario, proposal, sequence)
+}
+
+async fn object(
+    scenario: &mut WalletScenario,
+    owner: usize,
+    sequence: u64,
+) -> Result<(), BanksClientError> {
+    let owner = scenario.owners[owner].insecure_clone();
+    let instruction = client::object_proposal(
+        &scenario.program_id,
+        &owner.pubkey(),
+        &scenario.wallet_config.pubkey(),
+        sequence,
+    );
+    scenario.process(&[instruction], &[&owner]).await
+}
+
+#[tokio::test]
+async fn rejects_delay_outliving_emergency_lifetime() {
+    let mut scenario = WalletFixture::new(1, 2).with_owners(2).start().await;
+    assert!(scenario
+        .propose(
+            0,
+            ProposalType::SetOptimisticDelay {
+                delay: DEFAULT_MIN_PROPOSAL_LIFETIME,
+            },
+        )
+        .await
+        .is_err());
+    scenario
+        .propose(
+            0,
+            ProposalType::SetOptimisticDelay {
+                delay: OPTIMISTIC_DELAY,
+            },
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn objection_ends_challenge_period() {
+    let (mut scenario, proposal, sequence) = optimistic_proposal().await;
+    let proposal_details = scenario.account::<Proposal>(&proposal).await.unwrap();
+    assert_ne!(proposal_details.challenge_ends_at, 0);
+    object(&mut scenario, 1, sequence).await.unwrap();
+    let proposal_details = scenario.account::<Proposal>(&proposal).await.unwrap();
+    assert_eq!(proposal_details.challenge_ends_at, 0);
+}
+
+#[tokio::test]
+async fn rejects_objection_after_challenge_period() {
+    let (mut scenario, _, sequence) = optimistic_proposal().await;
+    scenario.advance_clock(OPTIMISTIC_DELAY).await.unwrap();
+    assert!(object(&mut scenario, 1, sequence).await.is_err());
+}
+
+#[tokio::test]
+async fn rejects_objection_to_expired_proposal() {
+    let (mut scenario, _, sequence) = optimistic_proposal().await;
+    scenario
+        .advance_clock(DEFAULT_PROPOSAL_LIFETIME - 10)
+        .await
+        .unwrap();
+    // a late change restarts the challenge period past the proposal's expiry
+    let proposer = scenario.owners[0].insecure_clone();
+    let instruction = client::set_content_hash(
+        &scenario.program_id,
+        &proposer.pubkey(),
+        &scenario.wallet_config.pubkey(),
+        sequence,
+        [7; 32],
+    );
+    scenario
+        .process(&[instruction], &[&proposer])
+        .await
+        .unwrap();
+    scenario.advance_clock(20).await.unwrap();
+    assert!(object(&mut scenario, 1, sequence).await.is_err());
+}
+
+#[tokio::test]
+async fn rejects_objection_from_older_epoch() {
+    let (mut scenario, _, sequence) = optimistic_proposal().await;
+    let leaving = scenario.owners[2].insecure_clone();
+    let instruction = client::give_up_ownership(
+        &scenario.program_id,
+        &leaving.pubkey(),
+        &scenario.wallet_config.pubkey(),
+        &leaving.pubkey(),
+        &[],
+    );
+    scenario.process(&[instruction], &[&leaving]).await.unwrap();
+    assert!(object(&mut scenario, 1, sequence).await.is_err());
+}
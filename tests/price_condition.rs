@@ -0,0 +1,109 @@
+#![cfg(feature = "test-support")]
+
+use multisig_wallet::client::{price_feed_account, set_price_condition};
+use multisig_wallet::processor::oracle::PYTH_PROGRAM_ID;
+use multisig_wallet::state::{PendingChange, PriceCondition, Proposal, ProposalType};
+use multisig_wallet::test_support::{WalletFixture, WalletScenario};
+use solana_program::{clock::Clock, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    signature::Signer,
+};
+
+// a trading pyth v2 price account with only the fields the program reads filled in
+async fn set_price_feed(
+    scenario: &mut WalletScenario,
+    feed: &Pubkey,
+    owner: Pubkey,
+    price: i64,
+    age: i64,
+) {
+    let now = scenario
+        .context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+    let mut data = vec![0; 3312];
+    data[0..4].copy_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+    data[8..12].copy_from_slice(&3u32.to_le_bytes());
+    data[96..104].copy_from_slice(&(now - age).to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[224..228].copy_from_slice(&1u32.to_le_bytes());
+    let account = Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner,
+        ..Account::default()
+    };
+    scenario
+        .context
+        .set_account(feed, &AccountSharedData::from(account));
+}
+
+// an approved proposal executable while the feed's price is above 100
+async fn conditional_proposal(scenario: &mut WalletScenario, feed: Pubkey) -> Pubkey {
+    let proposal = scenario
+        .propose(0, ProposalType::ChangeProposalLifetime { duration: 900 })
+        .await
+        .unwrap();
+    let sequence = scenario.wallet().await.proposal_count - 1;
+    let proposer = scenario.owners[0].insecure_clone();
+    let instruction = set_price_condition(
+        &scenario.program_id,
+        &proposer.pubkey(),
+        &scenario.wallet_config.pubkey(),
+        sequence,
+        PriceCondition {
+            feed,
+            above: true,
+            threshold: 100,
+        },
+    );
+    scenario
+        .process(&[instruction], &[&proposer])
+        .await
+        .unwrap();
+    scenario.vote(1, &proposal).await.unwrap();
+    proposal
+}
+
+#[tokio::test]
+async fn rejects_price_feed_not_owned_by_pyth() {
+    let mut scenario = WalletFixture::new(2, 3).with_owners(3).start().await;
+    let feed = Pubkey::new_unique();
+    let proposal = conditional_proposal(&mut scenario, feed).await;
+    set_price_feed(&mut scenario, &feed, Pubkey::new_unique(), 200, 0).await;
+    assert!(scenario
+        .close(0, &proposal, vec![price_feed_account(&feed)])
+        .await
+        .is_err());
+    assert!(scenario.account::<Proposal>(&proposal).await.is_some());
+
+    set_price_feed(&mut scenario, &feed, PYTH_PROGRAM_ID, 200, 0).await;
+    scenario
+        .close(0, &proposal, vec![price_feed_account(&feed)])
+        .await
+        .unwrap();
+    // the new lifetime waits out the wallet's timelock
+    assert!(
+        scenario.wallet().await.pending_change == PendingChange::ProposalLifetime { duration: 900 }
+    );
+}
+
+#[tokio::test]
+async fn rejects_stale_price_feed() {
+    let mut scenario = WalletFixture::new(2, 3).with_owners(3).start().await;
+    let feed = Pubkey::new_unique();
+    let proposal = conditional_proposal(&mut scenario, feed).await;
+    set_price_feed(&mut scenario, &feed, PYTH_PROGRAM_ID, 200, 120).await;
+    assert!(scenario
+        .close(0, &proposal, vec![price_feed_account(&feed)])
+        .await
+        .is_err());
+    assert!(scenario.account::<Proposal>(&proposal).await.is_some());
+    assert!(
+        scenario.wallet().await.pending_change != PendingChange::ProposalLifetime { duration: 900 }
+    );
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "test-support")]
+
+use multisig_wallet::client::find_wallet_auth_address;
+use multisig_wallet::state::{Proposal, ProposalType, ROLE_OPTIMISTIC, ROLE_PROPOSE, ROLE_VOTE};
+use multisig_wallet::test_support::{WalletFixture, DEFAULT_PROPOSAL_LIFETIME};
+use solana_program::instruction::AccountMeta;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn rejects_roles_with_unknown_bits() {
+    let mut scenario = WalletFixture::new(1, 2).with_owners(2).start().await;
+    let user = scenario.owners[1].pubkey();
+    assert!(scenario
+        .propose(
+            0,
+            ProposalType::SetRoles {
+                user,
+                roles: 1 << 4
+            }
+        )
+        .await
+        .is_err());
+    // the optimistic role sits outside ROLE_ALL but can still be granted
+    scenario
+        .propose(
+            0,
+            ProposalType::SetRoles {
+                user,
+                roles: ROLE_PROPOSE | ROLE_OPTIMISTIC,
+            },
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn proposer_without_execute_role_only_discards_own_proposal() {
+    let mut scenario = WalletFixture::new(1, 2).with_owners(2).start().await;
+    let user = scenario.owners[1].pubkey();
+    let wallet_auth = find_wallet_auth_address(
+        &scenario.program_id,
+        &scenario.wallet_config.pubkey(),
+        &user,
+    )
+    .0;
+    let proposal = scenario
+        .propose(
+            0,
+            ProposalType::SetRoles {
+                user,
+                roles: ROLE_PROPOSE | ROLE_VOTE,
+            },
+        )
+        .await
+        .unwrap();
+    scenario
+        .close(0, &proposal, vec![AccountMeta::new(wallet_auth, false)])
+        .await
+        .unwrap();
+
+    // the proposer's own vote approves both proposals
+    let own = scenario
+        .propose(1, ProposalType::ChangeProposalLifetime { duration: 600 })
+        .await
+        .unwrap();
+    let other = scenario
+        .propose(0, ProposalType::ChangeProposalLifetime { duration: 900 })
+        .await
+        .unwrap();
+    assert!(scenario.close(1, &other, vec![]).await.is_err());
+    assert!(scenario.close(1, &own, vec![]).await.is_err());
+    assert_ne!(scenario.wallet().await.proposal_lifetime, 600);
+
+    scenario
+        .advance_clock(DEFAULT_PROPOSAL_LIFETIME + 1)
+        .await
+        .unwrap();
+    scenario.close(1, &own, vec![]).await.unwrap();
+    assert!(scenario.account::<Proposal>(&own).await.is_none());
+    assert_ne!(scenario.wallet().await.proposal_lifetime, 600);
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "test-support")]
+
+use multisig_wallet::client::find_vault_authority_address;
+use multisig_wallet::state::ProposalType;
+use multisig_wallet::test_support::{WalletFixture, DEFAULT_PROPOSAL_LIFETIME};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account as TokenAccount;
+
+#[tokio::test]
+async fn creates_wallet_with_owners() {
+    let mut scenario = WalletFixture::new(2, 3).with_owners(3).start().await;
+    let wallet = scenario.wallet().await;
+    assert_eq!(wallet.owners, 3);
+    assert_eq!(wallet.proposal_count, 0);
+}
+
+#[tokio::test]
+async fn records_votes_of_each_owner() {
+    let mut scenario = WalletFixture::new(2, 3).with_owners(3).start().await;
+    let proposal = scenario
+        .propose(0, ProposalType::ChangeProposalLifetime { duration: 600 })
+        .await
+        .unwrap();
+    scenario.vote(1, &proposal).await.unwrap();
+    let votes = scenario.votes(&proposal).await.unwrap();
+    assert_eq!(votes.votes, 2);
+    assert_eq!(scenario.wallet().await.proposal_count, 1);
+}
+
+#[tokio::test]
+async fn rejects_second_vote_of_an_owner() {
+    let mut scenario = WalletFixture::new(2, 3).with_owners(3).start().await;
+    let proposal = scenario
+        .propose(0, ProposalType::ChangeProposalLifetime { duration: 600 })
+        .await
+        .unwrap();
+    scenario.vote(1, &proposal).await.unwrap();
+    assert!(scenario.vote(1, &proposal).await.is_err());
+}
+
+#[tokio::test]
+async fn rejects_votes_after_expiry() {
+    let mut scenario = WalletFixture::new(2, 3).with_owners(3).start().await;
+    let proposal = scenario
+        .propose(0, ProposalType::ChangeProposalLifetime { duration: 600 })
+        .await
+        .unwrap();
+    scenario
+        .advance_clock(DEFAULT_PROPOSAL_LIFETIME + 1)
+        .await
+        .unwrap();
+    assert!(scenario.vote(1, &proposal).await.is_err());
+}
+
+#[tokio::test]
+async fn funds_vault_with_fixture_tokens() {
+    let mint = Pubkey::new_unique();
+    let mut scenario = WalletFixture::new(1, 2)
+        .with_owners(2)
+        .with_token(mint, 1_000)
+        .start()
+        .await;
+    let authority =
+        find_vault_authority_address(&scenario.program_id, &scenario.wallet_config.pubkey(), 0).0;
+    let vault_account = scenario
+        .context
+        .banks_client
+        .get_account(get_associated_token_address(&authority, &mint))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        TokenAccount::unpack(&vault_account.data).unwrap().amount,
+        1_000
+    );
+}
@@ -0,0 +1,150 @@
+#![cfg(feature = "test-support")]
+
+use borsh::BorshSerialize;
+use multisig_wallet::client::{
+    find_vault_address, find_vault_authority_address, split_payment_accounts,
+};
+use multisig_wallet::state::{Payment, Proposal, ProposalType, Vault, DISCRIMINATOR_LEN};
+use multisig_wallet::test_support::WalletFixture;
+use solana_program::{
+    instruction::AccountMeta, native_token::LAMPORTS_PER_SOL, program_option::COption,
+    program_pack::Pack, pubkey::Pubkey, system_program,
+};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, AccountState};
+
+fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount::pack(
+        TokenAccount {
+            mint,
+            owner,
+            amount,
+            delegate: COption::None,
+            state: AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        },
+        &mut data,
+    )
+    .unwrap();
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: spl_token::ID,
+        ..Account::default()
+    }
+}
+
+#[tokio::test]
+async fn vault_transfer_keeps_threshold_it_was_proposed_under() {
+    let program_id = Pubkey::new_unique();
+    let wallet_config = Keypair::new();
+    let vault_authority = find_vault_authority_address(&program_id, &wallet_config.pubkey(), 1).0;
+    let vault = find_vault_address(&program_id, &wallet_config.pubkey(), 1).0;
+    let mint = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+    let mut scenario = WalletFixture::new(1, 3)
+        .with_owners(3)
+        .with_program_id(program_id)
+        .with_wallet_config(wallet_config.insecure_clone())
+        .with_token(mint, 100)
+        .with_account(
+            get_associated_token_address(&vault_authority, &mint),
+            token_account(mint, vault_authority, 100),
+        )
+        .with_account(destination, token_account(mint, Pubkey::new_unique(), 0))
+        .start()
+        .await;
+    // a vault every owner has to approve transfers out of
+    let create = scenario
+        .propose(
+            0,
+            ProposalType::CreateVault {
+                index: 1,
+                m: 3,
+                n: 3,
+            },
+        )
+        .await
+        .unwrap();
+    let payer = scenario.owners[0].pubkey();
+    scenario
+        .close(
+            0,
+            &create,
+            vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+        )
+        .await
+        .unwrap();
+
+    let proposal = scenario
+        .propose(
+            0,
+            ProposalType::SplitPayment {
+                token_mint: mint,
+                vault: 1,
+                by_share: false,
+                payments: vec![Payment {
+                    receive_account: destination,
+                    amount: 40,
+                }],
+            },
+        )
+        .await
+        .unwrap();
+    // no instruction changes a vault yet, it is rewritten in place to lower its threshold mid-vote
+    let mut vault_details = scenario.account::<Vault>(&vault).await.unwrap();
+    vault_details.m = 1;
+    let mut vault_account = scenario
+        .context
+        .banks_client
+        .get_account(vault)
+        .await
+        .unwrap()
+        .unwrap();
+    let data = vault_details.try_to_vec().unwrap();
+    vault_account.data[DISCRIMINATOR_LEN..DISCRIMINATOR_LEN + data.len()].copy_from_slice(&data);
+    scenario
+        .context
+        .set_account(&vault, &AccountSharedData::from(vault_account));
+
+    let accounts = split_payment_accounts(
+        &program_id,
+        &wallet_config.pubkey(),
+        &mint,
+        1,
+        &[destination],
+    );
+    assert!(scenario
+        .close(0, &proposal, accounts.clone())
+        .await
+        .is_err());
+    assert!(scenario.account::<Proposal>(&proposal).await.is_some());
+
+    scenario.vote(1, &proposal).await.unwrap();
+    scenario.vote(2, &proposal).await.unwrap();
+    scenario.close(0, &proposal, accounts).await.unwrap();
+    let destination_account = scenario
+        .context
+        .banks_client
+        .get_account(destination)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        TokenAccount::unpack(&destination_account.data)
+            .unwrap()
+            .amount,
+        40
+    );
+}